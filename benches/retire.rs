@@ -0,0 +1,39 @@
+#![feature(test)]
+
+extern crate test;
+
+use anchorage::{
+    domain::global::GlobalDomain,
+    hazbox::HazBox,
+    retire::RetireBatch,
+};
+use test::Bencher;
+
+const BURST: usize = 1000;
+
+fn boxes() -> Vec<HazBox<'static, Vec<usize>, GlobalDomain>> {
+    (0..BURST).map(|i| HazBox::new(vec![i])).collect()
+}
+
+#[bench]
+fn retire_burst_individually(b: &mut Bencher) {
+    let boxes = boxes();
+
+    b.iter(|| {
+        for (i, hazbox) in boxes.iter().enumerate() {
+            hazbox.set(vec![i]);
+        }
+    });
+}
+
+#[bench]
+fn retire_burst_batched(b: &mut Bencher) {
+    let boxes = boxes();
+
+    b.iter(|| {
+        let mut batch = RetireBatch::with_capacity_in(BURST, GlobalDomain);
+        for (i, hazbox) in boxes.iter().enumerate() {
+            batch.push(hazbox.swap(vec![i]));
+        }
+    });
+}
@@ -0,0 +1,141 @@
+use std::{
+    fmt,
+    ops::{
+        Deref,
+        DerefMut,
+    },
+};
+
+#[cfg(feature = "explicit-hazard")]
+use crate::Hazard;
+
+/// Marker types whose alignment [`Aligned`] gives to the values it wraps.
+pub trait Alignment: Copy + Send + Sync + 'static {}
+
+macro_rules! alignments {
+    ($($name:ident = $align:literal),* $(,)?) => {
+        $(
+            /// Alignment marker for [`Aligned`], named after its alignment in bytes.
+            #[repr(align($align))]
+            #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name;
+
+            impl Alignment for $name {}
+        )*
+    };
+}
+
+alignments!(
+    Align8 = 8,
+    Align16 = 16,
+    Align32 = 32,
+    Align64 = 64,
+    Align128 = 128,
+    Align256 = 256,
+    Align512 = 512,
+    Align4096 = 4096,
+);
+
+/// A value aligned to at least the alignment of `A`, e.g. to isolate it in its own cache line or
+/// for DMA.
+///
+/// The alignment is part of the type, thus a `HazBox<Aligned<T, A>>` allocates its values with
+/// it, and retiring them deallocates them with the same layout, without the domain having to keep
+/// track of it. The extra alignment also leaves more low bits of the pointer for
+/// [tags][crate::hazbox::HazBox::tag_mask].
+///
+/// ```
+/// # use anchorage::{aligned::CacheAligned, anchor::Anchor, hazbox::HazBox};
+/// #[derive(Default)]
+/// struct Counters {
+///     hits: u64,
+///     misses: u64,
+/// }
+/// # #[cfg(feature = "explicit-hazard")]
+/// # unsafe impl<'dom> anchorage::Hazard<'dom> for Counters {}
+///
+/// let counters = HazBox::new(CacheAligned::new(Counters::default()));
+///
+/// let mut anchor = Anchor::new();
+/// let hits = anchor.moor(&counters).hits;
+/// # assert_eq!(hits, 0);
+/// ```
+///
+#[repr(C)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Aligned<T, A>
+where
+    A: Alignment,
+{
+    _align: [A; 0],
+    value: T,
+}
+
+/// A value aligned to the usual size of a cache line.
+pub type CacheAligned<T> = Aligned<T, Align64>;
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Dropping the wrapper only drops the value.
+unsafe impl<'dom, T, A> Hazard<'dom> for Aligned<T, A>
+where
+    T: Hazard<'dom>,
+    A: Alignment,
+{
+}
+
+impl<T, A> Aligned<T, A>
+where
+    A: Alignment,
+{
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self { _align: [], value }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, A> From<T> for Aligned<T, A>
+where
+    A: Alignment,
+{
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T, A> Deref for Aligned<T, A>
+where
+    A: Alignment,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, A> DerefMut for Aligned<T, A>
+where
+    A: Alignment,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T, A> fmt::Debug for Aligned<T, A>
+where
+    T: fmt::Debug,
+    A: Alignment,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.value, f)
+    }
+}
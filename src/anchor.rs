@@ -1,12 +1,51 @@
-use std::sync::atomic::Ordering;
+#[cfg(feature = "stats")]
+use std::time::{
+    Duration,
+    Instant,
+};
+use std::{
+    alloc::Global,
+    error::Error,
+    fmt,
+    mem::{
+        self,
+        ManuallyDrop,
+    },
+    ops::{
+        Deref,
+        Index,
+        IndexMut,
+    },
+    ptr,
+    sync::{
+        atomic::{
+            AtomicPtr,
+            Ordering,
+        },
+        Arc,
+    },
+};
 
 use crate::{
+    backoff::{
+        Backoff,
+        DefaultBackoff,
+    },
     domain::{
         global::GlobalDomain,
+        scoped::ScopedDomainRef,
         Domain,
+        DomainOwner,
     },
-    hazbox::HazBox,
+    hazbox::{
+        self,
+        HazBox,
+    },
+    hazdyn::HazDyn,
+    hazoption::HazOption,
     hazptr::HazPtr,
+    hazslice::HazSlice,
+    hazstatic::HazStatic,
     Hazard,
 };
 
@@ -16,16 +55,34 @@ where
 {
     ptr: &'dom HazPtr,
     domain: D,
+    #[cfg(feature = "stats")]
+    stats: AnchorStats,
 }
 
+/// An [`Anchor`] in the [`GlobalDomain`].
+pub type GlobalAnchor = Anchor<'static, GlobalDomain>;
+
+/// An [`Anchor`] in a [`ScopedDomain`][crate::domain::scoped::ScopedDomain].
+pub type ScopedAnchor<'dom, A = Global> = Anchor<'dom, ScopedDomainRef<'dom, A>>;
+
 impl Anchor<'static, GlobalDomain> {
     #[inline]
     pub fn new() -> Self {
         // Safety: The global domain implementation is guaranteed to always return a HazPtr.
-        Self {
-            ptr: unsafe { GlobalDomain.acquire().unwrap_unchecked() },
-            domain: GlobalDomain,
-        }
+        let ptr = unsafe { GlobalDomain.acquire().unwrap_unchecked() };
+        Self::acquired(ptr, GlobalDomain)
+    }
+
+    /// Same as [`Anchor::new`], but never allocates, returning [None] if every
+    /// [`HazPtr`] of the [`GlobalDomain`] is in use.
+    ///
+    /// Meant for real time readers, along with [reserving][GlobalDomain::reserve_hazptrs] enough
+    /// [`HazPtrs`][HazPtr] up front.
+    ///
+    #[inline]
+    pub fn try_new_existing() -> Option<Self> {
+        let ptr = GlobalDomain.try_acquire_existing()?;
+        Some(Self::acquired(ptr, GlobalDomain))
     }
 }
 
@@ -36,16 +93,49 @@ impl Default for Anchor<'static, GlobalDomain> {
     }
 }
 
+/// Loop shared by the moor methods: calls `attempt` with the `expected` pointers, and while they
+/// were replaced, backs off and calls it again with the pointers found instead, returning the
+/// ones it eventually validates.
+#[inline]
+fn retry_until_validated<P, B, F>(mut expected: P, mut backoff: B, mut attempt: F) -> P
+where
+    B: Backoff,
+    F: FnMut(P) -> Result<P, P>,
+{
+    for retries in 1.. {
+        match attempt(expected) {
+            Ok(actual) => return actual,
+            Err(actual) => {
+                backoff.snooze(retries);
+                expected = actual;
+            }
+        }
+    }
+    unreachable!("Retried more than usize::MAX times")
+}
+
 impl<'dom, D> Anchor<'dom, D>
 where
     D: Domain<'dom>,
 {
     #[inline]
     pub fn try_new_in(domain: D) -> Option<Self> {
-        Some(Self {
-            ptr: domain.acquire()?,
+        let ptr = domain.acquire()?;
+        Some(Self::acquired(ptr, domain))
+    }
+
+    /// Wraps a [`HazPtr`] just acquired from `domain`.
+    #[inline]
+    pub(crate) fn acquired(ptr: &'dom HazPtr, domain: D) -> Self {
+        #[cfg(feature = "owner-tags")]
+        ptr.tag_owner();
+
+        Self {
+            ptr,
             domain,
-        })
+            #[cfg(feature = "stats")]
+            stats: AnchorStats::default(),
+        }
     }
 
     #[inline]
@@ -58,57 +148,451 @@ where
         self.domain
     }
 
+    #[inline]
+    pub(crate) fn hazptr(&self) -> &'dom HazPtr {
+        self.ptr
+    }
+
+    #[inline]
     pub fn moor<'r, T>(&'r mut self, src: &'r HazBox<'dom, T, D>) -> &'r T
     where
         T: Hazard<'dom>,
+    {
+        self.moor_backoff(src, DefaultBackoff::default())
+    }
+
+    /// Same as [`Anchor::moor`], but waits according to `backoff` whenever the value was
+    /// replaced while being validated, instead of the [`DefaultBackoff`].
+    pub fn moor_backoff<'r, T, B>(&'r mut self, src: &'r HazBox<'dom, T, D>, backoff: B) -> &'r T
+    where
+        T: Hazard<'dom>,
+        B: Backoff,
     {
         assert!(self.domain == src.domain);
 
-        let mut ptr = src.ptr.load(Ordering::Relaxed);
-        let mut this = self;
+        let ptr = self.protect_current(&src.ptr, |ptr| hazbox::untagged(ptr).cast(), backoff);
+        // Safety: Same as in try_moor.
+        unsafe { &*hazbox::untagged(ptr) }
+    }
 
+    /// Same as [`Anchor::moor`], but yields to the executor whenever the value was replaced while
+    /// being validated, instead of spinning, so that readers running as tasks don't starve the
+    /// other tasks of their worker thread. Nothing is protected while it yields.
+    #[cfg(feature = "async")]
+    pub async fn moor_async<'r, T>(&'r mut self, src: &'r HazBox<'dom, T, D>) -> &'r T
+    where
+        T: Hazard<'dom>,
+    {
         loop {
-            match this.try_moor(src, ptr) {
-                Ok(res) => return res,
-                Err((next_this, next_ptr)) => {
-                    this = next_this;
-                    ptr = next_ptr
-                }
+            // The pointer is loaded again after yielding, rather than held across it, which would
+            // make the future neither Send nor any more likely to still be current.
+            if let Ok(ptr) = self.try_protect(src, src.ptr.load(Ordering::Relaxed)) {
+                // Safety: Same as in try_moor.
+                return unsafe { &*ptr };
+            }
+            crate::backoff::yield_now().await;
+        }
+    }
+
+    /// Calls `with` with the value moored from `src`, resetting the anchor as soon as it returns,
+    /// or unwinds, so that the value is protected no longer than it is used.
+    ///
+    /// ```
+    /// # use anchorage::{anchor::Anchor, hazbox::HazBox};
+    /// # struct Config { port: u16 }
+    /// # #[cfg(feature = "explicit-hazard")]
+    /// # unsafe impl<'dom> anchorage::Hazard<'dom> for Config {}
+    /// # let config = HazBox::new(Config { port: 8080 });
+    /// # let mut anchor = Anchor::new();
+    /// let port = anchor.moor_with(&config, |config| config.port);
+    /// # assert_eq!(port, 8080);
+    /// ```
+    ///
+    #[inline]
+    pub fn moor_with<T, F, R>(&mut self, src: &HazBox<'dom, T, D>, with: F) -> R
+    where
+        T: Hazard<'dom>,
+        F: FnOnce(&T) -> R,
+    {
+        // The guard resets the anchor once dropped, even if `with` panics.
+        with(&self.protect(src))
+    }
+
+    /// Same as [`Anchor::moor`], but gives up once the value was replaced while being validated
+    /// `max_retries` times in a row, so that readers can fall back to a slower path instead of
+    /// spinning for as long as writers keep replacing it.
+    pub fn moor_bounded<'r, T>(
+        &'r mut self,
+        src: &'r HazBox<'dom, T, D>,
+        max_retries: usize,
+    ) -> Result<&'r T, MoorTimeout>
+    where
+        T: Hazard<'dom>,
+    {
+        let mut attempt = self.try_protect(src, src.ptr.load(Ordering::Relaxed));
+        for _ in 0..max_retries {
+            match attempt {
+                Ok(_) => break,
+                Err(err) => attempt = self.try_protect(src, err.actual as *mut T),
             }
         }
+        // Safety: Same as in try_moor.
+        attempt.map(|ptr| unsafe { &*ptr }).map_err(|_| MoorTimeout)
+    }
+
+    /// Same as [`Anchor::moor`], but also returns the [tag] of the value.
+    ///
+    /// [tag]: HazBox::tag
+    ///
+    pub fn moor_tagged<'r, T>(&'r mut self, src: &'r HazBox<'dom, T, D>) -> (&'r T, usize)
+    where
+        T: Hazard<'dom>,
+    {
+        assert!(self.domain == src.domain);
+
+        let ptr = self.protect_current(
+            &src.ptr,
+            |ptr| hazbox::untagged(ptr).cast(),
+            DefaultBackoff::default(),
+        );
+        // Safety: Same as in try_moor.
+        (unsafe { &*hazbox::untagged(ptr) }, hazbox::tag_of(ptr))
     }
 
+    /// Protects the value of `src` if it still is `expected`, returning a [`MoorError`] with the
+    /// current value otherwise, so that the attempt can be retried with the
+    /// [value found instead][MoorError::actual].
+    ///
+    /// ```
+    /// # use std::sync::atomic::Ordering;
+    /// # use anchorage::{anchor::{Anchor, MoorError}, hazbox::HazBox};
+    /// # fn main() -> Result<(), MoorError> {
+    /// # let src = HazBox::new(1);
+    /// # let mut anchor = Anchor::new();
+    /// let expected = src.as_ptr(Ordering::Relaxed);
+    /// let value = match anchor.try_moor(&src, expected) {
+    ///     Ok(value) => *value,
+    ///     Err(err) => *anchor.try_moor(&src, err.actual() as *mut _)?,
+    /// };
+    /// # assert_eq!(value, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    #[inline]
     pub fn try_moor<'r, T>(
         &'r mut self,
         src: &'r HazBox<'dom, T, D>,
         expected: *mut T,
-    ) -> Result<&'r T, (&'r mut Self, *mut T)>
+    ) -> Result<&'r T, MoorError>
+    where
+        T: Hazard<'dom>,
+    {
+        // Safety:
+        //  1. Target of the pointer will not be deallocated for the returned lifetime since
+        //     our hazptr is active and pointing at it.
+        //  2. Pointer address is a valid reference and not null since it was created from a HazBox.
+        self.try_protect(src, expected).map(|ptr| unsafe { &*ptr })
+    }
+
+    /// Protects the value of `src` if it still is `expected`, returning its untagged pointer.
+    ///
+    /// Unlike [`Anchor::try_moor`], nothing stays borrowed on failure, thus callers can retry in
+    /// a loop.
+    fn try_protect<T>(
+        &mut self,
+        src: &HazBox<'dom, T, D>,
+        expected: *mut T,
+    ) -> Result<*mut T, MoorError>
     where
         T: Hazard<'dom>,
     {
         assert!(self.domain == src.domain);
 
-        self.ptr.protect(expected.cast());
+        // Retired pointers are never tagged, thus only the address is protected.
+        match self.try_protect_ptr(&src.ptr, expected, hazbox::untagged(expected).cast()) {
+            Ok(actual) => Ok(hazbox::untagged(actual)),
+            Err(actual) => Err(MoorError {
+                expected: expected as usize,
+                actual: actual as usize,
+            }),
+        }
+    }
 
-        crate::asymmetric_fence::light();
+    /// Protects `protected` on behalf of the `expected` pointer loaded from `src`, returning the
+    /// pointer if `src` still holds it, or resetting the anchor and returning the one `src` holds
+    /// instead. A null `protected` pointer needs no protection, thus only resets the anchor.
+    ///
+    /// Every moor method of the anchor is made of attempts of this, retried by
+    /// [`protect_current`][Anchor::protect_current] or by the method itself.
+    fn try_protect_ptr<P>(
+        &mut self,
+        src: &AtomicPtr<P>,
+        expected: *mut P,
+        protected: *mut u8,
+    ) -> Result<*mut P, *mut P> {
+        if protected.is_null() {
+            self.reset();
+            return Ok(expected);
+        }
 
-        let actual = src.ptr.load(Ordering::Acquire);
+        // An anchor only keeps protecting pointers it validated, thus re-mooring the value it
+        // already protects needs no new store nor fence, just checking it is still current.
+        #[cfg(feature = "stats")]
+        let started = Instant::now();
+        let reused = self.ptr.ptr() == protected;
+        if !reused {
+            self.ptr.protect(protected);
 
+            crate::asymmetric_fence::light();
+        }
+
+        let actual = src.load(Ordering::Acquire);
         if expected == actual {
-            // Safety:
-            //  1. Target of actual will not be deallocated for the returned lifetime since
-            //     our hazptr is active and pointing at it.
-            //  2. Pointer address is a valid reference and not null since it was created from a HazBox.
-            Ok(unsafe { &*actual })
+            #[cfg(feature = "stats")]
+            self.stats.record_moor(started, reused);
+            Ok(actual)
         } else {
             self.reset();
-            Err((self, actual))
+            #[cfg(feature = "stats")]
+            self.stats.record_retry(started);
+            Err(actual)
+        }
+    }
+
+    /// Protects the current pointer of `src`, mapped by `protected` to the address to protect,
+    /// retrying with [`try_protect_ptr`][Anchor::try_protect_ptr] until it is validated, and
+    /// returns it.
+    #[inline]
+    fn protect_current<P, F, B>(&mut self, src: &AtomicPtr<P>, protected: F, backoff: B) -> *mut P
+    where
+        F: Fn(*mut P) -> *mut u8,
+        B: Backoff,
+    {
+        retry_until_validated(src.load(Ordering::Relaxed), backoff, |expected| {
+            self.try_protect_ptr(src, expected, protected(expected))
+        })
+    }
+
+    /// Same as [`Anchor::moor`], but for a [`HazOption`], protecting nothing while it is empty.
+    pub fn moor_opt<'r, T>(&'r mut self, src: &'r HazOption<'dom, T, D>) -> Option<&'r T>
+    where
+        T: Hazard<'dom>,
+    {
+        assert!(self.domain == src.domain);
+
+        self.moor_nullable(&src.ptr)
+    }
+
+    /// Same as [`Anchor::moor_opt`], but for the slot at `index` of a [`HazSlice`].
+    ///
+    /// # Panics
+    ///
+    /// * If `index` is out of bounds.
+    ///
+    pub fn moor_slot<'r, T, const N: usize>(
+        &'r mut self,
+        src: &'r HazSlice<'dom, T, D, N>,
+        index: usize,
+    ) -> Option<&'r T>
+    where
+        T: Hazard<'dom>,
+    {
+        assert!(self.domain == src.domain);
+
+        self.moor_nullable(&src.slots[index])
+    }
+
+    /// Same as [`Anchor::moor_opt`], but for any [`AtomicPtr`], so that structures other than the
+    /// ones of this crate can protect their own nodes, as long as they retire them to the domain.
+    ///
+    /// ```
+    /// # use std::sync::atomic::AtomicPtr;
+    /// # use anchorage::anchor::Anchor;
+    /// struct Node {
+    ///     value: u64,
+    ///     next: AtomicPtr<Node>,
+    /// }
+    /// # #[cfg(feature = "explicit-hazard")]
+    /// # unsafe impl<'dom> anchorage::Hazard<'dom> for Node {}
+    ///
+    /// struct Stack {
+    ///     top: AtomicPtr<Node>,
+    /// }
+    /// # let stack = Stack { top: AtomicPtr::new(std::ptr::null_mut()) };
+    ///
+    /// let mut anchor = Anchor::new();
+    /// // Safety: Nodes are only unlinked from the stack by pop, which retires them.
+    /// let top = unsafe { anchor.protect_raw(&stack.top) };
+    /// # assert!(top.map(|node| node.value).is_none());
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// * Every value `src` points to must stay allocated until it is unlinked from `src` and then
+    /// [retired] to the domain of this anchor, or until `src` is no longer shared.
+    ///
+    /// [retired]: Domain::retire
+    ///
+    #[inline]
+    pub unsafe fn protect_raw<'r, T>(&'r mut self, src: &'r AtomicPtr<T>) -> Option<&'r T>
+    where
+        T: Hazard<'dom>,
+    {
+        self.moor_nullable(src)
+    }
+
+    /// Protects the value `src` points to, if any, which must be owned by a structure of the same
+    /// domain that only ever retires its values to it.
+    pub(crate) fn moor_nullable<'r, T>(&'r mut self, src: &'r AtomicPtr<T>) -> Option<&'r T>
+    where
+        T: Hazard<'dom>,
+    {
+        let ptr = self.protect_current(src, <*mut T>::cast, DefaultBackoff::default());
+        // Safety: Same as in try_moor, null pointers aside.
+        unsafe { ptr.as_ref() }
+    }
+
+    /// Same as [`Anchor::moor`], but for a [`HazStatic`].
+    #[inline]
+    pub fn moor_static<'r, T>(&'r mut self, src: &'r HazStatic<'dom, T, D>) -> &'r T
+    where
+        T: Hazard<'dom>,
+    {
+        self.moor(&src.inner)
+    }
+
+    /// Same as [`Anchor::moor`], but for a [`HazDyn`].
+    pub fn moor_dyn<'r, U>(&'r mut self, src: &'r HazDyn<'dom, U, D>) -> &'r U
+    where
+        U: ?Sized + Send + Sync + 'dom,
+    {
+        assert!(self.domain == src.domain);
+
+        let ptr = self.protect_current(&src.ptr, |ptr| ptr.cast(), DefaultBackoff::default());
+        // Safety: Same as in try_moor, and the header of a node always points at its value.
+        unsafe { (*ptr).value.as_ref() }
+    }
+
+    /// Same as [`Anchor::moor`], but returns a [`Protected`] guard that stops protecting the
+    /// value once dropped, instead of only once the anchor is reused.
+    #[inline]
+    pub fn protect<'r, T>(&'r mut self, src: &'r HazBox<'dom, T, D>) -> Protected<'r, 'dom, T, D>
+    where
+        T: Hazard<'dom>,
+    {
+        let value = self.moor(src) as *const T;
+        Protected {
+            anchor: self,
+            value,
         }
     }
 
+    /// Same as [`Anchor::protect`], but the returned [`OwnedGuard`] owns the anchor, thus it can
+    /// be stored or returned without borrowing it.
+    #[inline]
+    pub fn into_guard<'b, T>(self, src: &'b HazBox<'dom, T, D>) -> OwnedGuard<'b, 'dom, T, D>
+    where
+        T: Hazard<'dom>,
+    {
+        let mut guard = OwnedGuard {
+            value: ptr::null(),
+            anchor: self,
+            src,
+        };
+        guard.refresh();
+        guard
+    }
+
+    /// Same as [`Anchor::into_guard`], but the returned [`SendGuard`] holds `src` by any pointer
+    /// to it, e.g. an [`Arc`][std::sync::Arc], and can be sent to other threads.
+    #[inline]
+    pub fn into_send_guard<T, S>(self, src: S) -> SendGuard<'dom, T, D, S>
+    where
+        T: Hazard<'dom>,
+        S: Deref<Target = HazBox<'dom, T, D>>,
+    {
+        let mut guard = SendGuard {
+            value: ptr::null(),
+            anchor: self,
+            src,
+        };
+        guard.refresh();
+        guard
+    }
+
     pub fn reset(&self) {
         self.ptr.reset();
     }
+
+    /// Returns the statistics of the values moored through this anchor since it was acquired or
+    /// its statistics were [cleared][Anchor::clear_stats].
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> AnchorStats {
+        self.stats
+    }
+
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn clear_stats(&mut self) {
+        self.stats = AnchorStats::default();
+    }
+
+    /// Moves the protection of this anchor to `to`, which stops protecting what it did, leaving
+    /// this anchor protecting nothing, e.g. to keep recycling a pair of anchors when traversing a
+    /// list hand over hand.
+    ///
+    /// The [`HazPtrs`][HazPtr] are swapped rather than the protected address copied, thus the value
+    /// is never left unprotected in between, even to a reclamation scanning them meanwhile.
+    ///
+    /// ```
+    /// # use anchorage::{anchor::Anchor, domain::global::GlobalDomain, hazbox::HazBox};
+    /// struct Node {
+    ///     key: u64,
+    ///     next: Option<HazBox<'static, Node, GlobalDomain>>,
+    /// }
+    /// # #[cfg(feature = "explicit-hazard")]
+    /// # unsafe impl<'dom> anchorage::Hazard<'dom> for Node {}
+    ///
+    /// struct List {
+    ///     head: HazBox<'static, Node, GlobalDomain>,
+    /// }
+    /// # let last = Node { key: 2, next: None };
+    /// # let head = Node { key: 1, next: Some(HazBox::new(last)) };
+    /// # let list = List { head: HazBox::new(head) };
+    ///
+    /// let (mut curr, mut next) = (Anchor::new(), Anchor::new());
+    /// let mut node = curr.moor(&list.head) as *const Node;
+    /// // Safety: curr keeps protecting the node, and with it the box of the next one.
+    /// while let Some(link) = unsafe { &*node }.next.as_ref() {
+    ///     node = next.moor(link) as *const Node;
+    ///     next.transfer_protection(&mut curr);
+    /// }
+    /// # assert_eq!(unsafe { &*node }.key, 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// * If the anchors are from different domains.
+    ///
+    #[inline]
+    pub fn transfer_protection(&mut self, to: &mut Self) {
+        assert!(self.domain == to.domain);
+
+        mem::swap(&mut self.ptr, &mut to.ptr);
+        self.reset();
+    }
+
+    /// Stops protecting the value this anchor moored, but keeps its [`HazPtr`] and remembers
+    /// which value it was, so that protection can be [re-established][WeakAnchor::upgrade] later
+    /// without acquiring a [`HazPtr`] from the domain again.
+    #[inline]
+    pub fn downgrade(self) -> WeakAnchor<'dom, D> {
+        let ptr = self.ptr.ptr();
+        self.reset();
+        WeakAnchor { anchor: self, ptr }
+    }
 }
 
 impl<'dom, D> Drop for Anchor<'dom, D>
@@ -117,6 +601,1118 @@ where
 {
     fn drop(&mut self) {
         self.reset();
-        self.ptr.release();
+        #[cfg(feature = "owner-tags")]
+        self.ptr.untag_owner();
+        self.domain.release(self.ptr);
+    }
+}
+
+/// Statistics of the values moored through an [`Anchor`], returned by [`Anchor::stats`].
+///
+/// Many retries relative to the moors mean the values are replaced about as often as they are
+/// read, thus readers keep racing the writers, e.g. a box worth sharding.
+///
+#[cfg(feature = "stats")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AnchorStats {
+    /// Number of values protected.
+    pub moors: u64,
+    /// Number of validations that failed because the value was replaced meanwhile, each followed
+    /// by a retry.
+    pub retries: u64,
+    /// Number of values protected that the anchor already protected, which needed no fence.
+    pub reused: u64,
+    /// Time spent protecting and validating values, backoffs excluded.
+    pub moor_time: Duration,
+}
+
+#[cfg(feature = "stats")]
+impl AnchorStats {
+    #[inline]
+    fn record_moor(&mut self, started: Instant, reused: bool) {
+        self.moors += 1;
+        self.reused += reused as u64;
+        self.moor_time += started.elapsed();
+    }
+
+    #[inline]
+    fn record_retry(&mut self, started: Instant) {
+        self.retries += 1;
+        self.moor_time += started.elapsed();
+    }
+}
+
+/// Error returned by [`Anchor::moor_bounded`] when the value kept being replaced, in which case
+/// the anchor protects nothing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MoorTimeout;
+
+impl fmt::Display for MoorTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("value kept being replaced while mooring it")
+    }
+}
+
+impl Error for MoorTimeout {}
+
+/// Error returned by [`Anchor::try_moor`] when the value was replaced since it was loaded, in which
+/// case the anchor protects nothing.
+///
+/// Only holds the addresses of the values, thus it can be propagated like any other error, while
+/// the anchor stays usable to retry the attempt with.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MoorError {
+    expected: usize,
+    actual: usize,
+}
+
+impl MoorError {
+    /// Address of the value that was expected, tag included.
+    #[inline]
+    pub fn expected(&self) -> usize {
+        self.expected
+    }
+
+    /// Address of the value that was found instead, tag included.
+    #[inline]
+    pub fn actual(&self) -> usize {
+        self.actual
+    }
+}
+
+impl fmt::Display for MoorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {:#x} while mooring, found {:#x}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl Error for MoorError {}
+
+/// A value protected by an [`Anchor`], made by [`Anchor::protect`].
+///
+/// Unlike the reference returned by [`Anchor::moor`], the guard can be stored alongside other
+/// borrows of the same structure, and dropping it resets the anchor right away, so that the
+/// protection ends exactly where the guard does. The anchor is then free to protect the next
+/// value.
+///
+/// ```
+/// # use anchorage::{anchor::Anchor, domain::global::GlobalDomain, hazbox::HazBox};
+/// struct Node {
+///     key: u64,
+/// }
+/// # #[cfg(feature = "explicit-hazard")]
+/// # unsafe impl<'dom> anchorage::Hazard<'dom> for Node {}
+///
+/// struct Deque {
+///     head: HazBox<'static, Node, GlobalDomain>,
+///     tail: HazBox<'static, Node, GlobalDomain>,
+/// }
+/// # let list = Deque { head: HazBox::new(Node { key: 1 }), tail: HazBox::new(Node { key: 2 }) };
+///
+/// let mut anchor = Anchor::new();
+///
+/// let head = anchor.protect(&list.head);
+/// let first = head.key;
+/// drop(head);
+///
+/// let tail = anchor.protect(&list.tail);
+/// # assert_eq!((first, tail.key), (1, 2));
+/// ```
+///
+pub struct Protected<'r, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    anchor: &'r mut Anchor<'dom, D>,
+    value: *const T,
+}
+
+impl<'r, 'dom, T, D> Protected<'r, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.anchor.domain
+    }
+
+    /// Returns the pointer to the value, as validated when it was moored, e.g. to only
+    /// [replace][HazBox::compare_exchange] it if it is still current.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut T {
+        self.value as *mut T
+    }
+
+    /// Narrows the guard to a part of the value, e.g. one of its fields, which stays protected
+    /// until the returned guard is dropped, like [`Ref::map`][std::cell::Ref::map].
+    ///
+    /// ```
+    /// # use anchorage::{anchor::Anchor, hazbox::HazBox};
+    /// struct User {
+    ///     name: String,
+    /// }
+    /// # #[cfg(feature = "explicit-hazard")]
+    /// # unsafe impl<'dom> anchorage::Hazard<'dom> for User {}
+    /// # let user = HazBox::new(User { name: String::from("ferris") });
+    /// # let mut anchor = Anchor::new();
+    ///
+    /// let name = anchor.protect(&user).map(|user| user.name.as_str());
+    /// # assert_eq!(&*name, "ferris");
+    /// ```
+    ///
+    #[inline]
+    pub fn map<U, F>(self, f: F) -> MappedProtected<'r, 'dom, U, D>
+    where
+        U: ?Sized,
+        F: FnOnce(&T) -> &U,
+    {
+        let value = f(&self) as *const U;
+        let this = ManuallyDrop::new(self);
+        MappedProtected {
+            // Safety: The guard is never used nor dropped again, thus the anchor is moved out.
+            anchor: unsafe { ptr::read(&this.anchor) },
+            value,
+        }
+    }
+
+    /// Converts the guard into a plain reference, which keeps the value protected until the
+    /// anchor is reused, like the one returned by [`Anchor::moor`].
+    #[inline]
+    pub fn into_ref(self) -> &'r T {
+        let value = self.value;
+        mem::forget(self);
+        // Safety: The anchor stays borrowed, and thus protecting the value, for the whole 'r.
+        unsafe { &*value }
+    }
+}
+
+impl<'r, 'dom, T, D> Deref for Protected<'r, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Safety: The anchor keeps protecting the value until the guard is dropped.
+        unsafe { &*self.value }
+    }
+}
+
+impl<'r, 'dom, T, D> fmt::Debug for Protected<'r, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<'r, 'dom, T, D> Drop for Protected<'r, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.anchor.reset();
+    }
+}
+
+/// A part of a value protected by an [`Anchor`], made by [`Protected::map`].
+pub struct MappedProtected<'r, 'dom, U, D>
+where
+    D: Domain<'dom>,
+    U: ?Sized,
+{
+    anchor: &'r mut Anchor<'dom, D>,
+    value: *const U,
+}
+
+impl<'r, 'dom, U, D> MappedProtected<'r, 'dom, U, D>
+where
+    D: Domain<'dom>,
+    U: ?Sized,
+{
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.anchor.domain
     }
+
+    /// Same as [`Protected::map`].
+    #[inline]
+    pub fn map<V, F>(self, f: F) -> MappedProtected<'r, 'dom, V, D>
+    where
+        V: ?Sized,
+        F: FnOnce(&U) -> &V,
+    {
+        let value = f(&self) as *const V;
+        let this = ManuallyDrop::new(self);
+        MappedProtected {
+            // Safety: Same as in Protected::map.
+            anchor: unsafe { ptr::read(&this.anchor) },
+            value,
+        }
+    }
+}
+
+impl<'r, 'dom, U, D> Deref for MappedProtected<'r, 'dom, U, D>
+where
+    D: Domain<'dom>,
+    U: ?Sized,
+{
+    type Target = U;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Safety: Points into the value protected by the anchor, which keeps protecting it until
+        // the guard is dropped.
+        unsafe { &*self.value }
+    }
+}
+
+impl<'r, 'dom, U, D> fmt::Debug for MappedProtected<'r, 'dom, U, D>
+where
+    D: Domain<'dom>,
+    U: ?Sized + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<'r, 'dom, U, D> Drop for MappedProtected<'r, 'dom, U, D>
+where
+    D: Domain<'dom>,
+    U: ?Sized,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.anchor.reset();
+    }
+}
+
+/// A value protected by an [`Anchor`] the guard owns, made by [`OwnedGuard::new`] or
+/// [`Anchor::into_guard`].
+///
+/// The guard only borrows the [`HazBox`] it was loaded from, thus it can be kept in a struct or
+/// returned up the stack as a snapshot of the value, which stays alive even if the [`HazBox`] is
+/// swapped meanwhile, until the guard is [refreshed] or dropped.
+///
+/// ```
+/// # use anchorage::{anchor::OwnedGuard, domain::global::GlobalDomain, hazbox::HazBox};
+/// struct Config {
+///     timeout_ms: u64,
+/// }
+/// # #[cfg(feature = "explicit-hazard")]
+/// # unsafe impl<'dom> anchorage::Hazard<'dom> for Config {}
+/// # let config = HazBox::new(Config { timeout_ms: 100 });
+///
+/// struct Request<'c> {
+///     config: OwnedGuard<'c, 'static, Config, GlobalDomain>,
+/// }
+///
+/// let request = Request {
+///     config: OwnedGuard::new(&config),
+/// };
+///
+/// config.set(Config { timeout_ms: 200 });
+/// assert_eq!(request.config.timeout_ms, 100);
+/// ```
+///
+/// [refreshed]: OwnedGuard::refresh
+///
+pub struct OwnedGuard<'b, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    value: *const T,
+    anchor: Anchor<'dom, D>,
+    src: &'b HazBox<'dom, T, D>,
+}
+
+impl<'b, 'dom, T, D> OwnedGuard<'b, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    /// Protects the current value of `src` with a new [`Anchor`].
+    #[inline]
+    pub fn new(src: &'b HazBox<'dom, T, D>) -> Self {
+        Anchor::new_in(src.domain).into_guard(src)
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.anchor.domain
+    }
+
+    /// Returns the pointer to the value, as validated when it was moored, e.g. to only
+    /// [replace][HazBox::compare_exchange] it if it is still current.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut T {
+        self.value as *mut T
+    }
+
+    /// Protects the current value of the [`HazBox`] instead, if it was swapped since.
+    #[inline]
+    pub fn refresh(&mut self) {
+        self.value = self.anchor.moor(self.src);
+    }
+
+    /// Stops protecting the value, returning the anchor to be reused.
+    #[inline]
+    pub fn into_anchor(self) -> Anchor<'dom, D> {
+        self.anchor.reset();
+        self.anchor
+    }
+}
+
+impl<'b, 'dom, T, D> Deref for OwnedGuard<'b, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Safety: The value was moored by our anchor, which keeps protecting it until it's dropped
+        // or moors another value.
+        unsafe { &*self.value }
+    }
+}
+
+impl<'b, 'dom, T, D> fmt::Debug for OwnedGuard<'b, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Same as an [`OwnedGuard`], but holds its [`HazBox`] by any pointer to it, and can be sent to
+/// another thread along with the protection, thus the value never has to be moored again there.
+/// Made by [`SendGuard::new`] or [`Anchor::into_send_guard`].
+///
+/// ```
+/// # use std::{sync::{mpsc, Arc}, thread};
+/// # use anchorage::{anchor::SendGuard, hazbox::HazBox};
+/// # let latest = Arc::new(HazBox::new(vec![0_u8; 16]));
+/// # let (workers, frames) = mpsc::channel();
+/// let frame = SendGuard::new(Arc::clone(&latest));
+/// workers.send(frame)?;
+/// # drop(workers);
+/// # let worker = thread::spawn(move || {
+/// #     for frame in frames {
+/// #         assert_eq!(frame.len(), 16);
+/// #     }
+/// # });
+/// # worker.join().unwrap();
+/// # Ok::<(), mpsc::SendError<_>>(())
+/// ```
+///
+pub struct SendGuard<'dom, T, D, S>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+    S: Deref<Target = HazBox<'dom, T, D>>,
+{
+    value: *const T,
+    /// Before src, so that the value is no longer protected once the box may be dropped.
+    anchor: Anchor<'dom, D>,
+    src: S,
+}
+
+// Safety: The value is only ever shared, and hazards are Sync, thus the guard can be sent along
+// with its anchor and source like a reference would.
+unsafe impl<'dom, T, D, S> Send for SendGuard<'dom, T, D, S>
+where
+    D: Domain<'dom> + Send,
+    T: Hazard<'dom>,
+    S: Deref<Target = HazBox<'dom, T, D>> + Send,
+{
+}
+
+// Safety: Same as for Send.
+unsafe impl<'dom, T, D, S> Sync for SendGuard<'dom, T, D, S>
+where
+    D: Domain<'dom> + Sync,
+    T: Hazard<'dom>,
+    S: Deref<Target = HazBox<'dom, T, D>> + Sync,
+{
+}
+
+impl<'dom, T, D, S> SendGuard<'dom, T, D, S>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+    S: Deref<Target = HazBox<'dom, T, D>>,
+{
+    /// Protects the current value of `src` with a new [`Anchor`].
+    #[inline]
+    pub fn new(src: S) -> Self {
+        Anchor::new_in(src.domain).into_send_guard(src)
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.anchor.domain
+    }
+
+    #[inline]
+    pub fn source(&self) -> &S {
+        &self.src
+    }
+
+    /// Same as [`OwnedGuard::as_ptr`].
+    #[inline]
+    pub fn as_ptr(&self) -> *mut T {
+        self.value as *mut T
+    }
+
+    /// Same as [`OwnedGuard::refresh`].
+    #[inline]
+    pub fn refresh(&mut self) {
+        self.value = self.anchor.moor(&self.src);
+    }
+
+    /// Stops protecting the value, returning the anchor to be reused and the source.
+    #[inline]
+    pub fn into_parts(self) -> (Anchor<'dom, D>, S) {
+        self.anchor.reset();
+        (self.anchor, self.src)
+    }
+}
+
+impl<'dom, T, D, S> Deref for SendGuard<'dom, T, D, S>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+    S: Deref<Target = HazBox<'dom, T, D>>,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Safety: Same as for OwnedGuard.
+        unsafe { &*self.value }
+    }
+}
+
+impl<'dom, T, D, S> fmt::Debug for SendGuard<'dom, T, D, S>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom> + fmt::Debug,
+    S: Deref<Target = HazBox<'dom, T, D>>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// An [`Anchor`] that stopped protecting the value it moored, made by [`Anchor::downgrade`].
+///
+/// Long traversals can downgrade their anchors during phases known not to touch the protected
+/// values, like waiting on IO, so that they don't hold back reclamation meanwhile, then
+/// [upgrade] them to re-protect the same values if they are still current. Either way the
+/// [`HazPtr`] stays reserved, thus upgrading never goes back to the domain.
+///
+/// ```
+/// # use anchorage::{anchor::Anchor, domain::global::GlobalDomain, hazbox::HazBox};
+/// # struct Node {
+/// #     key: u64,
+/// # }
+/// # #[cfg(feature = "explicit-hazard")]
+/// # unsafe impl<'dom> anchorage::Hazard<'dom> for Node {}
+/// # struct List {
+/// #     head: HazBox<'static, Node, GlobalDomain>,
+/// # }
+/// # let list = List { head: HazBox::new(Node { key: 1 }) };
+/// # fn fetch_batch(cursor: u64) -> Vec<u64> {
+/// #     vec![cursor]
+/// # }
+/// let mut anchor = Anchor::new();
+/// let cursor = anchor.moor(&list.head).key;
+///
+/// let weak = anchor.downgrade();
+/// let batch = fetch_batch(cursor);
+///
+/// let mut anchor = match weak.upgrade(&list.head) {
+///     Ok(anchor) => anchor,
+///     // The head was replaced meanwhile, restart from the new one.
+///     Err(weak) => weak.into_anchor(),
+/// };
+/// let head = anchor.moor(&list.head);
+/// # assert_eq!(batch, [head.key]);
+/// ```
+///
+/// [upgrade]: WeakAnchor::upgrade
+///
+pub struct WeakAnchor<'dom, D>
+where
+    D: Domain<'dom>,
+{
+    anchor: Anchor<'dom, D>,
+    ptr: *mut u8,
+}
+
+impl<'dom, D> WeakAnchor<'dom, D>
+where
+    D: Domain<'dom>,
+{
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.anchor.domain
+    }
+
+    /// Re-protects the value the anchor moored before being downgraded, if `src` still holds it.
+    ///
+    /// On success, the returned [`Anchor`] already protects the value, thus [mooring] `src` with
+    /// it again needs no fence. Otherwise the value was replaced meanwhile and may already have
+    /// been reclaimed, and this is returned protecting nothing.
+    ///
+    /// Anchors only protect addresses, thus the [tag] of `src` isn't compared, use
+    /// [`WeakAnchor::upgrade_tagged`] for that.
+    ///
+    /// [mooring]: Anchor::moor
+    /// [tag]: HazBox::tag
+    ///
+    #[inline]
+    pub fn upgrade<T>(self, src: &HazBox<'dom, T, D>) -> Result<Anchor<'dom, D>, Self>
+    where
+        T: Hazard<'dom>,
+    {
+        let expected = self.ptr;
+        self.upgrade_if(src, |actual| hazbox::untagged(actual).cast() == expected)
+    }
+
+    /// Same as [`WeakAnchor::upgrade`], but only succeeds if `src` also still has the tag `tag`,
+    /// like the one returned by [`Anchor::moor_tagged`] before downgrading.
+    ///
+    /// # Panics
+    ///
+    /// * If `tag` doesn't fit in [`HazBox::tag_mask`].
+    ///
+    #[inline]
+    pub fn upgrade_tagged<T>(
+        self,
+        src: &HazBox<'dom, T, D>,
+        tag: usize,
+    ) -> Result<Anchor<'dom, D>, Self>
+    where
+        T: Hazard<'dom>,
+    {
+        let expected = hazbox::tagged(self.ptr.cast::<T>(), tag);
+        self.upgrade_if(src, |actual| actual == expected)
+    }
+
+    /// Re-protects the value, keeping it protected if `is_current` accepts the word `src` holds
+    /// afterwards, tag included.
+    fn upgrade_if<T, F>(
+        self,
+        src: &HazBox<'dom, T, D>,
+        is_current: F,
+    ) -> Result<Anchor<'dom, D>, Self>
+    where
+        T: Hazard<'dom>,
+        F: FnOnce(*mut T) -> bool,
+    {
+        assert!(self.anchor.domain == src.domain);
+
+        if self.ptr.is_null() {
+            return Err(self);
+        }
+        self.anchor.ptr.protect(self.ptr);
+
+        crate::asymmetric_fence::light();
+
+        if is_current(src.ptr.load(Ordering::Acquire)) {
+            Ok(self.anchor)
+        } else {
+            self.anchor.reset();
+            Err(self)
+        }
+    }
+
+    /// Forgets the value the anchor moored, to moor a new one without acquiring a [`HazPtr`].
+    #[inline]
+    pub fn into_anchor(self) -> Anchor<'dom, D> {
+        self.anchor
+    }
+}
+
+/// An [`Anchor`] that co-owns its domain through an [`Arc`], instead of borrowing it, thus it can
+/// be kept as a plain field of long lived objects when using a domain other than the
+/// [`GlobalDomain`], e.g. one per connection of a service.
+///
+/// The domain is kept alive for as long as the anchor is, and values are only moored for as long
+/// as the anchor is borrowed, from [`HazBoxes`][HazBox] using any
+/// [handle][DomainOwner::handle] of it.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use anchorage::{
+/// #     anchor::OwnedAnchor,
+/// #     domain::child::{ChildDomain, ChildDomainRef},
+/// #     hazbox::HazBox,
+/// # };
+/// # type UserId = u64;
+/// struct Session {
+///     user: UserId,
+/// }
+/// # #[cfg(feature = "explicit-hazard")]
+/// # unsafe impl<'dom> anchorage::Hazard<'dom> for Session {}
+///
+/// struct Connection {
+///     sessions: Arc<ChildDomain<'static>>,
+///     anchor: OwnedAnchor<ChildDomain<'static>>,
+/// }
+///
+/// impl Connection {
+///     fn new(sessions: &Arc<ChildDomain<'static>>) -> Self {
+///         Self {
+///             sessions: Arc::clone(sessions),
+///             anchor: OwnedAnchor::new_in(Arc::clone(sessions)),
+///         }
+///     }
+///
+///     fn user<'s>(&mut self, session: &HazBox<'s, Session, ChildDomainRef<'s>>) -> UserId {
+///         self.anchor.moor(session).user
+///     }
+/// }
+///
+/// let sessions = Arc::new(ChildDomain::new());
+/// let mut connection = Connection::new(&sessions);
+/// // Safety: Sessions own their data.
+/// let session = HazBox::new_in(Session { user: 7 }, unsafe { sessions.handle() });
+/// assert_eq!(connection.user(&session), 7);
+/// ```
+///
+pub struct OwnedAnchor<O>
+where
+    O: for<'dom> DomainOwner<'dom> + 'static,
+{
+    /// Before domain, so that the hazptr is released while the domain is still alive.
+    anchor: Anchor<'static, <O as DomainOwner<'static>>::Handle>,
+    domain: Arc<O>,
+}
+
+impl<O> OwnedAnchor<O>
+where
+    O: for<'dom> DomainOwner<'dom> + 'static,
+{
+    #[inline]
+    pub fn try_new_in(domain: Arc<O>) -> Option<Self> {
+        // Safety: The handle never outlives the Arc, since the anchor is dropped before it, and
+        // is only ever handed out for as long as self is borrowed. Anchors never retire values.
+        let handle = unsafe { (*Arc::as_ptr(&domain)).handle() };
+        Some(Self {
+            anchor: Anchor::try_new_in(handle)?,
+            domain,
+        })
+    }
+
+    #[inline]
+    pub fn new_in(domain: Arc<O>) -> Self {
+        Self::try_new_in(domain).expect("Unable to acquire a HazBox Pointer")
+    }
+
+    #[inline]
+    pub fn domain(&self) -> &Arc<O> {
+        &self.domain
+    }
+
+    /// Same as [`Anchor::moor`].
+    #[inline]
+    pub fn moor<'r, 'h, T>(
+        &'r mut self,
+        src: &'r HazBox<'h, T, <O as DomainOwner<'h>>::Handle>,
+    ) -> &'r T
+    where
+        T: Hazard<'h>,
+    {
+        // Safety: The box keeps a handle of the domain for 'h.
+        unsafe { self.anchor() }.moor(src)
+    }
+
+    /// Same as [`Anchor::moor_backoff`].
+    #[inline]
+    pub fn moor_backoff<'r, 'h, T, B>(
+        &'r mut self,
+        src: &'r HazBox<'h, T, <O as DomainOwner<'h>>::Handle>,
+        backoff: B,
+    ) -> &'r T
+    where
+        T: Hazard<'h>,
+        B: Backoff,
+    {
+        // Safety: Same as in moor.
+        unsafe { self.anchor() }.moor_backoff(src, backoff)
+    }
+
+    /// Same as [`Anchor::moor_with`].
+    #[inline]
+    pub fn moor_with<'h, T, F, R>(
+        &mut self,
+        src: &HazBox<'h, T, <O as DomainOwner<'h>>::Handle>,
+        with: F,
+    ) -> R
+    where
+        T: Hazard<'h>,
+        F: FnOnce(&T) -> R,
+    {
+        // Safety: Same as in moor.
+        unsafe { self.anchor() }.moor_with(src, with)
+    }
+
+    #[inline]
+    pub fn reset(&self) {
+        self.anchor.reset();
+    }
+
+    /// Borrows the anchor with a handle living for `'h`, which is never handed out as a mutable
+    /// reference, since the anchor could then be swapped with one of a domain that doesn't outlive
+    /// self.
+    ///
+    /// # Safety
+    ///
+    /// * The domain must be alive for `'h`.
+    ///
+    #[inline]
+    unsafe fn anchor<'h>(&mut self) -> &mut Anchor<'h, <O as DomainOwner<'h>>::Handle> {
+        let anchor = &mut self.anchor as *mut Anchor<'static, <O as DomainOwner<'static>>::Handle>;
+        // Safety: Both handles are of the same type but for their lifetime, and the domain is
+        // alive for 'h, as upheld by the caller.
+        unsafe { &mut *anchor.cast() }
+    }
+}
+
+impl<O> fmt::Debug for OwnedAnchor<O>
+where
+    O: for<'dom> DomainOwner<'dom> + fmt::Debug + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedAnchor")
+            .field("domain", &self.domain)
+            .finish()
+    }
+}
+
+/// A fixed set of `N` [`Anchors`][Anchor] acquired together, used to protect several
+/// [`HazBoxes`][HazBox] from the same domain at once.
+///
+/// The [`HazPtrs`][HazPtr] are [acquired][Domain::acquire_many] in a single pass over the domain,
+/// instead of one per [`Anchor`]. Protecting through [`AnchorSet::moor_all`] issues all the
+/// protecting stores first and a single [light fence] before validating every slot, instead of one
+/// fence per slot. Each [`Anchor`] can also be used on its own, while the others keep protecting
+/// what they moored:
+///
+/// ```
+/// # use std::collections::VecDeque;
+/// # use anchorage::{anchor::AnchorSet, domain::global::GlobalDomain, hazbox::HazBox};
+/// # struct Queue {
+/// #     head: HazBox<'static, VecDeque<u64>, GlobalDomain>,
+/// #     tail: HazBox<'static, VecDeque<u64>, GlobalDomain>,
+/// # }
+/// # let queue = Queue { head: HazBox::new(VecDeque::from([1])), tail: HazBox::new(VecDeque::from([2])) };
+/// let mut anchors = AnchorSet::<_, 2>::new();
+/// let [first, last] = anchors.anchors_mut();
+///
+/// let head = first.moor(&queue.head);
+/// let tail = last.moor(&queue.tail);
+/// # assert_eq!((head[0], tail[0]), (1, 2));
+/// ```
+///
+/// [light fence]: crate::asymmetric_fence::light
+///
+pub struct AnchorSet<'dom, D, const N: usize>
+where
+    D: Domain<'dom>,
+{
+    anchors: [Anchor<'dom, D>; N],
+    domain: D,
+}
+
+impl<const N: usize> AnchorSet<'static, GlobalDomain, N> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(GlobalDomain)
+    }
+}
+
+impl<const N: usize> Default for AnchorSet<'static, GlobalDomain, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'dom, D, const N: usize> AnchorSet<'dom, D, N>
+where
+    D: Domain<'dom>,
+{
+    pub fn try_new_in(domain: D) -> Option<Self> {
+        let anchors = domain
+            .acquire_many::<N>()?
+            .map(|ptr| Anchor::acquired(ptr, domain));
+
+        Some(Self { anchors, domain })
+    }
+
+    #[inline]
+    pub fn new_in(domain: D) -> Self {
+        Self::try_new_in(domain).expect("Unable to acquire a HazBox Pointer")
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    #[inline]
+    pub fn anchors(&self) -> &[Anchor<'dom, D>; N] {
+        &self.anchors
+    }
+
+    /// Returns every anchor of the set, which can be destructured to moor through each of them
+    /// independently.
+    #[inline]
+    pub fn anchors_mut(&mut self) -> &mut [Anchor<'dom, D>; N] {
+        &mut self.anchors
+    }
+
+    /// Same as [`Anchor::moor`], through the anchor at `index`.
+    ///
+    /// # Panics
+    ///
+    /// * If `index` is out of bounds.
+    ///
+    #[inline]
+    pub fn moor<'r, T>(&'r mut self, index: usize, src: &'r HazBox<'dom, T, D>) -> &'r T
+    where
+        T: Hazard<'dom>,
+    {
+        self.anchors[index].moor(src)
+    }
+
+    pub fn moor_all<'r, T>(&'r mut self, srcs: [&'r HazBox<'dom, T, D>; N]) -> [&'r T; N]
+    where
+        T: Hazard<'dom>,
+    {
+        let mut backoff = DefaultBackoff::default();
+        let mut ptrs = srcs.map(|src| src.ptr.load(Ordering::Relaxed));
+        let mut this = self;
+
+        for retries in 1.. {
+            match this.try_moor_all(srcs, ptrs) {
+                Ok(res) => return res,
+                Err((next_this, next_ptrs)) => {
+                    this = next_this;
+                    ptrs = next_ptrs
+                }
+            }
+            backoff.snooze(retries);
+        }
+        unreachable!("Retried more than usize::MAX times")
+    }
+
+    pub fn try_moor_all<'r, T>(
+        &'r mut self,
+        srcs: [&'r HazBox<'dom, T, D>; N],
+        expected: [*mut T; N],
+    ) -> Result<[&'r T; N], (&'r mut Self, [*mut T; N])>
+    where
+        T: Hazard<'dom>,
+    {
+        // Anchors may have been swapped with ones from elsewhere, thus each is checked.
+        assert!(self
+            .anchors
+            .iter()
+            .zip(&srcs)
+            .all(|(anchor, src)| anchor.domain == src.domain));
+
+        self.protect_all(expected.map(|ptr| hazbox::untagged(ptr).cast()));
+
+        let actual = srcs.map(|src| src.ptr.load(Ordering::Acquire));
+
+        if expected == actual {
+            // Safety: Same as in Anchor::try_moor, for every slot.
+            Ok(actual.map(|ptr| unsafe { &*hazbox::untagged(ptr) }))
+        } else {
+            self.reset();
+            Err((self, actual))
+        }
+    }
+
+    /// Same as [`AnchorSet::moor_all`], but for boxes holding different types, given as a tuple
+    /// with one box per anchor, returning a tuple of references that were all validated together.
+    ///
+    /// ```
+    /// # use anchorage::{anchor::AnchorSet, hazbox::HazBox};
+    /// # let config = HazBox::new(String::from("config"));
+    /// # let routes = HazBox::new(vec![String::from("/")]);
+    /// let mut anchors = AnchorSet::<_, 2>::new();
+    /// let (config, routes) = anchors.moor_many((&config, &routes));
+    /// # assert_eq!((config.as_str(), routes.len()), ("config", 1));
+    /// ```
+    ///
+    pub fn moor_many<'r, S>(&'r mut self, srcs: S) -> S::Refs
+    where
+        S: MoorMany<'r, 'dom, D, N>,
+    {
+        // Same as in try_moor_all.
+        assert!(self
+            .anchors
+            .iter()
+            .zip(srcs.domains())
+            .all(|(anchor, domain)| anchor.domain == domain));
+
+        let expected = srcs.load(Ordering::Relaxed);
+        let ptrs = retry_until_validated(expected, DefaultBackoff::default(), |expected| {
+            self.protect_all(S::untagged(expected));
+
+            let actual = srcs.load(Ordering::Acquire);
+            if expected == actual {
+                Ok(actual)
+            } else {
+                self.reset();
+                Err(actual)
+            }
+        });
+        // Safety: Same as in try_moor_all, the anchors stay borrowed for 'r.
+        unsafe { S::refs(ptrs) }
+    }
+
+    /// Protects each of the untagged `ptrs` with the anchor at the same index, with a single fence.
+    fn protect_all(&self, ptrs: [*mut u8; N]) {
+        let mut protected = false;
+        for (anchor, &ptr) in self.anchors.iter().zip(&ptrs) {
+            // Same as in Anchor::try_moor, slots already protecting their pointer are kept.
+            if anchor.ptr.ptr() != ptr {
+                anchor.ptr.protect(ptr);
+                protected = true;
+            }
+        }
+
+        if protected {
+            crate::asymmetric_fence::light();
+        }
+    }
+
+    pub fn reset(&self) {
+        self.anchors.iter().for_each(Anchor::reset);
+    }
+}
+
+impl<'dom, D, const N: usize> Index<usize> for AnchorSet<'dom, D, N>
+where
+    D: Domain<'dom>,
+{
+    type Output = Anchor<'dom, D>;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.anchors[index]
+    }
+}
+
+impl<'dom, D, const N: usize> IndexMut<usize> for AnchorSet<'dom, D, N>
+where
+    D: Domain<'dom>,
+{
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.anchors[index]
+    }
+}
+
+/// Tuples of [`HazBoxes`][HazBox] that can be [moored together][AnchorSet::moor_many] by an
+/// [`AnchorSet`] of `N` anchors.
+///
+/// Implemented for tuples of up to 6 references to [`HazBoxes`][HazBox] of the same domain.
+///
+/// # Safety
+///
+/// * [`MoorMany::load`] must load the pointers of the boxes, in order, and [`MoorMany::refs`]
+/// must only dereference them, with the lifetime of the borrows of the boxes.
+///
+pub unsafe trait MoorMany<'r, 'dom, D, const N: usize>
+where
+    D: Domain<'dom>,
+{
+    /// References to the values of the boxes, in the same order.
+    type Refs;
+
+    fn domains(&self) -> [D; N];
+
+    /// Loads the pointers of the boxes, tags included.
+    fn load(&self, order: Ordering) -> [*mut u8; N];
+
+    /// Strips the tags of pointers returned by [`MoorMany::load`].
+    fn untagged(ptrs: [*mut u8; N]) -> [*mut u8; N];
+
+    /// # Safety
+    ///
+    /// * `ptrs` must have been returned by [`MoorMany::load`], and be protected for `'r`.
+    ///
+    unsafe fn refs(ptrs: [*mut u8; N]) -> Self::Refs;
+}
+
+macro_rules! moor_many {
+    ($($n:literal => ($($t:ident $i:tt),+);)*) => {
+        $(
+            // Safety: Every pointer is loaded from and cast back to the box at the same index.
+            unsafe impl<'r, 'dom, D, $($t),+> MoorMany<'r, 'dom, D, $n>
+                for ($(&'r HazBox<'dom, $t, D>,)+)
+            where
+                D: Domain<'dom>,
+                $($t: Hazard<'dom>,)+
+            {
+                type Refs = ($(&'r $t,)+);
+
+                #[inline]
+                fn domains(&self) -> [D; $n] {
+                    [$(self.$i.domain),+]
+                }
+
+                #[inline]
+                fn load(&self, order: Ordering) -> [*mut u8; $n] {
+                    [$(self.$i.ptr.load(order).cast()),+]
+                }
+
+                #[inline]
+                fn untagged(ptrs: [*mut u8; $n]) -> [*mut u8; $n] {
+                    [$(hazbox::untagged(ptrs[$i].cast::<$t>()).cast()),+]
+                }
+
+                #[inline]
+                unsafe fn refs(ptrs: [*mut u8; $n]) -> Self::Refs {
+                    // Safety: Upheld by the caller.
+                    unsafe { ($(&*hazbox::untagged(ptrs[$i].cast::<$t>()),)+) }
+                }
+            }
+        )*
+    };
+}
+
+moor_many! {
+    1 => (A 0);
+    2 => (A 0, B 1);
+    3 => (A 0, B 1, C 2);
+    4 => (A 0, B 1, C 2, E 3);
+    5 => (A 0, B 1, C 2, E 3, F 4);
+    6 => (A 0, B 1, C 2, E 3, F 4, G 5);
 }
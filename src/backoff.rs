@@ -0,0 +1,155 @@
+#[cfg(feature = "async")]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{
+        Context,
+        Poll,
+    },
+};
+use std::{
+    hint,
+    thread,
+};
+
+/// Decides how long an [`Anchor`][crate::anchor::Anchor] waits after failing to validate a value
+/// because it was replaced meanwhile, before trying again.
+///
+/// Retrying right away is best when the value is rarely replaced, but under write heavy
+/// contention it only makes the readers fight the writers for the cache line. Closures taking the
+/// number of retries also implement it.
+///
+/// ```
+/// # use std::{thread, time::Duration};
+/// # use anchorage::{anchor::Anchor, backoff::Exponential, hazbox::HazBox};
+/// # let config = HazBox::new(String::from("config"));
+/// # let mut anchor = Anchor::new();
+/// let value = anchor.moor_backoff(&config, Exponential::default());
+/// # assert_eq!(value, "config");
+/// let value = anchor.moor_backoff(&config, |_| thread::sleep(Duration::from_micros(10)));
+/// # assert_eq!(value, "config");
+/// ```
+///
+pub trait Backoff {
+    /// Waits before the next attempt, after `retries` failed validations in a row, starting at 1.
+    fn snooze(&mut self, retries: usize);
+}
+
+impl<F> Backoff for F
+where
+    F: FnMut(usize),
+{
+    #[inline]
+    fn snooze(&mut self, retries: usize) {
+        self(retries)
+    }
+}
+
+/// The [`Backoff`] used by [`Anchor::moor`][crate::anchor::Anchor::moor] and the other methods
+/// that don't take one.
+pub type DefaultBackoff = SpinThenYield;
+
+/// Retries right away, only hinting the processor that it is spinning.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Spin;
+
+impl Backoff for Spin {
+    #[inline]
+    fn snooze(&mut self, _retries: usize) {
+        hint::spin_loop();
+    }
+}
+
+/// Spins for the first retries, then yields the thread to the scheduler on every further one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpinThenYield {
+    spins: usize,
+}
+
+impl SpinThenYield {
+    /// Spins for the first `spins` retries.
+    #[inline]
+    pub const fn new(spins: usize) -> Self {
+        Self { spins }
+    }
+}
+
+impl Default for SpinThenYield {
+    #[inline]
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+impl Backoff for SpinThenYield {
+    #[inline]
+    fn snooze(&mut self, retries: usize) {
+        if retries <= self.spins {
+            hint::spin_loop();
+        } else {
+            thread::yield_now();
+        }
+    }
+}
+
+/// Spins twice as long on every retry, up to `2^max_exponent` spins, then yields the thread to the
+/// scheduler on every further one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Exponential {
+    max_exponent: u32,
+}
+
+impl Exponential {
+    #[inline]
+    pub const fn new(max_exponent: u32) -> Self {
+        Self { max_exponent }
+    }
+}
+
+impl Default for Exponential {
+    #[inline]
+    fn default() -> Self {
+        Self::new(6)
+    }
+}
+
+impl Backoff for Exponential {
+    #[inline]
+    fn snooze(&mut self, retries: usize) {
+        let exponent = retries.saturating_sub(1);
+        if exponent > self.max_exponent as usize {
+            thread::yield_now();
+            return;
+        }
+        for _ in 0..1usize << exponent {
+            hint::spin_loop();
+        }
+    }
+}
+
+/// Returns a future that is pending once, waking its task right away, so that an async retry loop
+/// lets the executor run other tasks between two attempts, like a thread yielding.
+#[cfg(feature = "async")]
+#[inline]
+pub(crate) fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+#[cfg(feature = "async")]
+pub(crate) struct YieldNow {
+    yielded: bool,
+}
+
+#[cfg(feature = "async")]
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
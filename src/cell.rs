@@ -0,0 +1,187 @@
+use std::{
+    alloc::AllocError,
+    fmt,
+    marker::PhantomData,
+    ops::Deref,
+};
+
+use crate::{
+    anchor::GlobalAnchor,
+    domain::global::GlobalDomain,
+    hazbox::HazBox,
+    retire::Retire,
+    Hazard,
+};
+
+/// A value that can be replaced while it is being read, without locking the readers, like an
+/// `ArcSwap`, but without reference counting on the read path.
+///
+/// Readers [load] a [`Guard`] that keeps the value it was loaded with alive for as long as it is
+/// held, even if a new value is [stored] meanwhile. Guards hold an anchor of the [`GlobalDomain`],
+/// whose per-thread cache of [`HazPtrs`][crate::hazptr::HazPtr] spares loading from scanning for
+/// one every time, and the [`GlobalDomain`] takes care of dropping the old values once no guard holds them anymore.
+///
+/// ```
+/// # use anchorage::cell::HazardCell;
+/// let routes = HazardCell::new(vec![String::from("/")]);
+///
+/// let current = routes.load();
+/// routes.rcu(|routes| [routes.as_slice(), &[String::from("/health")]].concat());
+///
+/// // The guard still holds the value it was loaded with.
+/// assert_eq!(current.len(), 1);
+/// assert_eq!(routes.load().len(), 2);
+/// ```
+///
+/// [load]: HazardCell::load
+/// [stored]: HazardCell::store
+///
+pub struct HazardCell<T>
+where
+    T: Hazard<'static>,
+{
+    current: HazBox<'static, T, GlobalDomain>,
+}
+
+impl<T> HazardCell<T>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self {
+            current: HazBox::new(value),
+        }
+    }
+
+    /// Returns a guard holding the current value.
+    pub fn load(&self) -> Guard<'_, T> {
+        let mut anchor = GlobalAnchor::new();
+        let value = anchor.moor(&self.current) as *const T;
+
+        Guard {
+            value,
+            _anchor: anchor,
+            __mk: PhantomData,
+        }
+    }
+
+    /// Replaces the value, dropping the old one once no guard holds it anymore.
+    #[inline]
+    pub fn store(&self, value: T) {
+        self.current.set(value);
+    }
+
+    /// Replaces the value, returning the old one to be retired.
+    #[inline]
+    pub fn swap(&self, value: T) -> Retire<'static, T, GlobalDomain> {
+        self.current.swap(value)
+    }
+
+    /// Same as [`HazardCell::swap`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`][std::alloc::handle_alloc_error] if the allocation fails, in which
+    /// case the value is left unchanged.
+    #[inline]
+    pub fn try_swap(&self, value: T) -> Result<Retire<'static, T, GlobalDomain>, AllocError> {
+        self.current.try_swap(value)
+    }
+
+    /// Replaces the value with the one built by `update` from the current one, returning the old
+    /// value to be retired.
+    ///
+    /// If the value is replaced while `update` runs, its result is dropped and `update` is called
+    /// again with the newer value, thus no concurrent change is ever lost.
+    ///
+    pub fn rcu<F>(&self, mut update: F) -> Retire<'static, T, GlobalDomain>
+    where
+        F: FnMut(&T) -> T,
+    {
+        match self.current.fetch_update(|current| Some(update(current))) {
+            Some(old) => old,
+            None => unreachable!("Update always returns a value"),
+        }
+    }
+
+    /// Same as [`HazardCell::rcu`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`][std::alloc::handle_alloc_error] if allocating the new value fails,
+    /// in which case the value is left unchanged.
+    pub fn try_rcu<F>(&self, mut update: F) -> Result<Retire<'static, T, GlobalDomain>, AllocError>
+    where
+        F: FnMut(&T) -> T,
+    {
+        match self
+            .current
+            .try_fetch_update(|current| Some(update(current)))?
+        {
+            Some(old) => Ok(old),
+            None => unreachable!("Update always returns a value"),
+        }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.current.into_inner()
+    }
+}
+
+impl<T> Default for HazardCell<T>
+where
+    T: Hazard<'static> + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for HazardCell<T>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> fmt::Debug for HazardCell<T>
+where
+    T: Hazard<'static> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HazardCell").field(&*self.load()).finish()
+    }
+}
+
+/// A value loaded from a [`HazardCell`], which is kept alive for as long as the guard is held.
+pub struct Guard<'c, T>
+where
+    T: Hazard<'static>,
+{
+    value: *const T,
+    /// Protects the value until the guard is dropped.
+    _anchor: GlobalAnchor,
+    __mk: PhantomData<&'c HazardCell<T>>,
+}
+
+impl<'c, T> Deref for Guard<'c, T>
+where
+    T: Hazard<'static>,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Safety: The value was moored by our anchor, which keeps protecting it until dropped.
+        unsafe { &*self.value }
+    }
+}
+
+impl<'c, T> fmt::Debug for Guard<'c, T>
+where
+    T: Hazard<'static> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
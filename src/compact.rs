@@ -0,0 +1,350 @@
+use std::{
+    alloc::{
+        handle_alloc_error,
+        AllocError,
+        Allocator,
+        Layout,
+    },
+    cell::UnsafeCell,
+    marker::PhantomData,
+    mem::{
+        self,
+        MaybeUninit,
+    },
+    ptr::NonNull,
+    sync::atomic::{
+        AtomicU32,
+        AtomicU64,
+        Ordering,
+    },
+};
+
+use crate::{
+    anchor::Anchor,
+    domain::Domain,
+    failpoints::{
+        self,
+        Failpoint,
+    },
+    retire::Retire,
+    Hazard,
+};
+
+/// Number of bits of a [`CompactBox`] word used for the slot index.
+pub const INDEX_BITS: u32 = 24;
+
+/// Number of bits of a [`CompactBox`] word available as tag bits.
+pub const TAG_BITS: u32 = u32::BITS - INDEX_BITS;
+
+/// Maximum number of slots a [`Slab`] can hold.
+pub const MAX_SLOTS: usize = 1 << INDEX_BITS;
+
+const INDEX_MASK: u32 = (1 << INDEX_BITS) - 1;
+
+/// Fixed capacity backing store for [`CompactBoxes`][CompactBox].
+///
+/// A [`Slab`] is an [`Allocator`] that only hands out storage for a single `T` at a time, out of a
+/// contiguous array of slots. Using it as the [allocator] of a domain lets payloads be addressed by
+/// their slot index, while protection and retirement keep working on the slot addresses as usual.
+///
+/// [allocator]: Domain::Alloc
+///
+pub struct Slab<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    /// Index + 1 of the next free slot after each free slot, 0 if none.
+    next_free: Box<[AtomicU32]>,
+    /// ABA counter in the high half, index + 1 of the first free slot in the low half.
+    free_head: AtomicU64,
+}
+
+// Safety: Slots are only ever accessed through the storage handed out by the allocator.
+unsafe impl<T> Sync for Slab<T> where T: Send + Sync {}
+
+impl<T> Slab<T> {
+    /// Creates a [`Slab`] with room for `capacity` values.
+    ///
+    /// # Panics
+    ///
+    /// * If `T` is zero sized, since slots could not be told apart by their address.
+    /// * If `capacity` is greater than [`MAX_SLOTS`].
+    ///
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert_ne!(mem::size_of::<T>(), 0, "Slabs cannot hold zero sized types");
+        assert!(capacity <= MAX_SLOTS, "Slab capacity is too large");
+
+        let slots = (0..capacity)
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect();
+        let next_free = (1..=capacity as u32)
+            .map(|next| {
+                AtomicU32::new(if next as usize == capacity {
+                    0
+                } else {
+                    next + 1
+                })
+            })
+            .collect();
+
+        Self {
+            slots,
+            next_free,
+            free_head: AtomicU64::new(if capacity == 0 { 0 } else { 1 }),
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    #[inline]
+    fn slot_ptr(&self, index: u32) -> *mut T {
+        self.slots[index as usize].get().cast()
+    }
+
+    /// Index of the slot starting at `ptr`, or [None] if no slot of this slab does.
+    #[inline]
+    fn index_of(&self, ptr: *const T) -> Option<u32> {
+        let offset = (ptr as usize).checked_sub(self.slots.as_ptr() as usize)?;
+        let index = offset / mem::size_of::<T>();
+        (offset % mem::size_of::<T>() == 0 && index < self.capacity()).then_some(index as u32)
+    }
+
+    fn pop_free(&self) -> Option<u32> {
+        let mut head = self.free_head.load(Ordering::Acquire);
+        loop {
+            let index = (head as u32).checked_sub(1)?;
+            let next = self.next_free[index as usize].load(Ordering::Relaxed);
+            let new_head = (((head >> 32) + 1) << 32) | next as u64;
+
+            match self.free_head.compare_exchange_weak(
+                head,
+                new_head,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Some(index),
+                Err(head_now) => head = head_now,
+            }
+        }
+    }
+
+    fn push_free(&self, index: u32) {
+        let mut head = self.free_head.load(Ordering::Acquire);
+        loop {
+            self.next_free[index as usize].store(head as u32, Ordering::Relaxed);
+            let new_head = (((head >> 32) + 1) << 32) | (index + 1) as u64;
+
+            match self.free_head.compare_exchange_weak(
+                head,
+                new_head,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return,
+                Err(head_now) => head = head_now,
+            }
+        }
+    }
+}
+
+unsafe impl<T> Allocator for Slab<T> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout != Layout::new::<T>() {
+            return Err(AllocError);
+        }
+
+        let index = self.pop_free().ok_or(AllocError)?;
+        // Safety: Slot pointers come from a live boxed slice, thus are never null.
+        let ptr = unsafe { NonNull::new_unchecked(self.slot_ptr(index).cast::<u8>()) };
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    /// # Panics
+    ///
+    /// * If `ptr` doesn't point at the start of a slot of this slab, or `layout` isn't the one of
+    /// `T`, since the slab could only have allocated that storage for a `T`.
+    ///
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        let index = match self.index_of(ptr.as_ptr().cast()) {
+            Some(index) if layout == Layout::new::<T>() => index,
+            _ => panic!("Deallocated {:p}, which wasn't allocated by this slab", ptr),
+        };
+        self.push_free(index);
+    }
+}
+
+/// Atomic owning pointer into a [`Slab`], stored as a 32-bit word.
+///
+/// Works like a [`HazBox`][crate::hazbox::HazBox], but the atomic word holds a [`INDEX_BITS`]-bit
+/// slot index and [`TAG_BITS`] tag bits instead of a full pointer, halving the hot atomic footprint
+/// on 64-bit targets and providing tag bits on 32-bit ones.
+///
+/// The domain must use a [`Slab`] as its [allocator][Domain::Alloc].
+///
+pub struct CompactBox<'dom, T, D>
+where
+    D: Domain<'dom, Alloc = Slab<T>>,
+    T: Hazard<'dom>,
+{
+    word: AtomicU32,
+    domain: D,
+    __mk: PhantomData<&'dom D>,
+}
+
+impl<'dom, T, D> CompactBox<'dom, T, D>
+where
+    D: Domain<'dom, Alloc = Slab<T>>,
+    T: Hazard<'dom>,
+{
+    pub fn try_new_in(obj: T, domain: D) -> Result<Self, AllocError> {
+        let index = Self::try_alloc(obj, domain).map_err(|(err, _)| err)?;
+
+        Ok(Self {
+            word: AtomicU32::new(index),
+            domain,
+            __mk: PhantomData,
+        })
+    }
+
+    #[inline]
+    pub fn new_in(obj: T, domain: D) -> Self {
+        match Self::try_new_in(obj, domain) {
+            Ok(haz) => haz,
+            Err(_) => handle_alloc_error(Layout::new::<T>()),
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    /// Returns the tag bits currently stored alongside the slot index.
+    #[inline]
+    pub fn tag(&self) -> u32 {
+        self.word.load(Ordering::Acquire) >> INDEX_BITS
+    }
+
+    /// Sets the tag bits stored alongside the slot index, returning the previous ones.
+    /// Only the lowest [`TAG_BITS`] bits of `tag` are kept.
+    #[inline]
+    pub fn set_tag(&self, tag: u32) -> u32 {
+        let mut word = self.word.load(Ordering::Relaxed);
+        loop {
+            let new_word = (tag << INDEX_BITS) | (word & INDEX_MASK);
+            match self.word.compare_exchange_weak(
+                word,
+                new_word,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return word >> INDEX_BITS,
+                Err(word_now) => word = word_now,
+            }
+        }
+    }
+
+    /// Publishes `with` in a new slot, keeping the tag bits, and returns the old value to be retired.
+    ///
+    /// Returns an [`AllocError`] along with `with` if the slab is full, in which case the box is
+    /// left unchanged.
+    pub fn try_swap(&self, with: T) -> Result<Retire<'dom, T, D>, (AllocError, T)> {
+        let index = Self::try_alloc(with, self.domain)?;
+
+        let mut word = self.word.load(Ordering::Relaxed);
+        loop {
+            let new_word = (word & !INDEX_MASK) | index;
+            match self.word.compare_exchange_weak(
+                word,
+                new_word,
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(word_now) => word = word_now,
+            }
+        }
+
+        let old = self.domain.allocator().slot_ptr(word & INDEX_MASK);
+        Ok(Retire::new_in(old, self.domain))
+    }
+
+    #[inline]
+    pub fn swap(&self, with: T) -> Retire<'dom, T, D> {
+        match self.try_swap(with) {
+            Ok(retire) => retire,
+            Err(_) => handle_alloc_error(Layout::new::<T>()),
+        }
+    }
+
+    #[inline]
+    pub fn set(&self, to: T) {
+        let _ = self.swap(to);
+    }
+
+    /// Same as [`CompactBox::try_swap`], but retires the old value immediately.
+    #[inline]
+    pub fn try_set(&self, to: T) -> Result<(), (AllocError, T)> {
+        self.try_swap(to).map(drop)
+    }
+
+    /// Protects the current value with `anchor`, returning a reference to it.
+    pub fn moor<'r>(&'r self, anchor: &'r mut Anchor<'dom, D>) -> &'r T {
+        assert!(self.domain == anchor.domain());
+
+        let slab = self.domain.allocator();
+        let hazptr = anchor.hazptr();
+        let mut index = self.word.load(Ordering::Relaxed) & INDEX_MASK;
+
+        loop {
+            let ptr = slab.slot_ptr(index);
+            // Same as in Anchor::try_moor, the slot may already be protected by the anchor.
+            if hazptr.ptr() != ptr.cast() {
+                hazptr.protect(ptr.cast());
+
+                crate::asymmetric_fence::light();
+            }
+
+            let actual = self.word.load(Ordering::Acquire) & INDEX_MASK;
+            if index == actual {
+                // Safety: Same as in Anchor::try_moor, the slot is initialized since it is
+                // published, and our hazptr prevents it from being freed.
+                return unsafe { &*ptr };
+            }
+            hazptr.reset();
+            index = actual;
+        }
+    }
+
+    /// Moves `obj` into a free slot, returning its index, or `obj` back if the slab is full.
+    fn try_alloc(obj: T, domain: D) -> Result<u32, (AllocError, T)> {
+        if failpoints::hit(Failpoint::Alloc) {
+            return Err((AllocError, obj));
+        }
+        let slab = domain.allocator();
+        let ptr = match slab.allocate(Layout::new::<T>()) {
+            Ok(ptr) => ptr.as_ptr().cast::<T>(),
+            Err(err) => return Err((err, obj)),
+        };
+        // Safety: The slot is free and sized and aligned for a T.
+        unsafe { ptr.write(obj) };
+        domain.allocated(mem::size_of::<T>());
+        // Safety: The slab only hands out pointers to the start of its slots.
+        Ok(unsafe { slab.index_of(ptr).unwrap_unchecked() })
+    }
+}
+
+impl<'dom, T, D> Drop for CompactBox<'dom, T, D>
+where
+    D: Domain<'dom, Alloc = Slab<T>>,
+    T: Hazard<'dom>,
+{
+    fn drop(&mut self) {
+        let slab = self.domain.allocator();
+        let ptr = slab.slot_ptr(*self.word.get_mut() & INDEX_MASK);
+        self.domain.deallocated(mem::size_of::<T>());
+        // Safety: Same as in HazBox::drop, we have exclusive access to the current slot.
+        let _ = unsafe { Box::from_raw_in(ptr, slab) };
+    }
+}
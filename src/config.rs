@@ -0,0 +1,228 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    ops::Deref,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+};
+
+use crate::{
+    cell::{
+        Guard,
+        HazardCell,
+    },
+    domain::global::GlobalDomain,
+    retire::Retire,
+    Hazard,
+};
+
+type Hook<T> = Arc<dyn Fn(&T, &T) + Send + Sync>;
+
+thread_local! {
+    /// Addresses of the configs whose writer lock is held by the current thread.
+    static WRITING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Configuration that can be reloaded while it is being read, without locking the readers.
+///
+/// Readers [load] a [`Snapshot`] of the current configuration, which stays the same for as long as
+/// it is held even if a new configuration is [stored] meanwhile. Snapshots are [`Guards`][Guard]
+/// of a [`HazardCell`], so loading doesn't acquire a [`HazPtr`][crate::hazptr::HazPtr] every time.
+///
+/// Writers are serialized, and [hooks] registered with [`SnapshotConfig::on_change`] are called
+/// with the old and the new configuration after each change, in the order they were registered.
+/// Hooks are called before the writer is done, thus they see the changes in the order they were
+/// made. They may still register other hooks or change the configuration themselves, in which
+/// case that change is made, and its hooks called, before the remaining hooks of the current one.
+///
+/// ```
+/// # use std::{sync::LazyLock, time::Duration};
+/// # use anchorage::config::SnapshotConfig;
+/// #[derive(Default)]
+/// struct Config {
+///     version: u64,
+///     timeout: Duration,
+/// }
+/// # #[cfg(feature = "explicit-hazard")]
+/// # unsafe impl<'dom> anchorage::Hazard<'dom> for Config {}
+///
+/// static CONFIG: LazyLock<SnapshotConfig<Config>> =
+///     LazyLock::new(|| SnapshotConfig::new(Config::default()));
+///
+/// CONFIG.on_change(|old, new| println!("reloaded, {} -> {}", old.version, new.version));
+///
+/// let config = CONFIG.load();
+///
+/// CONFIG.update(|config| Config {
+///     version: config.version + 1,
+///     timeout: Duration::from_secs(5),
+/// });
+/// assert_eq!(config.timeout, Duration::ZERO);
+/// ```
+///
+/// [hooks]: SnapshotConfig::on_change
+/// [load]: SnapshotConfig::load
+/// [stored]: SnapshotConfig::store
+///
+pub struct SnapshotConfig<T>
+where
+    T: Hazard<'static>,
+{
+    current: HazardCell<T>,
+    /// Serializes writers.
+    writer: Mutex<()>,
+    hooks: Mutex<Vec<Hook<T>>>,
+}
+
+impl<T> SnapshotConfig<T>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    pub fn new(config: T) -> Self {
+        Self {
+            current: HazardCell::new(config),
+            writer: Mutex::new(()),
+            hooks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns a snapshot of the current configuration.
+    #[inline]
+    pub fn load(&self) -> Snapshot<'_, T> {
+        Snapshot(self.current.load())
+    }
+
+    /// Replaces the configuration, then calls the change hooks.
+    pub fn store(&self, config: T) {
+        let _writer = self.writer();
+        let (old, new) = self.replace(config);
+        self.changed(&old, &new);
+    }
+
+    /// Replaces the configuration with the one built by `update` from the current one, then
+    /// calls the change hooks.
+    ///
+    /// Writers are serialized, thus no change made while `update` runs can be lost.
+    ///
+    pub fn update<F>(&self, update: F)
+    where
+        F: FnOnce(&T) -> T,
+    {
+        let _writer = self.writer();
+        let config = update(&self.load());
+        let (old, new) = self.replace(config);
+        self.changed(&old, &new);
+    }
+
+    /// Registers `hook` to be called with the old and the new configuration after each change.
+    pub fn on_change<F>(&self, hook: F)
+    where
+        F: Fn(&T, &T) + Send + Sync + 'static,
+    {
+        self.hooks().push(Arc::new(hook));
+    }
+
+    /// Replaces the configuration, returning the old one along with a snapshot of the new one,
+    /// taken before any other writer can replace it.
+    fn replace(&self, config: T) -> (Retire<'static, T, GlobalDomain>, Snapshot<'_, T>) {
+        let old = self.current.swap(config);
+        (old, self.load())
+    }
+
+    /// Calls the hooks registered so far, without holding the lock of the hooks.
+    fn changed(&self, old: &T, new: &T) {
+        let hooks = self.hooks().clone();
+        hooks.iter().for_each(|hook| hook(old, new));
+    }
+
+    /// Acquires the writer lock, unless the current thread already holds it, calling the hooks.
+    fn writer(&self) -> Writer<'_> {
+        let config = self as *const Self as usize;
+        if WRITING.with(|writing| writing.borrow().contains(&config)) {
+            return Writer {
+                guard: None,
+                config,
+            };
+        }
+
+        // Nothing is guarded by the lock itself, a panic while holding it can't leave anything
+        // inconsistent.
+        let guard = self.writer.lock().unwrap_or_else(|err| err.into_inner());
+        WRITING.with(|writing| writing.borrow_mut().push(config));
+        Writer {
+            guard: Some(guard),
+            config,
+        }
+    }
+
+    fn hooks(&self) -> MutexGuard<'_, Vec<Hook<T>>> {
+        // Hooks are only ever pushed, a panic while holding the lock can't leave them inconsistent.
+        self.hooks.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<T> Default for SnapshotConfig<T>
+where
+    T: Hazard<'static> + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> fmt::Debug for SnapshotConfig<T>
+where
+    T: Hazard<'static> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SnapshotConfig")
+            .field(&*self.load())
+            .finish()
+    }
+}
+
+/// The writer lock of a [`SnapshotConfig`], or [None] for changes made by its hooks, which are
+/// already serialized by the lock of the change calling them.
+struct Writer<'c> {
+    guard: Option<MutexGuard<'c, ()>>,
+    config: usize,
+}
+
+impl<'c> Drop for Writer<'c> {
+    fn drop(&mut self) {
+        if self.guard.is_some() {
+            WRITING.with(|writing| writing.borrow_mut().retain(|&config| config != self.config));
+        }
+    }
+}
+
+/// A snapshot of a [`SnapshotConfig`], which stays the same for as long as it is held.
+pub struct Snapshot<'c, T>(Guard<'c, T>)
+where
+    T: Hazard<'static>;
+
+impl<'c, T> Deref for Snapshot<'c, T>
+where
+    T: Hazard<'static>,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'c, T> fmt::Debug for Snapshot<'c, T>
+where
+    T: Hazard<'static> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::atomic::{
+    AtomicIsize,
+    AtomicUsize,
+    Ordering,
+};
+
+/// Number of shards per counter. Should be at least the number of cores writing concurrently.
+pub(crate) const SHARDS: usize = 16;
+
+/// Count a shard accumulates before flushing it into the shared approximation, bounding how far
+/// the approximation lags behind to `SHARDS * (FLUSH - 1)`.
+const FLUSH: isize = 8;
+
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static SHARD: usize = NEXT_SHARD.fetch_add(1, Ordering::Relaxed) % SHARDS;
+}
+
+/// Index of the shard the current thread writes to, below [SHARDS].
+#[inline]
+pub(crate) fn local_shard() -> usize {
+    // Falls back to the first shard while the thread locals are being destroyed.
+    SHARD.try_with(|&shard| shard).unwrap_or(0)
+}
+
+/// Padded so that each shard sits on its own cache line.
+#[repr(align(128))]
+struct Shard(AtomicIsize);
+
+/// Counter split into per-thread shards, which are only aggregated when read.
+///
+/// Writers on different threads update different cache lines, removing the contention a single
+/// shared counter would have on every push. Reads are not atomic across shards, which is fine for
+/// the heuristics these counters drive.
+///
+/// Shards are flushed into a shared approximation every [FLUSH] counts, so that hot paths can
+/// check thresholds with [`ShardedCounter::approx`] touching a single cache line, leaving summing
+/// every shard to the rarer paths that need the exact count.
+pub struct ShardedCounter {
+    approx: Shard,
+    shards: [Shard; SHARDS],
+}
+
+impl ShardedCounter {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            approx: Shard(AtomicIsize::new(0)),
+            shards: [const { Shard(AtomicIsize::new(0)) }; SHARDS],
+        }
+    }
+
+    #[inline]
+    fn local(&self) -> &AtomicIsize {
+        &self.shards[local_shard()].0
+    }
+
+    #[inline]
+    pub fn add(&self, n: isize) {
+        let local = self.local();
+        let pending = local.fetch_add(n, Ordering::SeqCst) + n;
+        if pending.abs() >= FLUSH {
+            // The shard may be shared with other threads, thus only the count swapped out is moved.
+            self.approx
+                .0
+                .fetch_add(local.swap(0, Ordering::AcqRel), Ordering::SeqCst);
+        }
+    }
+
+    /// Count flushed so far, short of less than [FLUSH] per shard.
+    #[inline]
+    pub fn approx(&self) -> isize {
+        self.approx.0.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub fn load(&self) -> isize {
+        self.approx()
+            + self
+                .shards
+                .iter()
+                .map(|shard| shard.0.load(Ordering::Acquire))
+                .sum::<isize>()
+    }
+
+    /// Resets the counter to 0, returning the count it had.
+    #[inline]
+    pub fn take(&self) -> isize {
+        self.approx.0.swap(0, Ordering::AcqRel)
+            + self
+                .shards
+                .iter()
+                .map(|shard| shard.0.swap(0, Ordering::AcqRel))
+                .sum::<isize>()
+    }
+}
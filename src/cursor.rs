@@ -0,0 +1,268 @@
+use std::{
+    fmt,
+    ptr,
+    sync::atomic::AtomicPtr,
+};
+
+use crate::{
+    anchor::Anchor,
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazoption::HazOption,
+    Hazard,
+};
+
+/// Walks a chain of nodes linked by [`HazOptions`][HazOption] or raw atomic pointers, protecting
+/// each node before releasing the previous one, as the building block of lists, sets and queues.
+///
+/// Two [`Anchors`][Anchor] take turns: the next node is moored with the spare one while the
+/// current one still protects the node holding the link, then their
+/// [protection is swapped][Anchor::transfer_protection], thus the link is never read from a node
+/// that may have been reclaimed meanwhile. Only the current node is reachable through the cursor,
+/// since the previous one is no longer protected once it advances.
+///
+/// Unlike an [`Anchor`], the cursor keeps the node it is at protected after releasing every node
+/// it was reached through, including the box holding the first one. Owning boxes free their value
+/// right away when dropped, instead of retiring it, thus moving the cursor is only sound for
+/// chains whose nodes are only ever freed by retiring them, see [`Cursor::start`].
+///
+/// ```
+/// # use anchorage::{cursor::Cursor, domain::global::GlobalDomain, hazoption::HazOption};
+/// struct Entry {
+///     key: u32,
+///     value: u64,
+///     next: HazOption<'static, Entry, GlobalDomain>,
+/// }
+/// # #[cfg(feature = "explicit-hazard")]
+/// # unsafe impl<'dom> anchorage::Hazard<'dom> for Entry {}
+///
+/// fn find(head: &HazOption<'static, Entry, GlobalDomain>, key: u32) -> Option<u64> {
+///     let mut cursor = Cursor::new();
+///     // Safety: Nodes are only unlinked by remove, which retires them without dropping their links.
+///     let mut node = unsafe { cursor.start(head) };
+///     while let Some(entry) = node {
+///         if entry.key == key {
+///             return Some(entry.value);
+///         }
+///         node = unsafe { cursor.advance(|entry| &entry.next) };
+///     }
+///     None
+/// }
+/// # let next = HazOption::new(None);
+/// # let head = HazOption::new(Some(Entry { key: 1, value: 10, next }));
+/// # assert_eq!(find(&head, 1), Some(10));
+/// # assert_eq!(find(&head, 2), None);
+/// ```
+///
+pub struct Cursor<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    node: *const T,
+    /// Protects the node, if any.
+    curr: Anchor<'dom, D>,
+    /// Protects the next node while it is being moored, nothing otherwise.
+    next: Anchor<'dom, D>,
+}
+
+// Safety: The node is only ever shared, and hazards are Sync, like holding a reference to it.
+unsafe impl<'dom, T, D> Send for Cursor<'dom, T, D>
+where
+    D: Domain<'dom> + Send,
+    T: Hazard<'dom>,
+{
+}
+
+// Safety: Same as for Send.
+unsafe impl<'dom, T, D> Sync for Cursor<'dom, T, D>
+where
+    D: Domain<'dom> + Sync,
+    T: Hazard<'dom>,
+{
+}
+
+impl<T> Cursor<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    pub fn new() -> Self {
+        Self::from_anchors(Anchor::new(), Anchor::new())
+    }
+}
+
+impl<T> Default for Cursor<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'dom, T, D> Cursor<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    #[inline]
+    pub fn try_new_in(domain: D) -> Option<Self> {
+        let curr = Anchor::try_new_in(domain)?;
+        let next = Anchor::try_new_in(domain)?;
+        Some(Self::from_anchors(curr, next))
+    }
+
+    #[inline]
+    pub fn new_in(domain: D) -> Self {
+        Self::try_new_in(domain).expect("Unable to acquire a HazBox Pointer")
+    }
+
+    /// Creates a cursor taking turns between two existing anchors, e.g. from an
+    /// [`AnchorPool`][crate::pool::AnchorPool].
+    ///
+    /// # Panics
+    ///
+    /// * If the anchors are from different domains.
+    ///
+    #[inline]
+    pub fn from_anchors(curr: Anchor<'dom, D>, next: Anchor<'dom, D>) -> Self {
+        assert!(curr.domain() == next.domain());
+
+        curr.reset();
+        next.reset();
+        Self {
+            node: ptr::null(),
+            curr,
+            next,
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.curr.domain()
+    }
+
+    /// Returns the node the cursor is at, or [None] if it wasn't started or went past the end.
+    #[inline]
+    pub fn current(&self) -> Option<&T> {
+        // Safety: The node is protected by curr until the cursor moves.
+        unsafe { self.node.as_ref() }
+    }
+
+    /// Moves the cursor to the first node of the chain, the one `head` holds, releasing the node it
+    /// was at.
+    ///
+    /// # Safety
+    ///
+    /// * Every node reachable from `head` must only be freed by [retiring] it to the domain of the
+    /// cursor, for as long as the cursor may be at it. In particular, neither `head` nor a node
+    /// linking to the one the cursor is at may be dropped meanwhile, since dropping a
+    /// [`HazOption`] frees its value right away.
+    ///
+    /// [retiring]: Domain::retire
+    ///
+    pub unsafe fn start(&mut self, head: &HazOption<'dom, T, D>) -> Option<&T> {
+        let node = self
+            .next
+            .moor_opt(head)
+            .map_or(ptr::null(), |node| node as *const T);
+        self.moved(node)
+    }
+
+    /// Moves the cursor to the node linked from the one it is at, selected by `link`, releasing the
+    /// latter once the former is protected. Does nothing if the cursor isn't at any node.
+    ///
+    /// # Safety
+    ///
+    /// * Same as for [`Cursor::start`], for every node reachable from the selected link.
+    ///
+    pub unsafe fn advance<F>(&mut self, link: F) -> Option<&T>
+    where
+        F: FnOnce(&T) -> &HazOption<'dom, T, D>,
+    {
+        // Safety: The node is protected by curr, which is left alone until the next one is.
+        let curr = unsafe { self.node.as_ref() }?;
+        let node = self
+            .next
+            .moor_opt(link(curr))
+            .map_or(ptr::null(), |node| node as *const T);
+        self.moved(node)
+    }
+
+    /// Same as [`Cursor::start`], but for a chain linked by raw atomic pointers.
+    ///
+    /// # Safety
+    ///
+    /// * Every node reachable from `head` must stay allocated until it is unlinked and then
+    /// [retired][Domain::retire] to the domain of the cursor, even once the node linking to it is
+    /// no longer shared.
+    ///
+    pub unsafe fn start_raw(&mut self, head: &AtomicPtr<T>) -> Option<&T> {
+        let node = self
+            .next
+            .moor_nullable(head)
+            .map_or(ptr::null(), |node| node as *const T);
+        self.moved(node)
+    }
+
+    /// Same as [`Cursor::advance`], but for a chain linked by raw atomic pointers.
+    ///
+    /// # Safety
+    ///
+    /// * Same as for [`Cursor::start_raw`], for every node reachable from the selected link.
+    ///
+    pub unsafe fn advance_raw<F>(&mut self, link: F) -> Option<&T>
+    where
+        F: FnOnce(&T) -> &AtomicPtr<T>,
+    {
+        // Safety: Same as in advance.
+        let curr = unsafe { self.node.as_ref() }?;
+        let node = self
+            .next
+            .moor_nullable(link(curr))
+            .map_or(ptr::null(), |node| node as *const T);
+        self.moved(node)
+    }
+
+    /// Releases the node the cursor is at, leaving it at none.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.curr.reset();
+        self.node = ptr::null();
+    }
+
+    /// Releases both anchors, to be reused on their own.
+    #[inline]
+    pub fn into_anchors(self) -> (Anchor<'dom, D>, Anchor<'dom, D>) {
+        self.curr.reset();
+        (self.curr, self.next)
+    }
+
+    /// Makes the node just moored by next the current one, releasing the previous one.
+    #[inline]
+    fn moved(&mut self, node: *const T) -> Option<&T> {
+        if node.is_null() {
+            self.reset();
+        } else {
+            self.next.transfer_protection(&mut self.curr);
+        }
+        self.node = node;
+        self.current()
+    }
+}
+
+impl<'dom, T, D> fmt::Debug for Cursor<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("current", &self.current())
+            .finish()
+    }
+}
@@ -1,15 +1,29 @@
 use std::{
     alloc::Allocator,
+    convert::TryFrom,
     ptr::NonNull,
+    vec,
 };
 
 use crate::{
     hazptr::HazPtr,
+    metrics::MetricsSnapshot,
     Hazard,
 };
 
+pub mod backend;
+#[cfg(feature = "watchdog")]
+pub mod bounded;
+pub mod child;
+pub mod composite;
 pub mod global;
+pub mod local;
 pub mod scoped;
+pub mod sim;
+
+/// A reclaimed [`Hazard`] handed out by [`Domain::drain_reclaimable`], still owning its storage in
+/// the domain's allocator.
+pub type Reclaimed<'dom, A> = Box<dyn Hazard<'dom> + 'dom, &'dom A>;
 
 /// Owns a set of [`HazPtrs`][HazPtr] to prevent [`Hazards`][Hazard] from being dropped, and retires
 /// said [`Hazards`][Hazard] when they are no longer protected by any [`HazPtr`] from this domain.
@@ -102,6 +116,47 @@ pub unsafe trait Domain<'dom>: Copy + Eq + 'dom {
     ///
     fn acquire(self) -> Option<&'dom HazPtr>;
 
+    /// Acquires `N` [`HazPtrs`][HazPtr] at once, or none of them.
+    ///
+    /// The default implementation [acquires][acquire] them one at a time, [releasing] the ones it
+    /// got if any of them can't be. Implementations should override it to find all of them in a
+    /// single pass.
+    ///
+    /// # Implementation Safety
+    ///
+    /// * Same as [`Domain::acquire`], for every returned [`HazPtr`].
+    ///
+    /// [acquire]: Domain::acquire
+    /// [releasing]: Domain::release
+    ///
+    fn acquire_many<const N: usize>(self) -> Option<[&'dom HazPtr; N]> {
+        let mut hazptrs = Vec::with_capacity(N);
+        for _ in 0..N {
+            match self.acquire() {
+                Some(hazptr) => hazptrs.push(hazptr),
+                None => {
+                    hazptrs.into_iter().for_each(|hazptr| self.release(hazptr));
+                    return None;
+                }
+            }
+        }
+
+        // Safety: Exactly N pointers were pushed above.
+        Some(unsafe { <[_; N]>::try_from(hazptrs).unwrap_unchecked() })
+    }
+
+    /// Releases a [`HazPtr`] previously [acquired] from this domain, so it can be reused.
+    ///
+    /// The default implementation simply calls [`HazPtr::release`]. Implementations that keep
+    /// track of acquired [`HazPtrs`][HazPtr] should override it.
+    ///
+    /// [acquired]: Domain::acquire
+    ///
+    #[inline]
+    fn release(self, hazptr: &'dom HazPtr) {
+        hazptr.release();
+    }
+
     ///
     /// Sets the [`Hazards`][Hazard] pointed by `retired` to be [dropped] some time after no more
     /// [`HazPtrs`][HazPtr] owned by this domain are protecting it.
@@ -120,4 +175,136 @@ pub unsafe trait Domain<'dom>: Copy + Eq + 'dom {
     /// [retired]: Domain::retire
     ///
     unsafe fn retire(self, retired: NonNull<dyn Hazard<'dom>>);
+
+    /// Records that `bytes` were allocated through [`Domain::allocator`] for a [`Hazard`] held by a
+    /// box, to be reported in the [metrics] of this domain.
+    ///
+    /// Boxes report their storage once allocated, and once deallocated unless they
+    /// [retire][retired] it, since the domain then accounts for it until it is reclaimed. The
+    /// default implementation records nothing, for domains that don't keep metrics.
+    ///
+    /// [metrics]: Domain::metrics
+    /// [retired]: Domain::retire
+    ///
+    #[inline]
+    fn allocated(self, bytes: usize) {
+        let _ = bytes;
+    }
+
+    /// Records that `bytes` previously [allocated] were deallocated without being
+    /// [retired][retired] to this domain.
+    ///
+    /// [allocated]: Domain::allocated
+    /// [retired]: Domain::retire
+    ///
+    #[inline]
+    fn deallocated(self, bytes: usize) {
+        let _ = bytes;
+    }
+
+    ///
+    /// [Retires][retired] all [`Hazards`][Hazard] in `retired` as a single burst.
+    ///
+    /// Implementations should override this to amortize fences and reclamation checks over the
+    /// whole burst. The default implementation [retires][retired] them one at a time.
+    ///
+    /// # Safety
+    ///
+    /// * Same as [`Domain::retire`], for every pointer in `retired`.
+    ///
+    /// [retired]: Domain::retire
+    ///
+    unsafe fn retire_all<I>(self, retired: I)
+    where
+        I: IntoIterator<Item = NonNull<dyn Hazard<'dom>>>,
+    {
+        for hazard in retired {
+            // Safety: Upheld by the caller.
+            unsafe { self.retire(hazard) }
+        }
+    }
+
+    /// Reclaims every retired [`Hazard`] that is no longer protected, returning how many were.
+    ///
+    /// The default implementation reclaims nothing, for domains that only reclaim on their own
+    /// schedule.
+    ///
+    #[inline]
+    fn eager_reclaim(self) -> usize {
+        0
+    }
+
+    /// Takes every retired [`Hazard`] that is no longer protected out of this domain, handing
+    /// them to the caller instead of dropping them, e.g. to reuse their storage or to assert on
+    /// exactly what was reclaimed.
+    ///
+    /// The default implementation hands out nothing, for domains that only reclaim on their own
+    /// schedule.
+    ///
+    #[inline]
+    fn drain_reclaimable(self) -> vec::IntoIter<Reclaimed<'dom, Self::Alloc>> {
+        Vec::new().into_iter()
+    }
+
+    /// Checks the internal invariants of this domain, panicking if any of them is broken.
+    ///
+    /// May be called concurrently with any other operation, thus implementations must only check
+    /// invariants that hold at every point in time. The default implementation checks nothing.
+    ///
+    #[inline]
+    fn verify(self) {}
+
+    /// Calls `f` with every [`HazPtr`] owned by this domain, returning whether it did.
+    ///
+    /// The default implementation visits nothing and returns false, for domains that can't
+    /// enumerate their [`HazPtrs`][HazPtr].
+    ///
+    /// # Implementation Safety
+    ///
+    /// * When returning true, every [`HazPtr`] that may be protecting a [`Hazard`] retired to this
+    /// domain must have been visited, since callers may rely on it to decide that a [`Hazard`] is
+    /// not protected.
+    ///
+    #[inline]
+    fn visit_hazptrs(self, f: &mut dyn FnMut(&'dom HazPtr)) -> bool {
+        let _ = f;
+        false
+    }
+
+    /// Returns a snapshot of the metrics of this domain.
+    ///
+    /// The default implementation returns an empty snapshot, for domains that don't keep metrics.
+    ///
+    #[inline]
+    fn metrics(self) -> MetricsSnapshot {
+        MetricsSnapshot::default()
+    }
+}
+
+/// Domains owned by value, which hand out [`Domain`] handles borrowing them, such as a
+/// [`ChildDomain`][child::ChildDomain] and its [`ChildDomainRef`][child::ChildDomainRef].
+///
+/// Lets an owner shared through an [`Arc`][std::sync::Arc] stand in for its handles, as done by
+/// [`OwnedAnchor`][crate::anchor::OwnedAnchor], since the handles themselves can't own it while
+/// being [`Copy`].
+///
+/// # Safety
+///
+/// * The handle must only borrow the owner, thus staying valid for as long as the owner is
+/// neither moved nor dropped, whatever `'dom` it is given.
+///
+/// * [`DomainOwner::Handle`] must only differ in `'dom` between two lifetimes, which holds for any
+/// implementation generic over it.
+///
+pub unsafe trait DomainOwner<'dom> {
+    type Handle: Domain<'dom>;
+
+    /// Returns a handle borrowing this domain for `'dom`.
+    ///
+    /// # Safety
+    ///
+    /// * Values retired through the handle must not borrow data that may be dropped before this
+    /// domain is, since the domain may keep them until it is dropped itself, past `'dom`.
+    ///
+    unsafe fn handle(&'dom self) -> Self::Handle;
 }
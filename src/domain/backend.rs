@@ -0,0 +1,624 @@
+//! Building blocks for alternative reclamation backends, plugging into the [`HazBox`] and
+//! [`Anchor`] front end without implementing a whole [`Domain`].
+//!
+//! A [`BackendDomain`] is assembled from three parts, each of which can be replaced on its own:
+//!
+//! * A [`SlotProvider`], handing out the [`HazPtrs`][HazPtr] anchors protect through.
+//! * A [`RetiredList`], storing retired hazards until they are reclaimed.
+//! * A [`ReclaimTrigger`], deciding when a reclamation pass runs.
+//!
+//! Reclamation itself, i.e. scanning the [`HazPtrs`][HazPtr] for the retired hazards still
+//! protected and dropping the others, stays with the [`BackendDomain`], so that backends never
+//! have to uphold the safety requirements of [`Domain`] themselves, only those of the part they
+//! replace.
+//!
+//! ```
+//! # use anchorage::{
+//! #     anchor::Anchor,
+//! #     domain::backend::{BackendDomain, HazPtrSlots, LockedRetired, Threshold},
+//! #     hazbox::HazBox,
+//! # };
+//! let domain = BackendDomain::new(HazPtrSlots::new(), LockedRetired::new(), Threshold::default());
+//! // Safety: Strings own their data.
+//! let handle = unsafe { domain.handle() };
+//! let config = HazBox::new_in(String::from("localhost"), handle);
+//! assert_eq!(Anchor::new_in(handle).moor(&config), "localhost");
+//! ```
+//!
+//! [`Anchor`]: crate::anchor::Anchor
+//! [`HazBox`]: crate::hazbox::HazBox
+
+use std::{
+    alloc::{
+        Allocator,
+        Global,
+    },
+    fmt,
+    marker::PhantomData,
+    mem,
+    ptr::{
+        self,
+        NonNull,
+    },
+    sync::{
+        Mutex,
+        MutexGuard,
+    },
+    vec,
+};
+
+use crate::{
+    domain::{
+        Domain,
+        DomainOwner,
+        Reclaimed,
+    },
+    failpoints::{
+        self,
+        Failpoint,
+    },
+    guarded::{
+        partition_in_place,
+        GuardedSet,
+    },
+    hazptr::{
+        HazPtr,
+        HazPtrRecords,
+    },
+    metrics::{
+        hazard_size,
+        MetricsRecorder,
+        MetricsSnapshot,
+    },
+    published,
+    Hazard,
+};
+
+/// A hazard retired to a [`BackendDomain`], with its type and lifetime erased.
+///
+/// [`RetiredLists`][RetiredList] only ever store these and look at their [address], the
+/// [`BackendDomain`] restores and drops the hazard once it is reclaimed.
+///
+/// [address]: Retired::addr
+///
+pub struct Retired(NonNull<dyn Hazard<'static>>);
+
+// Safety: Hazards are Send + Sync, and are only dropped by the domain they were retired to.
+unsafe impl Send for Retired {}
+unsafe impl Sync for Retired {}
+
+impl Retired {
+    /// Address of the hazard, the one [`HazPtrs`][HazPtr] protect it with.
+    #[inline]
+    pub fn addr(&self) -> *const u8 {
+        self.0.as_ptr() as *const u8
+    }
+}
+
+impl fmt::Debug for Retired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Retired").field(&self.addr()).finish()
+    }
+}
+
+/// Hands out the [`HazPtrs`][HazPtr] used by the anchors of a [`BackendDomain`].
+///
+/// # Safety
+///
+/// * [`SlotProvider::acquire`] must return a [`HazPtr`] that is [acquired] and not used by anyone
+/// else until it is [released][SlotProvider::release].
+///
+/// * [`SlotProvider::visit`] must visit every [`HazPtr`] that was ever acquired from the provider,
+/// since hazards are reclaimed when none of the visited ones protects them.
+///
+/// [acquired]: HazPtr::try_acquire
+///
+pub unsafe trait SlotProvider: Sync {
+    /// Acquires an unused [`HazPtr`], or returns [None] if none could be found nor created.
+    fn acquire(&self) -> Option<&HazPtr>;
+
+    /// Releases a [`HazPtr`] previously [acquired][SlotProvider::acquire] from this provider.
+    fn release(&self, hazptr: &HazPtr);
+
+    /// Calls `f` with every [`HazPtr`] owned by this provider.
+    fn visit<'s>(&'s self, f: &mut dyn FnMut(&'s HazPtr));
+
+    /// Number of [`HazPtrs`][HazPtr] owned by this provider.
+    fn count(&self) -> usize;
+}
+
+/// Stores the hazards retired to a [`BackendDomain`] until they are reclaimed.
+///
+/// # Safety
+///
+/// * Every [`Retired`] pushed or [restored] must be kept until it is handed back through
+/// [`RetiredList::take`], exactly once, since the [`BackendDomain`] can't tell whether a hazard
+/// was lost or handed back twice.
+///
+/// [restored]: RetiredList::restore
+///
+pub unsafe trait RetiredList: Sync {
+    /// Stores `retired`, returning how many hazards are retired, which may be approximate.
+    fn push(&self, retired: Retired) -> usize;
+
+    /// Removes every stored hazard and hands them back, for a reclamation pass to check them
+    /// without holding the list.
+    fn take(&self) -> Vec<Retired>;
+
+    /// Stores back the hazards a reclamation pass found still protected.
+    fn restore(&self, kept: Vec<Retired>);
+
+    /// Number of hazards stored, which may be approximate.
+    fn len(&self) -> usize;
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Decides when a [`BackendDomain`] runs a reclamation pass.
+pub trait ReclaimTrigger: Sync {
+    /// Whether a reclamation pass should run right after a hazard is retired, given how many are
+    /// retired and how many [`HazPtrs`][HazPtr] can be protecting them.
+    fn should_reclaim(&self, retired: usize, hazptrs: usize) -> bool;
+}
+
+/// The [`SlotProvider`] of the built-in domains, which reuses released [`HazPtrs`][HazPtr] and
+/// only ever creates more when all of them are acquired.
+pub struct HazPtrSlots(HazPtrRecords);
+
+impl HazPtrSlots {
+    #[inline]
+    pub const fn new() -> Self {
+        Self(HazPtrRecords::new())
+    }
+}
+
+impl Default for HazPtrSlots {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for HazPtrSlots {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HazPtrSlots")
+            .field("active", &self.0.active())
+            .field("total", &self.0.count())
+            .finish()
+    }
+}
+
+// Safety: HazPtrRecords only hands out acquired records, and iterates over all of them.
+unsafe impl SlotProvider for HazPtrSlots {
+    #[inline]
+    fn acquire(&self) -> Option<&HazPtr> {
+        self.0.acquire()
+    }
+
+    #[inline]
+    fn release(&self, hazptr: &HazPtr) {
+        self.0.release(hazptr)
+    }
+
+    #[inline]
+    fn visit<'s>(&'s self, f: &mut dyn FnMut(&'s HazPtr)) {
+        self.0.iter().for_each(f)
+    }
+
+    #[inline]
+    fn count(&self) -> usize {
+        self.0.count() as usize
+    }
+}
+
+/// A [`RetiredList`] keeping the hazards in a vector behind a mutex.
+#[derive(Default)]
+pub struct LockedRetired(Mutex<Vec<Retired>>);
+
+impl LockedRetired {
+    #[inline]
+    pub fn new() -> Self {
+        Self(Mutex::new(Vec::new()))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Retired>> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl fmt::Debug for LockedRetired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockedRetired")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+// Safety: Hazards are only removed from the vector when handed back.
+unsafe impl RetiredList for LockedRetired {
+    fn push(&self, retired: Retired) -> usize {
+        let mut list = self.lock();
+        list.push(retired);
+        list.len()
+    }
+
+    #[inline]
+    fn take(&self) -> Vec<Retired> {
+        mem::take(&mut *self.lock())
+    }
+
+    #[inline]
+    fn restore(&self, kept: Vec<Retired>) {
+        crate::guarded::merge(&mut self.lock(), kept)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.lock().len()
+    }
+}
+
+/// A [`ReclaimTrigger`] running a pass once enough hazards are retired, both in absolute terms
+/// and relative to the number of [`HazPtrs`][HazPtr], the same heuristic as the
+/// [`GlobalDomain`][crate::domain::global::GlobalDomain] without its timed cleanup.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Threshold {
+    /// Number of retired hazards below which no pass runs.
+    pub retired: usize,
+    /// Number of retired hazards per [`HazPtr`] below which no pass runs.
+    pub per_hazptr: usize,
+}
+
+impl Default for Threshold {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            retired: 1000,
+            per_hazptr: 2,
+        }
+    }
+}
+
+impl ReclaimTrigger for Threshold {
+    #[inline]
+    fn should_reclaim(&self, retired: usize, hazptrs: usize) -> bool {
+        retired >= self.retired && retired >= self.per_hazptr * hazptrs
+    }
+}
+
+/// Domain assembled from a [`SlotProvider`], a [`RetiredList`] and a [`ReclaimTrigger`], see the
+/// [module documentation][self].
+///
+/// Like a [`ChildDomain`][crate::domain::child::ChildDomain], it is used through the handle
+/// returned by [`BackendDomain::handle`]. Dropping it reclaims every retired hazard that is no
+/// longer protected, those still protected by leaked anchors are leaked along with them.
+///
+pub struct BackendDomain<'dom, S, L, T, A = Global>
+where
+    S: SlotProvider,
+    L: RetiredList,
+    T: ReclaimTrigger,
+    A: Allocator,
+{
+    slots: S,
+    retired: L,
+    trigger: T,
+    allocator: A,
+    metrics: MetricsRecorder,
+    __mk: PhantomData<&'dom ()>,
+}
+
+impl<'dom, S, L, T> BackendDomain<'dom, S, L, T, Global>
+where
+    S: SlotProvider,
+    L: RetiredList,
+    T: ReclaimTrigger,
+{
+    #[inline]
+    pub fn new(slots: S, retired: L, trigger: T) -> Self {
+        Self::new_in(slots, retired, trigger, Global)
+    }
+}
+
+impl<'dom, S, L, T, A> BackendDomain<'dom, S, L, T, A>
+where
+    S: SlotProvider,
+    L: RetiredList,
+    T: ReclaimTrigger,
+    A: Allocator,
+{
+    #[inline]
+    pub fn new_in(slots: S, retired: L, trigger: T, allocator: A) -> Self {
+        Self {
+            slots,
+            retired,
+            trigger,
+            allocator,
+            metrics: MetricsRecorder::new(),
+            __mk: PhantomData,
+        }
+    }
+
+    /// Returns a handle borrowing this domain, which lives no longer than the borrow, like
+    /// [`ScopedDomain::handle`][crate::domain::scoped::ScopedDomain::handle].
+    ///
+    /// # Safety
+    ///
+    /// * Values retired through the handle must not borrow data that may be dropped before the
+    /// domain is.
+    ///
+    #[inline]
+    pub unsafe fn handle(&self) -> BackendDomainRef<'_, S, L, T, A> {
+        BackendDomainRef(self)
+    }
+
+    #[inline]
+    pub fn slots(&self) -> &S {
+        &self.slots
+    }
+
+    #[inline]
+    pub fn retired(&self) -> &L {
+        &self.retired
+    }
+
+    #[inline]
+    pub fn trigger(&self) -> &T {
+        &self.trigger
+    }
+
+    /// Reclaims every retired hazard that is no longer protected, returning how many were.
+    pub fn reclaim(&self) -> usize {
+        self.reclaim_into(|hazard| {
+            // Safety: The hazard is not protected and was allocated using our allocator.
+            drop(unsafe { Box::from_raw_in(hazard.as_ptr(), &self.allocator) })
+        })
+    }
+
+    /// Passes every unprotected hazard to `reclaim`, which takes ownership of it.
+    fn reclaim_into<R>(&self, mut reclaim: R) -> usize
+    where
+        R: FnMut(NonNull<dyn Hazard<'static>>),
+    {
+        // Taken before scanning, since a reader may still protect a hazard after its HazPtr was
+        // visited, as long as the hazard wasn't retired yet, thus the scan only covers those
+        // retired before it.
+        let mut retired = self.retired.take();
+        if retired.is_empty() {
+            return 0;
+        }
+        self.metrics.reclaim_pass();
+
+        crate::asymmetric_fence::heavy();
+
+        let mut guarded = Vec::new();
+        let mut complete = true;
+        self.slots.visit(&mut |hp| {
+            if guarded.try_reserve(1).is_ok() {
+                guarded.push(hp.ptr() as *const u8);
+            } else {
+                complete = false;
+            }
+        });
+
+        let protected = match GuardedSet::try_collect(guarded).filter(|_| complete) {
+            Some(guarded) => {
+                partition_in_place(&mut retired, |hazard| guarded.contains(hazard.addr()))
+            }
+            // Slower, but reclaiming must not abort when the scratch set can't be allocated.
+            None => partition_in_place(&mut retired, |hazard| {
+                let mut found = false;
+                self.slots
+                    .visit(&mut |hp| found |= ptr::eq(hp.ptr() as *const u8, hazard.addr()));
+                found
+            }),
+        };
+
+        let mut bytes = 0;
+        let reclaimed = retired.len() - protected;
+        // Reclaiming drops the hazards, which may retire more of them, thus the list isn't held.
+        retired.drain(protected..).for_each(|hazard| {
+            bytes += hazard_size(hazard.0);
+            reclaim(hazard.0)
+        });
+        self.retired.restore(retired);
+
+        self.metrics.reclaimed(reclaimed, bytes);
+        reclaimed
+    }
+}
+
+// Safety: The handle only borrows the domain.
+unsafe impl<'h, 'dom, S, L, T, A> DomainOwner<'h> for BackendDomain<'dom, S, L, T, A>
+where
+    S: SlotProvider + 'h,
+    L: RetiredList + 'h,
+    T: ReclaimTrigger + 'h,
+    A: Allocator + 'h,
+{
+    type Handle = BackendDomainRef<'h, S, L, T, A>;
+
+    #[inline]
+    unsafe fn handle(&'h self) -> Self::Handle {
+        BackendDomainRef(self)
+    }
+}
+
+impl<'dom, S, L, T, A> Drop for BackendDomain<'dom, S, L, T, A>
+where
+    S: SlotProvider,
+    L: RetiredList,
+    T: ReclaimTrigger,
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        self.reclaim();
+    }
+}
+
+impl<'dom, S, L, T, A> fmt::Debug for BackendDomain<'dom, S, L, T, A>
+where
+    S: SlotProvider + fmt::Debug,
+    L: RetiredList + fmt::Debug,
+    T: ReclaimTrigger + fmt::Debug,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackendDomain")
+            .field("slots", &self.slots)
+            .field("retired", &self.retired)
+            .field("trigger", &self.trigger)
+            .finish()
+    }
+}
+
+pub struct BackendDomainRef<'dom, S, L, T, A = Global>(&'dom BackendDomain<'dom, S, L, T, A>)
+where
+    S: SlotProvider,
+    L: RetiredList,
+    T: ReclaimTrigger,
+    A: Allocator;
+
+impl<'dom, S, L, T, A> Eq for BackendDomainRef<'dom, S, L, T, A>
+where
+    S: SlotProvider,
+    L: RetiredList,
+    T: ReclaimTrigger,
+    A: Allocator,
+{
+}
+
+impl<'dom, S, L, T, A> Copy for BackendDomainRef<'dom, S, L, T, A>
+where
+    S: SlotProvider,
+    L: RetiredList,
+    T: ReclaimTrigger,
+    A: Allocator,
+{
+}
+
+impl<'dom, S, L, T, A> PartialEq for BackendDomainRef<'dom, S, L, T, A>
+where
+    S: SlotProvider,
+    L: RetiredList,
+    T: ReclaimTrigger,
+    A: Allocator,
+{
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.0, other.0)
+    }
+}
+
+impl<'dom, S, L, T, A> Clone for BackendDomainRef<'dom, S, L, T, A>
+where
+    S: SlotProvider,
+    L: RetiredList,
+    T: ReclaimTrigger,
+    A: Allocator,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'dom, S, L, T, A> fmt::Debug for BackendDomainRef<'dom, S, L, T, A>
+where
+    S: SlotProvider + fmt::Debug,
+    L: RetiredList + fmt::Debug,
+    T: ReclaimTrigger + fmt::Debug,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+unsafe impl<'dom, S, L, T, A> Domain<'dom> for BackendDomainRef<'dom, S, L, T, A>
+where
+    S: SlotProvider,
+    L: RetiredList,
+    T: ReclaimTrigger,
+    A: Allocator,
+{
+    type Alloc = A;
+
+    #[inline]
+    fn allocator(self) -> &'dom Self::Alloc {
+        &self.0.allocator
+    }
+
+    #[inline]
+    fn acquire(self) -> Option<&'dom HazPtr> {
+        if failpoints::hit(Failpoint::Acquire) {
+            return None;
+        }
+        self.0.slots.acquire()
+    }
+
+    #[inline]
+    fn release(self, hazptr: &'dom HazPtr) {
+        self.0.slots.release(hazptr)
+    }
+
+    #[inline]
+    fn allocated(self, bytes: usize) {
+        self.0.metrics.allocated(bytes)
+    }
+
+    #[inline]
+    fn deallocated(self, bytes: usize) {
+        self.0.metrics.deallocated(bytes)
+    }
+
+    unsafe fn retire(self, retired: NonNull<dyn Hazard<'dom>>) {
+        published::check_retired(retired);
+        self.0.metrics.retired(1, hazard_size(retired));
+
+        // Safety: Only the lifetime is erased, the hazard is dropped before the domain, thus
+        // before 'dom ends.
+        let retired = Retired(unsafe {
+            mem::transmute::<NonNull<dyn Hazard<'dom>>, NonNull<dyn Hazard<'static>>>(retired)
+        });
+        let count = self.0.retired.push(retired);
+        if self.0.trigger.should_reclaim(count, self.0.slots.count()) {
+            self.0.reclaim();
+        }
+    }
+
+    #[inline]
+    fn eager_reclaim(self) -> usize {
+        self.0.reclaim()
+    }
+
+    fn drain_reclaimable(self) -> vec::IntoIter<Reclaimed<'dom, Self::Alloc>> {
+        let mut drained = Vec::new();
+        self.0.reclaim_into(|hazard| {
+            // Safety: Restores the lifetime erased in retire, the hazard is not protected and was
+            // allocated using our allocator.
+            drained.push(unsafe {
+                let hazard = mem::transmute::<
+                    NonNull<dyn Hazard<'static>>,
+                    NonNull<dyn Hazard<'dom>>,
+                >(hazard);
+                Box::from_raw_in(hazard.as_ptr(), self.allocator())
+            })
+        });
+        drained.into_iter()
+    }
+
+    #[inline]
+    fn visit_hazptrs(self, f: &mut dyn FnMut(&'dom HazPtr)) -> bool {
+        self.0.slots.visit(f);
+        true
+    }
+
+    fn metrics(self) -> MetricsSnapshot {
+        self.0.metrics.snapshot(MetricsSnapshot {
+            hazptrs_total: self.0.slots.count(),
+            ..MetricsSnapshot::default()
+        })
+    }
+}
@@ -0,0 +1,290 @@
+use std::{
+    fmt,
+    process,
+    ptr::NonNull,
+    sync::atomic::{
+        AtomicU64,
+        AtomicUsize,
+        Ordering,
+    },
+    time::Duration,
+    vec,
+};
+
+use crate::{
+    domain::{
+        Domain,
+        Reclaimed,
+    },
+    hazptr::HazPtr,
+    metrics::MetricsSnapshot,
+    watchdog::{
+        self,
+        StuckGuard,
+    },
+    Hazard,
+};
+
+/// What a [`BoundedDomain`] does with a protection held for longer than allowed.
+#[derive(Copy, Clone, Debug)]
+pub enum Enforcement {
+    /// Calls the function with the protection, e.g. to kill the offending task.
+    Callback(fn(&StuckGuard)),
+    /// Prints the protection to stderr and aborts the process.
+    Abort,
+}
+
+/// Number of hazards retired to domains under the same [`Bounds`] between two enforcements.
+const BOUNDED_RETIRED_THRESHOLD: usize = 1000;
+
+/// Maximum hold time of the protections of [`BoundedDomains`][BoundedDomain], and what is done
+/// with those exceeding it.
+///
+/// Shared by every copy of the domains created with it, which only enforce it on protections
+/// acquired through them, and are thus unaffected by unrelated users of the domains they wrap.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use anchorage::domain::{
+/// #     bounded::{BoundedDomain, Bounds, Enforcement},
+/// #     global::GlobalDomain,
+/// # };
+/// static BOUNDS: Bounds = Bounds::new(Duration::from_millis(50), Enforcement::Abort);
+///
+/// let domain = BoundedDomain::new(GlobalDomain, &BOUNDS);
+/// ```
+///
+pub struct Bounds {
+    max_hold: Duration,
+    enforcement: Enforcement,
+    /// Hazards retired since the bounds were last enforced.
+    retired: AtomicUsize,
+    /// When the bounds were last enforced, as returned by [`watchdog::now`].
+    enforced_at: AtomicU64,
+}
+
+impl Bounds {
+    #[inline]
+    pub const fn new(max_hold: Duration, enforcement: Enforcement) -> Self {
+        Self {
+            max_hold,
+            enforcement,
+            retired: AtomicUsize::new(0),
+            enforced_at: AtomicU64::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn max_hold(&self) -> Duration {
+        self.max_hold
+    }
+
+    #[inline]
+    pub fn enforcement(&self) -> Enforcement {
+        self.enforcement
+    }
+
+    /// Address tagging the [`HazPtrs`][HazPtr] acquired under these bounds.
+    #[inline]
+    fn addr(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Counts `count` hazards as retired, returning whether the bounds are due to be enforced,
+    /// because enough were since they last were, or because the maximum hold time has elapsed.
+    fn retired(&self, count: usize) -> bool {
+        let retired = self.retired.fetch_add(count, Ordering::Relaxed) + count;
+        let elapsed = watchdog::now().saturating_sub(self.enforced_at.load(Ordering::Relaxed));
+        retired >= BOUNDED_RETIRED_THRESHOLD || elapsed >= self.max_hold.as_nanos() as u64
+    }
+}
+
+impl fmt::Debug for Bounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bounds")
+            .field("max_hold", &self.max_hold)
+            .field("enforcement", &self.enforcement)
+            .finish()
+    }
+}
+
+/// Domain bounding how long its [`HazPtrs`][HazPtr] may protect the same address, for
+/// deployments where the memory retained by reclamation must have an analyzable worst case.
+///
+/// Wraps another domain, and checks the protections acquired through it against the maximum hold
+/// time of its [`Bounds`], [enforcing] it on those exceeding it. Protections acquired directly from
+/// the wrapped domain, or through a bounded domain with other bounds, are left alone.
+///
+/// Checking means scanning every [`HazPtr`] of the wrapped domain, thus it is only done on
+/// reclamation passes, like [`Domain::eager_reclaim`], and when retiring once enough hazards were
+/// retired under the same bounds, or once the maximum hold time has elapsed since the last check.
+/// Enforcement can also be triggered periodically with [`BoundedDomain::enforce`].
+///
+/// Protection times are those recorded by the [watchdog][crate::watchdog], and domains that can't
+/// [enumerate] their [`HazPtrs`][HazPtr] can't be bounded.
+///
+/// [enforcing]: Enforcement
+/// [enumerate]: Domain::visit_hazptrs
+///
+#[derive(Copy, Clone)]
+pub struct BoundedDomain<'b, D> {
+    inner: D,
+    bounds: &'b Bounds,
+}
+
+impl<'b, D> BoundedDomain<'b, D> {
+    #[inline]
+    pub fn new(inner: D, bounds: &'b Bounds) -> Self {
+        Self { inner, bounds }
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn bounds(&self) -> &'b Bounds {
+        self.bounds
+    }
+
+    /// Enforces the maximum hold time on every protection acquired under the same bounds that
+    /// exceeds it, returning how many did.
+    pub fn enforce<'dom>(self) -> usize
+    where
+        D: Domain<'dom>,
+    {
+        let bounds = self.bounds;
+        bounds.retired.store(0, Ordering::Relaxed);
+        bounds.enforced_at.store(watchdog::now(), Ordering::Relaxed);
+
+        let mut exceeded = 0;
+        self.inner.visit_hazptrs(&mut |hazptr| {
+            if hazptr.bounds() != bounds.addr() {
+                return;
+            }
+            if let Some(guard) = watchdog::stuck_guard(hazptr, bounds.max_hold) {
+                exceeded += 1;
+                match bounds.enforcement {
+                    Enforcement::Callback(callback) => callback(&guard),
+                    Enforcement::Abort => {
+                        watchdog::log_stuck(&guard);
+                        process::abort();
+                    }
+                }
+            }
+        });
+        exceeded
+    }
+}
+
+impl<'b, D> Eq for BoundedDomain<'b, D> where D: Eq {}
+
+impl<'b, D> PartialEq for BoundedDomain<'b, D>
+where
+    D: PartialEq,
+{
+    /// Bounded domains wrapping equal domains are equivalent regardless of their bounds, which
+    /// only apply to the protections acquired through them.
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<'b, D> fmt::Debug for BoundedDomain<'b, D>
+where
+    D: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedDomain")
+            .field("inner", &self.inner)
+            .field("bounds", &self.bounds)
+            .finish()
+    }
+}
+
+unsafe impl<'b, 'dom, D> Domain<'dom> for BoundedDomain<'b, D>
+where
+    D: Domain<'dom>,
+    'b: 'dom,
+{
+    type Alloc = D::Alloc;
+
+    #[inline]
+    fn allocator(self) -> &'dom Self::Alloc {
+        self.inner.allocator()
+    }
+
+    #[inline]
+    fn acquire(self) -> Option<&'dom HazPtr> {
+        let hazptr = self.inner.acquire()?;
+        hazptr.set_bounds(self.bounds.addr());
+        Some(hazptr)
+    }
+
+    #[inline]
+    fn release(self, hazptr: &'dom HazPtr) {
+        hazptr.set_bounds(0);
+        self.inner.release(hazptr)
+    }
+
+    #[inline]
+    fn allocated(self, bytes: usize) {
+        self.inner.allocated(bytes)
+    }
+
+    #[inline]
+    fn deallocated(self, bytes: usize) {
+        self.inner.deallocated(bytes)
+    }
+
+    unsafe fn retire(self, retired: NonNull<dyn Hazard<'dom>>) {
+        if self.bounds.retired(1) {
+            self.enforce();
+        }
+        // Safety: Upheld by the caller.
+        unsafe { self.inner.retire(retired) }
+    }
+
+    unsafe fn retire_all<I>(self, retired: I)
+    where
+        I: IntoIterator<Item = NonNull<dyn Hazard<'dom>>>,
+    {
+        let mut count = 0;
+        // Safety: Upheld by the caller.
+        unsafe {
+            self.inner
+                .retire_all(retired.into_iter().inspect(|_| count += 1))
+        }
+        if self.bounds.retired(count) {
+            self.enforce();
+        }
+    }
+
+    #[inline]
+    fn eager_reclaim(self) -> usize {
+        self.enforce();
+        self.inner.eager_reclaim()
+    }
+
+    #[inline]
+    fn drain_reclaimable(self) -> vec::IntoIter<Reclaimed<'dom, Self::Alloc>> {
+        self.enforce();
+        self.inner.drain_reclaimable()
+    }
+
+    #[inline]
+    fn verify(self) {
+        self.inner.verify()
+    }
+
+    #[inline]
+    fn visit_hazptrs(self, f: &mut dyn FnMut(&'dom HazPtr)) -> bool {
+        self.inner.visit_hazptrs(f)
+    }
+
+    #[inline]
+    fn metrics(self) -> MetricsSnapshot {
+        self.inner.metrics()
+    }
+}
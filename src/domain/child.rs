@@ -0,0 +1,361 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    mem,
+    ptr::{
+        self,
+        NonNull,
+    },
+    sync::{
+        Mutex,
+        MutexGuard,
+    },
+    vec,
+};
+
+use crate::{
+    domain::{
+        global::GlobalDomain,
+        Domain,
+        DomainOwner,
+        Reclaimed,
+    },
+    guarded::{
+        merge,
+        partition_in_place,
+        GuardedSet,
+    },
+    hazptr::HazPtr,
+    metrics::{
+        hazard_size,
+        MetricsSnapshot,
+    },
+    published,
+    Hazard,
+};
+
+/// Number of hazards retired to a child before it first tries to reclaim them.
+const CHILD_RETIRED_THRESHOLD: usize = 64;
+
+struct ChildState {
+    /// HazPtrs acquired from the parent by the child's anchors and not yet released.
+    hazptrs: Vec<&'static HazPtr>,
+    /// Hazards retired to the domain, with their lifetime erased from 'dom so that the domain
+    /// stays covariant over it.
+    retired: Vec<NonNull<dyn Hazard<'static>>>,
+    reclaim_at: usize,
+}
+
+impl ChildState {
+    /// Moves the retired hazards not protected by any of the child's HazPtrs to the end of
+    /// `retired`, returning how many are still protected.
+    fn partition(&mut self, retired: &mut [NonNull<dyn Hazard<'static>>]) -> usize {
+        crate::asymmetric_fence::heavy();
+
+        let hazptrs = &self.hazptrs;
+        let protected =
+            match GuardedSet::try_collect(hazptrs.iter().map(|hp| hp.ptr() as *const u8)) {
+                Some(guarded) => partition_in_place(retired, |hazard| {
+                    guarded.contains(hazard.as_ptr() as *const u8)
+                }),
+                // Slower, but reclaiming must not abort when the scratch set can't be allocated.
+                None => partition_in_place(retired, |hazard| {
+                    hazptrs
+                        .iter()
+                        .any(|hp| ptr::eq(hp.ptr() as *const u8, hazard.as_ptr() as *const u8))
+                }),
+            };
+
+        self.reclaim_at = CHILD_RETIRED_THRESHOLD.max(2 * protected);
+        protected
+    }
+}
+
+/// Short-lived domain created from a long-lived parent, such as a per-request or per-task domain
+/// inside a service.
+///
+/// Anchors of the child protect through [`HazPtrs`][HazPtr] acquired from the parent, while the
+/// hazards retired to the child are kept and reclaimed by the child itself. When the child is
+/// dropped, every hazard that is no longer protected is reclaimed right away, and the ones still
+/// protected, which can only happen if an [`Anchor`][crate::anchor::Anchor] was leaked, are
+/// transferred to the parent instead of blocking the drop or leaking.
+///
+/// ```
+/// # use anchorage::{domain::child::ChildDomain, hazbox::HazBox};
+/// let child = ChildDomain::new();
+/// // Safety: Request paths own their data.
+/// let path = HazBox::new_in(String::from("/"), unsafe { child.handle() });
+/// path.set(String::from("/health"));
+/// ```
+///
+pub struct ChildDomain<'dom, P = GlobalDomain>
+where
+    P: Domain<'static>,
+{
+    parent: P,
+    state: Mutex<ChildState>,
+    __mk: PhantomData<&'dom ()>,
+}
+
+// Safety: All access to the retired hazards is serialized by the mutex, and hazards are Send + Sync.
+unsafe impl<'dom, P> Send for ChildDomain<'dom, P> where P: Domain<'static> + Send {}
+unsafe impl<'dom, P> Sync for ChildDomain<'dom, P> where P: Domain<'static> + Sync {}
+
+impl<'dom> ChildDomain<'dom, GlobalDomain> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(GlobalDomain)
+    }
+}
+
+impl<'dom> Default for ChildDomain<'dom, GlobalDomain> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'dom, P> ChildDomain<'dom, P>
+where
+    P: Domain<'static>,
+{
+    pub fn new_in(parent: P) -> Self {
+        Self {
+            parent,
+            state: Mutex::new(ChildState {
+                hazptrs: Vec::new(),
+                retired: Vec::new(),
+                reclaim_at: CHILD_RETIRED_THRESHOLD,
+            }),
+            __mk: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn parent(&self) -> P {
+        self.parent
+    }
+
+    /// Returns a handle borrowing this domain, which lives no longer than the borrow, like
+    /// [`ScopedDomain::handle`][crate::domain::scoped::ScopedDomain::handle].
+    ///
+    /// # Safety
+    ///
+    /// * Values retired through the handle must not borrow data that may be dropped before the
+    /// domain is.
+    ///
+    #[inline]
+    pub unsafe fn handle(&self) -> ChildDomainRef<'_, P> {
+        ChildDomainRef(self)
+    }
+
+    /// Reclaims every retired hazard that is no longer protected, returning how many were.
+    pub fn reclaim(&self) -> usize {
+        self.reclaim_into(|hazard| {
+            // Safety: The hazard is not protected and was allocated using the parent's allocator.
+            drop(unsafe { Box::from_raw_in(hazard.as_ptr(), self.parent.allocator()) })
+        })
+    }
+
+    /// Passes every unprotected hazard to `reclaim`, which takes ownership of it.
+    fn reclaim_into<R>(&self, mut reclaim: R) -> usize
+    where
+        R: FnMut(NonNull<dyn Hazard<'static>>),
+    {
+        let (mut retired, protected) = {
+            let mut state = self.lock();
+            let mut retired = mem::take(&mut state.retired);
+            let protected = state.partition(&mut retired);
+            (retired, protected)
+        };
+
+        // Dropped outside of the lock, since their destructors may retire more of them.
+        let reclaimed = retired.len() - protected;
+        retired.drain(protected..).for_each(|hazard| {
+            // Accounted for by the parent until reclaimed, see ChildDomainRef::allocated.
+            self.parent.deallocated(hazard_size(hazard));
+            reclaim(hazard)
+        });
+
+        merge(&mut self.lock().retired, retired);
+        reclaimed
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ChildState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+// Safety: The handle only borrows the domain.
+unsafe impl<'h, 'dom, P> DomainOwner<'h> for ChildDomain<'dom, P>
+where
+    P: Domain<'static>,
+{
+    type Handle = ChildDomainRef<'h, P>;
+
+    #[inline]
+    unsafe fn handle(&'h self) -> Self::Handle {
+        ChildDomainRef(self)
+    }
+}
+
+impl<'dom, P> Drop for ChildDomain<'dom, P>
+where
+    P: Domain<'static>,
+{
+    fn drop(&mut self) {
+        self.reclaim();
+
+        let protected = mem::take(&mut self.lock().retired);
+        // Safety: The hazards were allocated using the parent's allocator. They are protected by
+        // HazPtrs of leaked anchors, which are never reset again, thus the parent never drops them
+        // and their erased lifetime can't be observed.
+        unsafe { self.parent.retire_all(protected) }
+    }
+}
+
+impl<'dom, P> fmt::Debug for ChildDomain<'dom, P>
+where
+    P: Domain<'static> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("ChildDomain")
+            .field("parent", &self.parent)
+            .field("hazptrs_active", &state.hazptrs.len())
+            .field("retired", &state.retired.len())
+            .finish()
+    }
+}
+
+pub struct ChildDomainRef<'dom, P = GlobalDomain>(&'dom ChildDomain<'dom, P>)
+where
+    P: Domain<'static>;
+
+impl<'dom, P> Eq for ChildDomainRef<'dom, P> where P: Domain<'static> {}
+
+impl<'dom, P> Copy for ChildDomainRef<'dom, P> where P: Domain<'static> {}
+
+impl<'dom, P> PartialEq for ChildDomainRef<'dom, P>
+where
+    P: Domain<'static>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.0, other.0)
+    }
+}
+
+impl<'dom, P> Clone for ChildDomainRef<'dom, P>
+where
+    P: Domain<'static>,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'dom, P> fmt::Debug for ChildDomainRef<'dom, P>
+where
+    P: Domain<'static> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+unsafe impl<'dom, P> Domain<'dom> for ChildDomainRef<'dom, P>
+where
+    P: Domain<'static>,
+{
+    type Alloc = P::Alloc;
+
+    #[inline]
+    fn allocator(self) -> &'dom Self::Alloc {
+        self.0.parent.allocator()
+    }
+
+    fn acquire(self) -> Option<&'dom HazPtr> {
+        let hazptr = self.0.parent.acquire()?;
+        self.0.lock().hazptrs.push(hazptr);
+        Some(hazptr)
+    }
+
+    fn release(self, hazptr: &'dom HazPtr) {
+        let released = {
+            let mut state = self.0.lock();
+            let slot = state
+                .hazptrs
+                .iter()
+                .position(|&hp| ptr::eq(hp, hazptr))
+                .expect("HazPtr was not acquired from this domain");
+            state.hazptrs.swap_remove(slot)
+        };
+        self.0.parent.release(released);
+    }
+
+    /// Attributed to the parent, which shares its allocator, and reclaims whatever is still
+    /// retired to the child once it is dropped.
+    #[inline]
+    fn allocated(self, bytes: usize) {
+        self.0.parent.allocated(bytes)
+    }
+
+    #[inline]
+    fn deallocated(self, bytes: usize) {
+        self.0.parent.deallocated(bytes)
+    }
+
+    unsafe fn retire(self, retired: NonNull<dyn Hazard<'dom>>) {
+        published::check_retired(retired);
+
+        let reclaim = {
+            let mut state = self.0.lock();
+            // Safety: Only the lifetime is erased, the hazard is dropped before the domain, thus
+            // before 'dom ends, unless it is transferred to the parent, see ChildDomain::drop.
+            state.retired.push(unsafe {
+                mem::transmute::<NonNull<dyn Hazard<'dom>>, NonNull<dyn Hazard<'static>>>(retired)
+            });
+            state.retired.len() >= state.reclaim_at
+        };
+        if reclaim {
+            self.0.reclaim();
+        }
+    }
+
+    #[inline]
+    fn eager_reclaim(self) -> usize {
+        self.0.reclaim()
+    }
+
+    fn drain_reclaimable(self) -> vec::IntoIter<Reclaimed<'dom, Self::Alloc>> {
+        let mut drained = Vec::new();
+        self.0.reclaim_into(|hazard| {
+            // Safety: Restores the lifetime erased in retire, the hazard is not protected and was
+            // allocated using the parent's allocator.
+            drained.push(unsafe {
+                let hazard = mem::transmute::<
+                    NonNull<dyn Hazard<'static>>,
+                    NonNull<dyn Hazard<'dom>>,
+                >(hazard);
+                Box::from_raw_in(hazard.as_ptr(), self.allocator())
+            })
+        });
+        drained.into_iter()
+    }
+
+    fn visit_hazptrs(self, f: &mut dyn FnMut(&'dom HazPtr)) -> bool {
+        let hazptrs = self.0.lock().hazptrs.clone();
+        hazptrs.into_iter().for_each(f);
+        true
+    }
+
+    fn metrics(self) -> MetricsSnapshot {
+        let state = self.0.lock();
+        MetricsSnapshot {
+            hazptrs_total: state.hazptrs.len(),
+            hazptrs_active: state.hazptrs.len(),
+            retired: state.retired.len(),
+            ..MetricsSnapshot::default()
+        }
+    }
+}
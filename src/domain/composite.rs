@@ -0,0 +1,278 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    ptr::NonNull,
+    vec,
+};
+
+use crate::{
+    domain::{
+        global::GlobalDomain,
+        local::ThreadLocalDomain,
+        Domain,
+        Reclaimed,
+    },
+    hazptr::HazPtr,
+    metrics::{
+        hazard_size,
+        MetricsSnapshot,
+    },
+    Hazard,
+};
+
+/// Which domain of a [`CompositeDomain`] a retired [`Hazard`] is sent to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Route {
+    First,
+    Second,
+}
+
+/// Domain routing each retirement to one of two underlying domains, configured once with a routing
+/// function and then used through the normal [`Domain`] trait.
+///
+/// The routing function sees the retired [`Hazard`] itself, e.g. to send large buffers to a domain
+/// that reclaims them in bulk and small nodes to one that reclaims them inline:
+///
+/// ```
+/// # use anchorage::{domain::composite::{CompositeDomain, Route}, hazbox::HazBox};
+/// let domain = CompositeDomain::new(|hazard| {
+///     if std::mem::size_of_val(hazard) > 4096 {
+///         Route::Second
+///     } else {
+///         Route::First
+///     }
+/// });
+/// let buffer = HazBox::new_in([0_u8; 8192], domain);
+/// buffer.set([1; 8192]);
+/// ```
+///
+/// [`HazPtrs`][HazPtr] are always acquired from the first domain, thus the second one must take
+/// them into account when reclaiming, see [`CompositeDomain::new_unchecked`].
+///
+pub struct CompositeDomain<'dom, A, B>
+where
+    A: Domain<'dom>,
+    B: Domain<'dom, Alloc = A::Alloc>,
+{
+    first: A,
+    second: B,
+    route: fn(&(dyn Hazard<'dom> + 'dom)) -> Route,
+    __mk: PhantomData<&'dom A>,
+}
+
+impl CompositeDomain<'static, ThreadLocalDomain, GlobalDomain> {
+    /// Routes retirements between the [`ThreadLocalDomain`], reclaiming inline on the retiring
+    /// thread, and the [`GlobalDomain`], reclaiming in bulk. Both share their
+    /// [`HazPtrs`][HazPtr].
+    #[inline]
+    pub fn new(route: fn(&(dyn Hazard<'static> + 'static)) -> Route) -> Self {
+        // Safety: The ThreadLocalDomain protects through the HazPtrs of the GlobalDomain, and both
+        // allocate with Global.
+        unsafe { Self::new_unchecked(ThreadLocalDomain, GlobalDomain, route) }
+    }
+}
+
+impl<'dom, A, B> CompositeDomain<'dom, A, B>
+where
+    A: Domain<'dom>,
+    B: Domain<'dom, Alloc = A::Alloc>,
+{
+    /// Creates a domain routing retirements between `first` and `second` with `route`.
+    ///
+    /// # Safety
+    ///
+    /// * `second` must not drop a retired [`Hazard`] while any [`HazPtr`] acquired from `first` is
+    /// protecting it, i.e. both must share their [`HazPtrs`][HazPtr].
+    ///
+    /// * Storage allocated by the allocator of `first` must be able to be deallocated by the
+    /// allocator of `second`.
+    ///
+    #[inline]
+    pub unsafe fn new_unchecked(
+        first: A,
+        second: B,
+        route: fn(&(dyn Hazard<'dom> + 'dom)) -> Route,
+    ) -> Self {
+        Self {
+            first,
+            second,
+            route,
+            __mk: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn first(&self) -> A {
+        self.first
+    }
+
+    #[inline]
+    pub fn second(&self) -> B {
+        self.second
+    }
+
+    #[inline]
+    fn route(&self, hazard: NonNull<dyn Hazard<'dom> + 'dom>) -> Route {
+        // Safety: Retired hazards are valid until they are reclaimed.
+        (self.route)(unsafe { hazard.as_ref() })
+    }
+
+    /// Moves the size of `hazard` from the allocations of the first domain to those of the second,
+    /// which accounts for it once it is retired there.
+    #[inline]
+    fn transfer(&self, hazard: NonNull<dyn Hazard<'dom> + 'dom>) {
+        let bytes = hazard_size(hazard);
+        self.first.deallocated(bytes);
+        self.second.allocated(bytes);
+    }
+}
+
+impl<'dom, A, B> Copy for CompositeDomain<'dom, A, B>
+where
+    A: Domain<'dom>,
+    B: Domain<'dom, Alloc = A::Alloc>,
+{
+}
+
+impl<'dom, A, B> Clone for CompositeDomain<'dom, A, B>
+where
+    A: Domain<'dom>,
+    B: Domain<'dom, Alloc = A::Alloc>,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'dom, A, B> Eq for CompositeDomain<'dom, A, B>
+where
+    A: Domain<'dom>,
+    B: Domain<'dom, Alloc = A::Alloc>,
+{
+}
+
+impl<'dom, A, B> PartialEq for CompositeDomain<'dom, A, B>
+where
+    A: Domain<'dom>,
+    B: Domain<'dom, Alloc = A::Alloc>,
+{
+    /// Composites of equal domains are equivalent regardless of how they route retirements.
+    fn eq(&self, other: &Self) -> bool {
+        self.first == other.first && self.second == other.second
+    }
+}
+
+impl<'dom, A, B> fmt::Debug for CompositeDomain<'dom, A, B>
+where
+    A: Domain<'dom> + fmt::Debug,
+    B: Domain<'dom, Alloc = A::Alloc> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompositeDomain")
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .finish()
+    }
+}
+
+unsafe impl<'dom, A, B> Domain<'dom> for CompositeDomain<'dom, A, B>
+where
+    A: Domain<'dom>,
+    B: Domain<'dom, Alloc = A::Alloc>,
+{
+    type Alloc = A::Alloc;
+
+    #[inline]
+    fn allocator(self) -> &'dom Self::Alloc {
+        self.first.allocator()
+    }
+
+    #[inline]
+    fn acquire(self) -> Option<&'dom HazPtr> {
+        self.first.acquire()
+    }
+
+    #[inline]
+    fn release(self, hazptr: &'dom HazPtr) {
+        self.first.release(hazptr)
+    }
+
+    /// Attributed to the first domain until retired to the second one, if it is.
+    #[inline]
+    fn allocated(self, bytes: usize) {
+        self.first.allocated(bytes)
+    }
+
+    #[inline]
+    fn deallocated(self, bytes: usize) {
+        self.first.deallocated(bytes)
+    }
+
+    unsafe fn retire(self, retired: NonNull<dyn Hazard<'dom>>) {
+        // Safety: Upheld by the caller and by the constructor.
+        match self.route(retired) {
+            Route::First => unsafe { self.first.retire(retired) },
+            Route::Second => {
+                self.transfer(retired);
+                unsafe { self.second.retire(retired) }
+            }
+        }
+    }
+
+    unsafe fn retire_all<I>(self, retired: I)
+    where
+        I: IntoIterator<Item = NonNull<dyn Hazard<'dom>>>,
+    {
+        let mut second = Vec::new();
+        let first = retired.into_iter().filter(|&hazard| {
+            if self.route(hazard) == Route::First {
+                return true;
+            }
+            self.transfer(hazard);
+            if second.try_reserve(1).is_ok() {
+                second.push(hazard);
+            } else {
+                // Safety: Same as in retire.
+                // Retired on its own when the batch can't grow, rather than aborting.
+                unsafe { self.second.retire(hazard) }
+            }
+            false
+        });
+
+        // Safety: Same as in retire.
+        unsafe {
+            self.first.retire_all(first);
+            self.second.retire_all(second);
+        }
+    }
+
+    #[inline]
+    fn eager_reclaim(self) -> usize {
+        self.first.eager_reclaim() + self.second.eager_reclaim()
+    }
+
+    fn drain_reclaimable(self) -> vec::IntoIter<Reclaimed<'dom, Self::Alloc>> {
+        let mut drained = self.first.drain_reclaimable().collect::<Vec<_>>();
+        drained.extend(self.second.drain_reclaimable());
+        drained.into_iter()
+    }
+
+    #[inline]
+    fn verify(self) {
+        self.first.verify();
+        self.second.verify();
+    }
+
+    #[inline]
+    fn visit_hazptrs(self, f: &mut dyn FnMut(&'dom HazPtr)) -> bool {
+        self.first.visit_hazptrs(f)
+    }
+
+    /// Returns the metrics of the first domain, since domains sharing [`HazPtrs`][HazPtr] may
+    /// also share their counters. Those of the second domain can be retrieved through
+    /// [`CompositeDomain::second`].
+    #[inline]
+    fn metrics(self) -> MetricsSnapshot {
+        self.first.metrics()
+    }
+}
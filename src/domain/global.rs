@@ -1,63 +1,311 @@
 use std::{
     alloc::Global,
-    collections::HashSet,
-    convert::TryFrom,
-    iter,
+    cell::RefCell,
+    fmt,
     ptr,
     ptr::NonNull,
     sync::atomic::{
-        AtomicU64,
+        AtomicIsize,
         AtomicUsize,
         Ordering,
     },
+    time::{
+        Duration,
+        Instant,
+    },
+    vec,
+};
+#[cfg(feature = "timed-cleanup")]
+use std::{
+    convert::TryFrom,
+    sync::atomic::AtomicU64,
 };
 
 use crate::{
-    domain::Domain,
-    hazptr::HazPtr,
+    domain::{
+        Domain,
+        Reclaimed,
+    },
+    failpoints::{
+        self,
+        Failpoint,
+    },
+    guarded::GuardedSet,
+    hazptr::{
+        HazPtr,
+        HazPtrRecords,
+    },
+    metrics::{
+        hazard_size,
+        MetricsRecorder,
+        MetricsSnapshot,
+    },
     node_list::{
+        prefetch,
         List,
         Node,
     },
+    published,
     Hazard,
 };
 
+#[cfg(feature = "timed-cleanup")]
 const SYNC_TIME_PERIOD: u64 = std::time::Duration::from_nanos(2_000_000_000).as_nanos() as u64;
 const RETIRED_COUNT_THRESHOLD: isize = 1000;
 const HP_COUNT_MULTIPLIER: isize = 2;
 
-static GLOBAL: GlobalDomainStatic = GlobalDomainStatic::new();
+static GLOBAL: StaticDomain = StaticDomain::new();
+
+/// Number of [`HazPtrs`][HazPtr] of the [`GlobalDomain`] each thread keeps once released, so that
+/// acquiring them again is a thread local pop instead of a scan of the records.
+const CACHED_HAZPTRS: usize = 8;
+
+/// [`HazPtrs`][HazPtr] released by a thread, still marked as acquired but stashed from the active
+/// count, which are released for good once the thread exits.
+///
+/// Kept in place rather than in a vector, so that acquiring and releasing never allocate.
+struct HazPtrCache {
+    hazptrs: [Option<&'static HazPtr>; CACHED_HAZPTRS],
+    len: usize,
+}
+
+impl HazPtrCache {
+    #[inline]
+    fn pop(&mut self) -> Option<&'static HazPtr> {
+        self.len = self.len.checked_sub(1)?;
+        self.hazptrs[self.len].take()
+    }
+
+    /// Pushes `hazptr`, unless the cache is full.
+    #[inline]
+    fn try_push(&mut self, hazptr: &'static HazPtr) -> bool {
+        if self.len == CACHED_HAZPTRS {
+            return false;
+        }
+        self.hazptrs[self.len] = Some(hazptr);
+        self.len += 1;
+        true
+    }
+}
+
+impl Drop for HazPtrCache {
+    fn drop(&mut self) {
+        // Already stashed, thus only their flags are cleared.
+        while let Some(hazptr) = self.pop() {
+            hazptr.release();
+        }
+    }
+}
+
+thread_local! {
+    static HAZPTR_CACHE: RefCell<HazPtrCache> = const {
+        RefCell::new(HazPtrCache {
+            hazptrs: [None; CACHED_HAZPTRS],
+            len: 0,
+        })
+    };
+}
 
 const fn reached_threshold(retired_num: isize, hazptr_num: isize) -> bool {
     retired_num >= RETIRED_COUNT_THRESHOLD && retired_num >= HP_COUNT_MULTIPLIER * hazptr_num
 }
 
-struct GlobalDomainStatic {
-    hazptrs: List<HazPtr>,
+/// Threshold triggered reclamations only steal about half of the retired list, so a single
+/// unlucky retiring thread never inherits all the work of someone else's huge burst.
+const fn steal_limit(retired_num: isize) -> usize {
+    let half = retired_num / 2;
+    if half > RETIRED_COUNT_THRESHOLD {
+        half as usize
+    } else {
+        RETIRED_COUNT_THRESHOLD as usize
+    }
+}
+
+/// Number of retired hazards checked between two looks at the clock by a budgeted reclamation.
+const DEADLINE_CHECK_PERIOD: usize = 64;
+
+/// Limits how much work [`GlobalDomain::eager_reclaim_within`] may do, so that it can be run
+/// incrementally, e.g. from an admin endpoint, without stalling the process.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use anchorage::domain::global::{GlobalDomain, ReclaimBudget};
+/// let budget = ReclaimBudget::default()
+///     .max_objects(10_000)
+///     .max_duration(Duration::from_millis(5));
+/// let progress = GlobalDomain.eager_reclaim_within(budget);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ReclaimBudget {
+    max_objects: Option<usize>,
+    max_duration: Option<Duration>,
+}
+
+impl ReclaimBudget {
+    /// Sets the maximum number of retired hazards checked.
+    #[inline]
+    pub fn max_objects(mut self, max_objects: usize) -> Self {
+        self.max_objects = Some(max_objects);
+        self
+    }
+
+    /// Sets the maximum time spent checking retired hazards. It may be exceeded by the time it
+    /// takes to collect the protected addresses and to drop a few hazards.
+    #[inline]
+    pub fn max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+}
+
+/// Progress made by [`GlobalDomain::eager_reclaim_within`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ReclaimProgress {
+    /// Number of hazards reclaimed.
+    pub reclaimed: usize,
+    /// Number of hazards still retired afterwards, including those retired meanwhile.
+    pub remaining: usize,
+    /// Number of hazards checked but not reclaimed, since they were still protected.
+    pub blocked: usize,
+}
+
+/// State of a domain that lives in a `static`, reclaiming with [`Global`].
+///
+/// Backs the [`GlobalDomain`], and any domain declared with the [`domain!`][crate::domain!] macro.
+#[doc(hidden)]
+pub struct StaticDomain {
+    hazptrs: HazPtrRecords,
     retired: List<NonNull<dyn Hazard<'static>>>,
+    #[cfg(feature = "timed-cleanup")]
     sync_time: AtomicU64,
     nbulk_reclaims: AtomicUsize,
+    last_reclaimed: AtomicUsize,
+    last_still_retired: AtomicIsize,
+    metrics: MetricsRecorder,
 }
 
-impl GlobalDomainStatic {
+impl StaticDomain {
     pub const fn new() -> Self {
         Self {
-            hazptrs: List::new(),
+            hazptrs: HazPtrRecords::new(),
             retired: List::new(),
+            #[cfg(feature = "timed-cleanup")]
             sync_time: AtomicU64::new(0),
             nbulk_reclaims: AtomicUsize::new(0),
+            last_reclaimed: AtomicUsize::new(0),
+            last_still_retired: AtomicIsize::new(0),
+            metrics: MetricsRecorder::new(),
+        }
+    }
+
+    #[inline]
+    pub fn acquire(&self) -> Option<&HazPtr> {
+        if failpoints::hit(Failpoint::Acquire) {
+            return None;
+        }
+        self.hazptrs.acquire()
+    }
+
+    #[inline]
+    pub fn acquire_many<const N: usize>(&self) -> Option<[&HazPtr; N]> {
+        if failpoints::hit(Failpoint::Acquire) {
+            return None;
+        }
+        self.hazptrs.acquire_many()
+    }
+
+    #[inline]
+    pub fn release(&self, hazptr: &HazPtr) {
+        self.hazptrs.release(hazptr)
+    }
+
+    #[inline]
+    pub fn eager_reclaim(&self) -> usize {
+        self.bulk_reclaim(true, None)
+    }
+
+    #[inline]
+    pub fn allocated(&self, bytes: usize) {
+        self.metrics.allocated(bytes)
+    }
+
+    #[inline]
+    pub fn deallocated(&self, bytes: usize) {
+        self.metrics.deallocated(bytes)
+    }
+
+    /// Reclaims retired hazards that are no longer protected, in a single pass stopped once
+    /// `budget` runs out, leaving the hazards it didn't check for a later pass.
+    pub fn eager_reclaim_within(&self, budget: ReclaimBudget) -> ReclaimProgress {
+        let deadline = budget
+            .max_duration
+            .map(|duration| Instant::now() + duration);
+        let mut checked = 0;
+        let mut out_of_time = || {
+            checked += 1;
+            checked % DEADLINE_CHECK_PERIOD == 0
+                && deadline.is_some_and(|deadline| Instant::now() >= deadline)
+        };
+
+        let (reclaimed, blocked) =
+            self.bulk_reclaim_into(false, budget.max_objects, &mut out_of_time, |hazard| {
+                // Safety: The hazard is no longer protected, and it was allocated using Global.
+                drop(unsafe { Box::from_raw_in(hazard.as_ptr(), Global) })
+            });
+
+        ReclaimProgress {
+            reclaimed,
+            remaining: self.metrics().retired,
+            blocked,
         }
     }
 
-    fn try_acquire_existing(&self) -> Option<&HazPtr> {
-        self.hazptrs.iter().find(|hp| hp.try_acquire())
+    #[inline]
+    pub fn verify(&self) {
+        self.hazptrs.verify()
+    }
+
+    #[inline]
+    pub fn visit_hazptrs<'s>(&'s self, f: &mut dyn FnMut(&'s HazPtr)) -> bool {
+        self.hazptrs.iter().for_each(f);
+        true
+    }
+
+    #[inline]
+    pub fn metrics(&self) -> MetricsSnapshot {
+        self.metrics
+            .snapshot(self.hazptrs.metrics(MetricsSnapshot::default()))
     }
 
-    fn acquire_new(&self) -> &HazPtr {
-        self.hazptrs.push_front(HazPtr::new(true))
+    pub fn fmt_stats(&self, name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(name)
+            .field("hazptrs_active", &self.hazptrs.active())
+            .field("hazptrs_total", &self.hazptrs.count())
+            .field("retired", &self.retired.count.load())
+            .field(
+                "last_reclaimed",
+                &self.last_reclaimed.load(Ordering::Relaxed),
+            )
+            .field(
+                "last_still_retired",
+                &self.last_still_retired.load(Ordering::Relaxed),
+            )
+            .finish()
     }
 
-    fn retire(&self, retired: NonNull<dyn Hazard<'static>>) {
+    pub fn retire(&self, retired: NonNull<dyn Hazard<'static>>) {
+        published::check_retired(retired);
+        let bytes = hazard_size(retired);
+        self.metrics.retired(1, bytes);
+
+        if self.hazptrs.is_quiescent() {
+            // Safety: Nothing can be protecting the hazard, and it was allocated using Global.
+            drop(unsafe { Box::from_raw_in(retired.as_ptr(), Global) });
+            self.metrics.reclaimed(1, bytes);
+            return;
+        }
+
         self.retired.push_front(retired);
 
         // Folly has if check here, but only for recursion from bulk_lookup_and_reclaim,
@@ -65,18 +313,53 @@ impl GlobalDomainStatic {
         self.check_cleanup_and_reclaim();
     }
 
+    pub fn retire_all<I>(&self, retired: I)
+    where
+        I: IntoIterator<Item = NonNull<dyn Hazard<'static>>>,
+    {
+        let retired = retired
+            .into_iter()
+            .inspect(|hazard| published::check_retired(*hazard));
+
+        if self.hazptrs.is_quiescent() {
+            for hazard in retired {
+                let bytes = hazard_size(hazard);
+                self.metrics.retired(1, bytes);
+                // Safety: Same as in retire.
+                drop(unsafe { Box::from_raw_in(hazard.as_ptr(), Global) });
+                self.metrics.reclaimed(1, bytes);
+            }
+            return;
+        }
+
+        // Recorded as the nodes are created, before they are published to reclaimers.
+        let retired = retired
+            .into_iter()
+            .inspect(|&hazard| self.metrics.retired(1, hazard_size(hazard)));
+        if self.retired.push_all_front(retired) > 0 {
+            self.check_cleanup_and_reclaim();
+        }
+    }
+
     fn check_cleanup_and_reclaim(&self) {
+        #[cfg(feature = "timed-cleanup")]
         if self.try_timed_cleanup() {
             return;
         }
 
-        let retired_num = self.retired.count.load(Ordering::Acquire);
-        let hazptr_num = self.hazptrs.count.load(Ordering::Acquire);
+        // Checked on every retire, thus against the approximate counts, which only touch a
+        // single cache line each.
+        let retired_num = self.retired.count.approx();
+        let hazptr_num = self.hazptrs.approx_count();
         if reached_threshold(retired_num, hazptr_num) {
             self.try_bulk_reclaim();
         }
     }
 
+    /// Reclaims everything at least every [`SYNC_TIME_PERIOD`], even if the thresholds are never
+    /// reached. Compiled out without the `timed-cleanup` feature, so that retiring never reads the
+    /// clock, at the cost of retired hazards lingering until enough more of them are retired.
+    #[cfg(feature = "timed-cleanup")]
     fn try_timed_cleanup(&self) -> bool {
         if !self.check_sync_time() {
             return false;
@@ -85,6 +368,7 @@ impl GlobalDomainStatic {
         true
     }
 
+    #[cfg(feature = "timed-cleanup")]
     fn check_sync_time(&self) -> bool {
         let time = u64::try_from(
             std::time::SystemTime::now()
@@ -109,20 +393,22 @@ impl GlobalDomainStatic {
                 .is_ok()
     }
 
+    #[cfg(feature = "timed-cleanup")]
     fn relaxed_cleanup(&self) {
-        self.retired.count.store(0, Ordering::Release);
-        self.bulk_reclaim(true);
+        self.retired.count.take();
+        self.bulk_reclaim(true, None);
     }
 
     fn try_bulk_reclaim(&self) {
-        let retired_num = self.retired.count.load(Ordering::Acquire);
-        let hazptr_num = self.hazptrs.count.load(Ordering::Acquire);
+        let retired_num = self.retired.count.approx();
+        let hazptr_num = self.hazptrs.approx_count();
 
         if !reached_threshold(retired_num, hazptr_num) {
             return;
         }
 
-        let retired_num = self.retired.count.swap(0, Ordering::Release);
+        // Only summed exactly once the thresholds look reached.
+        let retired_num = self.retired.count.take();
 
         // No need to add retired_num back to self.retired.count.
         // At least one concurrent try_bulk_reclaim will proceed to bulk_reclaim.
@@ -130,45 +416,138 @@ impl GlobalDomainStatic {
             return;
         }
 
-        self.bulk_reclaim(false);
+        self.bulk_reclaim(false, Some(steal_limit(retired_num)));
+    }
+
+    /// Steals the retired list, keeping at most `limit` nodes from its front and pushing the rest
+    /// back to be reclaimed by a later pass.
+    fn steal(&self, limit: Option<usize>) -> *mut Node<NonNull<dyn Hazard<'static>>> {
+        if limit == Some(0) {
+            return ptr::null_mut();
+        }
+        let steal = self.retired.head.swap(ptr::null_mut(), Ordering::Acquire);
+
+        let Some(limit) = limit else {
+            return steal;
+        };
+
+        // Safety: We own the only pointers to the stolen nodes, and they are all valid or null.
+        unsafe {
+            let last_kept = match NonNull::new(steal) {
+                Some(head) => head.as_ref().iter().nth(limit - 1),
+                None => None,
+            };
+            let rest_head = match last_kept {
+                Some(last_kept) => last_kept.next.swap(ptr::null_mut(), Ordering::Relaxed),
+                None => return steal,
+            };
+            if let Some(rest) = rest_head.as_ref() {
+                let (count, tail) = rest
+                    .iter()
+                    .fold((0, rest), |(count, _), node| (count + 1, node));
+                self.retired
+                    .push_list_front(rest_head, tail as *const _ as *mut _, count);
+            }
+        }
+        steal
+    }
+
+    /// Hands out every retired hazard that is no longer protected, instead of dropping them.
+    pub fn drain_reclaimable(&self) -> vec::IntoIter<Reclaimed<'static, Global>> {
+        let mut drained = Vec::new();
+        self.bulk_reclaim_into(true, None, &mut || false, |hazard| {
+            // Safety: The hazard is no longer protected, and it was allocated using Global.
+            drained.push(unsafe { Box::from_raw_in(hazard.as_ptr(), &Global) })
+        });
+        drained.into_iter()
+    }
+
+    fn bulk_reclaim(&self, transitive: bool, limit: Option<usize>) -> usize {
+        let (reclaimed, _) = self.bulk_reclaim_into(transitive, limit, &mut || false, |hazard| {
+            // Safety: The hazard is no longer protected, and it was allocated using Global.
+            drop(unsafe { Box::from_raw_in(hazard.as_ptr(), Global) })
+        });
+        reclaimed
     }
 
-    fn bulk_reclaim(&self, transitive: bool) -> usize {
+    /// Passes every unprotected hazard found to `reclaim`, which takes ownership of it, until
+    /// `stop` returns true, returning how many were reclaimed and how many were still protected.
+    fn bulk_reclaim_into<S, R>(
+        &self,
+        transitive: bool,
+        limit: Option<usize>,
+        stop: &mut S,
+        mut reclaim: R,
+    ) -> (usize, usize)
+    where
+        S: FnMut() -> bool,
+        R: FnMut(NonNull<dyn Hazard<'static>>),
+    {
         self.nbulk_reclaims.fetch_add(1, Ordering::Acquire);
 
         let mut reclaimed = 0;
+        let mut blocked = 0;
         loop {
-            let steal = self.retired.head.swap(ptr::null_mut(), Ordering::Acquire);
+            let steal = self.steal(limit);
 
             crate::asymmetric_fence::heavy();
 
             if steal.is_null() {
-                return reclaimed;
+                break;
             }
 
             // Find all guarded addresses.
             let guarded_ptrs = self
                 .hazptrs
                 .iter()
-                .map(|hp| hp.ptr() as *const _)
-                .collect::<HashSet<_>>();
-
-            let (reclaimed_now, done) = self.bulk_lookup_and_reclaim(steal, guarded_ptrs);
+                .inspect(|_hp| {
+                    #[cfg(feature = "watchdog")]
+                    crate::watchdog::check_hazptr(_hp);
+                })
+                .map(|hp| hp.ptr() as *const _);
+
+            let (reclaimed_now, blocked_now, done) = match GuardedSet::try_collect(guarded_ptrs) {
+                Some(guarded) => self.bulk_lookup_and_reclaim(
+                    steal,
+                    |ptr| guarded.contains(ptr),
+                    stop,
+                    &mut reclaim,
+                ),
+                // Slower, but reclaiming must not abort when the scratch set can't be allocated.
+                None => self.bulk_lookup_and_reclaim(
+                    steal,
+                    |hazard| {
+                        self.hazptrs
+                            .iter()
+                            .any(|hp| ptr::eq(hp.ptr() as *const u8, hazard))
+                    },
+                    stop,
+                    &mut reclaim,
+                ),
+            };
             reclaimed += reclaimed_now;
+            blocked += blocked_now;
 
             if done || !transitive {
                 break;
             }
         }
         self.nbulk_reclaims.fetch_sub(1, Ordering::Release);
-        reclaimed
+        (reclaimed, blocked)
     }
 
-    fn bulk_lookup_and_reclaim(
+    fn bulk_lookup_and_reclaim<F, S, R>(
         &self,
         stolen_hazard_head: *mut Node<NonNull<dyn Hazard<'static>>>,
-        guarded_ptrs: HashSet<*const u8>,
-    ) -> (usize, bool) {
+        is_guarded: F,
+        stop: &mut S,
+        reclaim: &mut R,
+    ) -> (usize, usize, bool)
+    where
+        F: Fn(*const u8) -> bool,
+        S: FnMut() -> bool,
+        R: FnMut(NonNull<dyn Hazard<'static>>),
+    {
         struct LiveList {
             head: *mut Node<NonNull<dyn Hazard<'static>>>,
             tail: Option<NonNull<Node<NonNull<dyn Hazard<'static>>>>>,
@@ -181,30 +560,31 @@ impl GlobalDomainStatic {
         };
 
         let mut reclaimed: usize = 0;
+        let mut reclaimed_bytes: usize = 0;
         let mut still_retired: isize = 0;
+        let mut blocked: usize = 0;
+        let mut stopped = false;
+
+        let mut next = stolen_hazard_head;
 
         // Safety: All accessors only access the head, and the head is no longer pointing here.
-        // We own the only pointers to these nodes, and they are all valid or null
-        let nodes = iter::successors(
-            NonNull::new(stolen_hazard_head),
-            // Same here
-            |node| unsafe {
-                let next = node.as_ref().next.load(Ordering::Relaxed);
-                debug_assert_ne!(node.as_ptr(), next);
-                NonNull::new(next)
-            },
-        );
-
-        for node in nodes {
+        // We own the only pointers to these nodes, and they are all valid or null.
+        // The next node is read before the current one is either dropped or relinked.
+        while let Some(node) = NonNull::new(next) {
+            next = unsafe { node.as_ref() }.next.load(Ordering::Relaxed);
+            debug_assert_ne!(node.as_ptr(), next);
+            prefetch(next);
+
             let node_ref = unsafe { node.as_ref() };
-            if !guarded_ptrs.contains(&(node_ref.value.as_ptr() as *const u8)) {
-                // Safety: The hazard is not being protected, thus we can drop it,
-                // as well as the node pointer. Both were allocated using Global.
-                unsafe {
-                    let drop_node = Box::from_raw_in(node.as_ptr(), Global);
-                    drop(Box::from_raw_in(drop_node.value.as_ptr(), Global));
-                    drop(drop_node);
-                }
+            // Once stopped, the remaining hazards are kept without being checked.
+            stopped = stopped || stop();
+            if !stopped && !is_guarded(node_ref.value.as_ptr() as *const u8) {
+                // Safety: The hazard is not being protected, thus it can be reclaimed, and the
+                // node pointer dropped, which was allocated using Global.
+                reclaimed_bytes += hazard_size(node_ref.value);
+                let drop_node = unsafe { Box::from_raw_in(node.as_ptr(), Global) };
+                reclaim(drop_node.value);
+                drop(drop_node);
                 reclaimed += 1;
             } else {
                 node_ref.next.store(live_list.head, Ordering::Relaxed);
@@ -217,6 +597,7 @@ impl GlobalDomainStatic {
                     live_list.head = node.as_ptr();
                 }
                 still_retired += 1;
+                blocked += usize::from(!stopped);
             }
         }
 
@@ -240,17 +621,69 @@ impl GlobalDomainStatic {
                 assert_eq!(still_retired, 0);
             }
         };
-        (reclaimed, done)
+
+        self.metrics.reclaimed(reclaimed, reclaimed_bytes);
+        self.metrics.reclaim_pass();
+        self.last_reclaimed.store(reclaimed, Ordering::Relaxed);
+        self.last_still_retired
+            .store(still_retired, Ordering::Relaxed);
+        (reclaimed, blocked, done)
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+impl Default for StaticDomain {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
 pub struct GlobalDomain;
 
+impl fmt::Debug for GlobalDomain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        GLOBAL.fmt_stats("GlobalDomain", f)
+    }
+}
+
 impl GlobalDomain {
     pub fn eager_reclaim(&self) -> usize {
-        GLOBAL.bulk_reclaim(true)
+        GLOBAL.eager_reclaim()
+    }
+
+    /// Same as [`GlobalDomain::eager_reclaim`], but stops once `budget` runs out, reporting the
+    /// progress it made.
+    #[inline]
+    pub fn eager_reclaim_within(&self, budget: ReclaimBudget) -> ReclaimProgress {
+        GLOBAL.eager_reclaim_within(budget)
     }
+
+    /// Creates inactive [`HazPtrs`][HazPtr] until the domain owns at least `total` of them, so
+    /// that as many [`Anchors`][crate::anchor::Anchor] can then be
+    /// [acquired without allocating][crate::anchor::Anchor::try_new_existing].
+    #[inline]
+    pub fn reserve_hazptrs(&self, total: usize) {
+        GLOBAL.hazptrs.reserve(total)
+    }
+
+    /// Same as [`Domain::acquire`], but never allocates, returning [None] if every [`HazPtr`] is
+    /// in use.
+    #[inline]
+    pub(crate) fn try_acquire_existing(self) -> Option<&'static HazPtr> {
+        cached_hazptr().or_else(|| GLOBAL.hazptrs.try_acquire())
+    }
+}
+
+/// Pops a [`HazPtr`] from the cache of the current thread, counting it as active again.
+#[inline]
+fn cached_hazptr() -> Option<&'static HazPtr> {
+    let hazptr = HAZPTR_CACHE
+        .try_with(|cache| cache.borrow_mut().pop())
+        .ok()
+        .flatten()?;
+    GLOBAL.hazptrs.unstash();
+    Some(hazptr)
 }
 
 unsafe impl Domain<'static> for GlobalDomain {
@@ -262,14 +695,74 @@ unsafe impl Domain<'static> for GlobalDomain {
     }
 
     fn acquire(self) -> Option<&'static HazPtr> {
-        let ptr = match GLOBAL.try_acquire_existing() {
-            Some(hazptr) => hazptr,
-            None => GLOBAL.acquire_new(),
-        };
-        Some(ptr)
+        // Checked here rather than by GLOBAL, since cached records never get there.
+        if failpoints::hit(Failpoint::Acquire) {
+            return None;
+        }
+        cached_hazptr().or_else(|| GLOBAL.hazptrs.acquire())
+    }
+
+    #[inline]
+    fn acquire_many<const N: usize>(self) -> Option<[&'static HazPtr; N]> {
+        GLOBAL.acquire_many()
+    }
+
+    #[inline]
+    fn release(self, hazptr: &'static HazPtr) {
+        // Once the thread is being torn down, the record is released instead.
+        let cached = HAZPTR_CACHE
+            .try_with(|cache| cache.borrow_mut().try_push(hazptr))
+            .unwrap_or(false);
+        if cached {
+            GLOBAL.hazptrs.stash(hazptr);
+        } else {
+            GLOBAL.release(hazptr)
+        }
+    }
+
+    #[inline]
+    fn allocated(self, bytes: usize) {
+        GLOBAL.allocated(bytes)
+    }
+
+    #[inline]
+    fn deallocated(self, bytes: usize) {
+        GLOBAL.deallocated(bytes)
     }
 
     unsafe fn retire(self, retired: NonNull<dyn Hazard<'static>>) {
         GLOBAL.retire(retired)
     }
+
+    unsafe fn retire_all<I>(self, retired: I)
+    where
+        I: IntoIterator<Item = NonNull<dyn Hazard<'static>>>,
+    {
+        GLOBAL.retire_all(retired)
+    }
+
+    #[inline]
+    fn eager_reclaim(self) -> usize {
+        GLOBAL.eager_reclaim()
+    }
+
+    #[inline]
+    fn drain_reclaimable(self) -> vec::IntoIter<Reclaimed<'static, Self::Alloc>> {
+        GLOBAL.drain_reclaimable()
+    }
+
+    #[inline]
+    fn verify(self) {
+        GLOBAL.verify()
+    }
+
+    #[inline]
+    fn visit_hazptrs(self, f: &mut dyn FnMut(&'static HazPtr)) -> bool {
+        GLOBAL.visit_hazptrs(f)
+    }
+
+    #[inline]
+    fn metrics(self) -> MetricsSnapshot {
+        GLOBAL.metrics()
+    }
 }
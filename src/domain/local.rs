@@ -0,0 +1,241 @@
+use std::{
+    alloc::Global,
+    cell::{
+        Cell,
+        RefCell,
+    },
+    fmt,
+    mem,
+    ptr::{
+        self,
+        NonNull,
+    },
+    vec,
+};
+
+use crate::{
+    domain::{
+        global::GlobalDomain,
+        Domain,
+        Reclaimed,
+    },
+    guarded::{
+        merge,
+        partition_in_place,
+        GuardedSet,
+    },
+    hazptr::HazPtr,
+    metrics::{
+        hazard_size,
+        MetricsSnapshot,
+    },
+    published,
+    Hazard,
+};
+
+/// Number of hazards retired by a thread before it first tries to reclaim them.
+const LOCAL_RETIRED_THRESHOLD: usize = 128;
+
+/// Hazards retired to the [`ThreadLocalDomain`] by the current thread.
+struct LocalRetired {
+    retired: RefCell<Vec<NonNull<dyn Hazard<'static>>>>,
+    reclaim_at: Cell<usize>,
+}
+
+thread_local! {
+    static LOCAL: LocalRetired = LocalRetired::new();
+}
+
+impl LocalRetired {
+    fn new() -> Self {
+        Self {
+            retired: RefCell::new(Vec::new()),
+            reclaim_at: Cell::new(LOCAL_RETIRED_THRESHOLD),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.retired.borrow().len()
+    }
+
+    fn push(&self, retired: NonNull<dyn Hazard<'static>>) {
+        let len = {
+            let mut local = self.retired.borrow_mut();
+            local.push(retired);
+            local.len()
+        };
+        if len >= self.reclaim_at.get() {
+            self.reclaim();
+        }
+    }
+
+    fn reclaim(&self) -> usize {
+        self.reclaim_into(|hazard| {
+            // Safety: Nothing protects the hazard, and it was allocated using Global.
+            drop(unsafe { Box::from_raw_in(hazard.as_ptr(), Global) })
+        })
+    }
+
+    /// Passes every unprotected hazard to `reclaim`, which takes ownership of it.
+    fn reclaim_into<R>(&self, mut reclaim: R) -> usize
+    where
+        R: FnMut(NonNull<dyn Hazard<'static>>),
+    {
+        // Taken out, since dropping the hazards may retire more of them to this thread.
+        let mut retired = mem::take(&mut *self.retired.borrow_mut());
+        if retired.is_empty() {
+            return 0;
+        }
+
+        crate::asymmetric_fence::heavy();
+
+        let mut guarded = Vec::new();
+        let mut complete = true;
+        GlobalDomain.visit_hazptrs(&mut |hp| {
+            if guarded.try_reserve(1).is_ok() {
+                guarded.push(hp.ptr() as *const u8);
+            } else {
+                complete = false;
+            }
+        });
+
+        let protected = match GuardedSet::try_collect(guarded).filter(|_| complete) {
+            Some(guarded) => partition_in_place(&mut retired, |hazard| {
+                guarded.contains(hazard.as_ptr() as *const u8)
+            }),
+            // Slower, but reclaiming must not abort when the scratch set can't be allocated.
+            None => partition_in_place(&mut retired, |hazard| {
+                let mut found = false;
+                GlobalDomain.visit_hazptrs(&mut |hp| {
+                    found |= ptr::eq(hp.ptr() as *const u8, hazard.as_ptr() as *const u8)
+                });
+                found
+            }),
+        };
+
+        let reclaimed = retired.len() - protected;
+        // Accounted for by the GlobalDomain until reclaimed, since they never reached its list.
+        retired.drain(protected..).for_each(|hazard| {
+            GlobalDomain.deallocated(hazard_size(hazard));
+            reclaim(hazard)
+        });
+
+        // Reclaiming again is pointless until enough new hazards were retired to pay for the scan.
+        self.reclaim_at
+            .set(LOCAL_RETIRED_THRESHOLD.max(2 * protected));
+        merge(&mut self.retired.borrow_mut(), retired);
+        reclaimed
+    }
+}
+
+impl Drop for LocalRetired {
+    fn drop(&mut self) {
+        self.reclaim();
+        let retired = mem::take(self.retired.get_mut());
+        // Safety: The hazards were retired to the ThreadLocalDomain, which shares the HazPtrs and
+        // allocator of the GlobalDomain.
+        unsafe { GlobalDomain.retire_all(retired) }
+    }
+}
+
+/// Domain where each thread reclaims the hazards it retired by itself, flushing whatever is still
+/// protected to the [`GlobalDomain`] when the thread exits.
+///
+/// Protection goes through the [`HazPtrs`][HazPtr] of the [`GlobalDomain`], so values retired here
+/// can safely be protected from any thread. Only retirement is thread local: it never touches
+/// shared state until a thread has retired enough hazards to run a reclamation pass, which then
+/// only scans the [`HazPtrs`][HazPtr]. This suits thread-per-core architectures, where values are
+/// mostly retired by the thread that owns them.
+///
+/// Values allocated in the [`GlobalDomain`] can't be moved into a [`ThreadLocalDomain`] or back,
+/// since they are different domains, but both allocate with [`Global`].
+///
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub struct ThreadLocalDomain;
+
+impl fmt::Debug for ThreadLocalDomain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadLocalDomain")
+            .field(
+                "local_retired",
+                &LOCAL.try_with(LocalRetired::len).unwrap_or(0),
+            )
+            .finish()
+    }
+}
+
+unsafe impl Domain<'static> for ThreadLocalDomain {
+    type Alloc = Global;
+
+    #[inline]
+    fn allocator(self) -> &'static Self::Alloc {
+        &Global
+    }
+
+    #[inline]
+    fn acquire(self) -> Option<&'static HazPtr> {
+        GlobalDomain.acquire()
+    }
+
+    #[inline]
+    fn release(self, hazptr: &'static HazPtr) {
+        GlobalDomain.release(hazptr)
+    }
+
+    #[inline]
+    fn allocated(self, bytes: usize) {
+        GlobalDomain.allocated(bytes)
+    }
+
+    #[inline]
+    fn deallocated(self, bytes: usize) {
+        GlobalDomain.deallocated(bytes)
+    }
+
+    unsafe fn retire(self, retired: NonNull<dyn Hazard<'static>>) {
+        published::check_retired(retired);
+        if LOCAL.try_with(|local| local.push(retired)).is_err() {
+            // Safety: The thread is exiting, thus the hazard is flushed right away instead.
+            unsafe { GlobalDomain.retire(retired) }
+        }
+    }
+
+    /// Reclaims the hazards retired by the current thread, then the ones in the [`GlobalDomain`].
+    fn eager_reclaim(self) -> usize {
+        LOCAL.try_with(LocalRetired::reclaim).unwrap_or(0) + GlobalDomain.eager_reclaim()
+    }
+
+    /// Hands out the hazards retired by the current thread, then the ones in the
+    /// [`GlobalDomain`].
+    fn drain_reclaimable(self) -> vec::IntoIter<Reclaimed<'static, Self::Alloc>> {
+        let mut drained = Vec::new();
+        let _ = LOCAL.try_with(|local| {
+            local.reclaim_into(|hazard| {
+                // Safety: Nothing protects the hazard, and it was allocated using Global.
+                drained.push(unsafe { Box::from_raw_in(hazard.as_ptr(), &Global) })
+            })
+        });
+        drained.extend(GlobalDomain.drain_reclaimable());
+        drained.into_iter()
+    }
+
+    #[inline]
+    fn verify(self) {
+        GlobalDomain.verify()
+    }
+
+    #[inline]
+    fn visit_hazptrs(self, f: &mut dyn FnMut(&'static HazPtr)) -> bool {
+        GlobalDomain.visit_hazptrs(f)
+    }
+
+    /// Returns the metrics of the [`GlobalDomain`], where `retired` also counts the hazards
+    /// retired by the current thread that are still waiting to be reclaimed.
+    fn metrics(self) -> MetricsSnapshot {
+        let metrics = GlobalDomain.metrics();
+        MetricsSnapshot {
+            retired: metrics.retired + LOCAL.try_with(LocalRetired::len).unwrap_or(0),
+            ..metrics
+        }
+    }
+}
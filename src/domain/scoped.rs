@@ -1,41 +1,311 @@
 use std::{
-    alloc::Allocator,
-    mem,
+    alloc::{
+        Allocator,
+        Global,
+    },
+    fmt,
+    marker::PhantomPinned,
     ptr,
     ptr::NonNull,
-    sync::atomic::Ordering,
+    sync::atomic::{
+        AtomicPtr,
+        AtomicUsize,
+        Ordering,
+    },
+    thread,
+};
+#[cfg(feature = "registry")]
+use std::{
+    pin::Pin,
+    sync::atomic::AtomicU64,
 };
 
 use crate::{
-    domain::Domain,
-    hazptr::HazPtr,
-    node_list::List,
+    anchor::Anchor,
+    domain::{
+        Domain,
+        DomainOwner,
+    },
+    failpoints::{
+        self,
+        Failpoint,
+    },
+    hazbox::HazBox,
+    hazptr::{
+        HazPtr,
+        HazPtrRecords,
+    },
+    metrics::{
+        hazard_size,
+        MetricsRecorder,
+        MetricsSnapshot,
+    },
+    node_list::{
+        List,
+        Node,
+    },
+    published,
     Hazard,
 };
 
+type RetiredNode<'dom> = Node<NonNull<dyn Hazard<'dom>>>;
+
 pub struct ScopedDomain<'dom, A>
 where
     A: Allocator,
 {
-    hazptrs: List<HazPtr>,
+    hazptrs: HazPtrRecords,
     retired: List<NonNull<dyn Hazard<'dom>>>,
     allocator: A,
+    metrics: MetricsRecorder,
+    /// Retired list nodes allocated up front, handed out in order before allocating new ones.
+    spare: AtomicPtr<RetiredNode<'dom>>,
+    spare_len: usize,
+    spare_next: AtomicUsize,
+    /// Id of the domain in the registry, 0 if it isn't registered.
+    #[cfg(feature = "registry")]
+    registration: AtomicU64,
+    /// Registering requires the domain to stay put until dropped. Not gated on the `registry`
+    /// feature, since enabling it would otherwise stop the domain from being [`Unpin`], breaking
+    /// dependents relying on it.
+    __pin: PhantomPinned,
+}
+
+impl<'dom> ScopedDomain<'dom, Global> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    #[inline]
+    pub fn builder() -> ScopedDomainBuilder {
+        ScopedDomainBuilder::default()
+    }
+}
+
+impl<'dom> Default for ScopedDomain<'dom, Global> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'dom, A> ScopedDomain<'dom, A>
 where
     A: Allocator,
 {
-    fn try_acquire_existing(&self) -> Option<&HazPtr> {
-        self.hazptrs.iter().find(|hp| hp.try_acquire())
+    /// Creates a domain without any [`HazPtrs`][HazPtr] or retired list nodes up front, see
+    /// [`ScopedDomain::builder`] to reserve them.
+    #[inline]
+    pub fn new_in(allocator: A) -> Self {
+        Self::with_capacity_in(0, 0, allocator)
     }
 
-    fn acquire_new(&self) -> &HazPtr {
-        self.hazptrs.push_front(HazPtr::new(true))
+    /// Returns a handle borrowing this domain, to create [`HazBoxes`][HazBox] and
+    /// [`Anchors`][Anchor] with.
+    ///
+    /// The domain can't be borrowed for all of `'dom`, since it would then still be borrowed once
+    /// dropped, thus the handle lives no longer than the borrow, while the values retired through it
+    /// may be dropped as late as when the domain is.
+    ///
+    /// # Safety
+    ///
+    /// * Values retired through the handle must not borrow data that may be dropped before the
+    /// domain is.
+    ///
+    #[inline]
+    pub unsafe fn handle(&self) -> ScopedDomainRef<'_, A> {
+        // Safety: The handle borrows the domain, thus can't outlive it, and the values retired
+        // through it outlive the domain, as upheld by the caller. Only the lifetime is cast, since
+        // the domain is invariant over it.
+        ScopedDomainRef(unsafe { &*(self as *const Self).cast::<ScopedDomain<'_, A>>() })
+    }
+
+    fn with_capacity_in(hazptrs: usize, retire_capacity: usize, allocator: A) -> Self {
+        let spare = (0..retire_capacity)
+            .map(|_| Node {
+                next: AtomicPtr::new(ptr::null_mut()),
+                value: NonNull::<()>::dangling() as NonNull<dyn Hazard<'dom>>,
+            })
+            .collect::<Box<[_]>>();
+
+        let domain = Self {
+            hazptrs: HazPtrRecords::new(),
+            retired: List::new(),
+            allocator,
+            metrics: MetricsRecorder::new(),
+            spare: AtomicPtr::new(Box::into_raw(spare).cast()),
+            spare_len: retire_capacity,
+            spare_next: AtomicUsize::new(0),
+            #[cfg(feature = "registry")]
+            registration: AtomicU64::new(0),
+            __pin: PhantomPinned,
+        };
+        domain.hazptrs.reserve(hazptrs);
+        domain
+    }
+
+    /// Registers the domain under `name` in the [registry][crate::registry], replacing any
+    /// previous registration, until it is dropped.
+    ///
+    /// The domain must be pinned, since the registry reads its metrics from other threads for as
+    /// long as it is registered.
+    #[cfg(feature = "registry")]
+    pub fn register(self: Pin<&Self>, name: &'static str) {
+        unsafe fn metrics<A>(domain: *const ()) -> MetricsSnapshot
+        where
+            A: Allocator,
+        {
+            // Safety: Registered from a pinned domain below, which unregisters itself when dropped.
+            ScopedDomainRef(unsafe { &*domain.cast::<ScopedDomain<'_, A>>() }).metrics()
+        }
+
+        let domain = &*self as *const Self as *const ();
+        // Safety: Pinning guarantees the domain stays at the same address until it is dropped,
+        // and reading its metrics only loads atomics, which is fine from any thread.
+        let id = unsafe { crate::registry::register_raw(name, domain, metrics::<A>, None) };
+        match self.registration.swap(id, Ordering::AcqRel) {
+            0 => {}
+            previous => crate::registry::unregister(previous),
+        }
+    }
+
+    fn is_spare(&self, node: *mut RetiredNode<'dom>) -> bool {
+        let spare = self.spare.load(Ordering::Relaxed);
+        spare <= node && node < spare.wrapping_add(self.spare_len)
+    }
+
+    fn new_node(&self, retired: NonNull<dyn Hazard<'dom>>) -> *mut RetiredNode<'dom> {
+        let index = self.spare_next.fetch_add(1, Ordering::Relaxed);
+        if index < self.spare_len {
+            // Safety: Each spare node is handed out exactly once, so we have exclusive access.
+            unsafe {
+                let node = self.spare.load(Ordering::Relaxed).add(index);
+                (*node).value = retired;
+                node
+            }
+        } else {
+            Box::into_raw(Box::new_in(
+                Node {
+                    next: AtomicPtr::new(ptr::null_mut()),
+                    value: retired,
+                },
+                Global,
+            ))
+        }
     }
 
     fn retire(&self, retired: NonNull<dyn Hazard<'dom>>) {
-        self.retired.push_front(retired);
+        published::check_retired(retired);
+        let bytes = hazard_size(retired);
+        self.metrics.retired(1, bytes);
+
+        if self.hazptrs.is_quiescent() {
+            // Safety: Nothing can be protecting the hazard, and it was allocated using self.allocator.
+            drop(unsafe { Box::from_raw_in(retired.as_ptr(), &self.allocator) });
+            self.metrics.reclaimed(1, bytes);
+            return;
+        }
+
+        let node = self.new_node(retired);
+        self.retired.push_list_front(node, node, 1);
+    }
+
+    fn retire_all<I>(&self, retired: I)
+    where
+        I: IntoIterator<Item = NonNull<dyn Hazard<'dom>>>,
+    {
+        let retired = retired
+            .into_iter()
+            .inspect(|hazard| published::check_retired(*hazard));
+
+        if self.hazptrs.is_quiescent() {
+            for hazard in retired {
+                let bytes = hazard_size(hazard);
+                self.metrics.retired(1, bytes);
+                // Safety: Same as in retire.
+                drop(unsafe { Box::from_raw_in(hazard.as_ptr(), &self.allocator) });
+                self.metrics.reclaimed(1, bytes);
+            }
+            return;
+        }
+
+        let mut head: *mut RetiredNode<'dom> = ptr::null_mut();
+        let mut tail: *mut RetiredNode<'dom> = ptr::null_mut();
+        let mut count = 0;
+        for hazard in retired {
+            self.metrics.retired(1, hazard_size(hazard));
+            let node = self.new_node(hazard);
+            // Safety: The node was just created and isn't shared yet.
+            unsafe { *(*node).next.get_mut() = head };
+            head = node;
+            if tail.is_null() {
+                tail = node;
+            }
+            count += 1;
+        }
+
+        if count > 0 {
+            self.retired.push_list_front(head, tail, count);
+        }
+    }
+
+    /// Reclaims the whole retired list if no [`HazPtr`] is acquired, pushing it back otherwise,
+    /// since the domain doesn't track which values are protected.
+    fn eager_reclaim(&self) -> usize {
+        // Taken before checking, since values retired after the check may be protected by
+        // records acquired after it too.
+        let head = self.retired.head.swap(ptr::null_mut(), Ordering::Acquire);
+        if head.is_null() {
+            return 0;
+        }
+
+        if !self.hazptrs.is_quiescent() {
+            let mut tail = head;
+            // Safety: The nodes were taken off the list, thus only we access them.
+            unsafe {
+                while !(*tail).next.load(Ordering::Relaxed).is_null() {
+                    tail = (*tail).next.load(Ordering::Relaxed);
+                }
+            }
+            // Still counted, since they were never removed from the count.
+            self.retired.push_list_front(head, tail, 0);
+            return 0;
+        }
+
+        let mut reclaimed = 0;
+        let mut node_ptr = head;
+        while !node_ptr.is_null() {
+            // Safety: Same as in drop, and nothing can be protecting the hazards anymore.
+            unsafe {
+                let hazard = (*node_ptr).value;
+                let bytes = hazard_size(hazard);
+                let next = (*node_ptr).next.load(Ordering::Relaxed);
+                drop(Box::from_raw_in(hazard.as_ptr(), &self.allocator));
+                self.metrics.reclaimed(1, bytes);
+                if !self.is_spare(node_ptr) {
+                    drop(Box::from_raw_in(node_ptr, Global));
+                }
+                node_ptr = next;
+            }
+            reclaimed += 1;
+        }
+        self.retired.count.add(-reclaimed);
+        reclaimed as usize
+    }
+}
+
+// Safety: The handle only borrows the domain.
+unsafe impl<'h, 'dom, A> DomainOwner<'h> for ScopedDomain<'dom, A>
+where
+    A: Allocator + 'h,
+{
+    type Handle = ScopedDomainRef<'h, A>;
+
+    #[inline]
+    unsafe fn handle(&'h self) -> Self::Handle {
+        // Safety: Upheld by the caller.
+        unsafe { ScopedDomain::handle(self) }
     }
 }
 
@@ -44,25 +314,211 @@ where
     A: Allocator,
 {
     fn drop(&mut self) {
+        #[cfg(feature = "registry")]
+        match *self.registration.get_mut() {
+            0 => {}
+            id => crate::registry::unregister(id),
+        }
+
         let mut node_ptr = *self.retired.head.get_mut();
         while !node_ptr.is_null() {
-            // Safety: The hazard and node were allocated using self.allocator by a Box.
+            // Safety: The hazard was allocated using self.allocator by a Box.
+            // The node is either a spare one, or was allocated using Global by a Box.
             unsafe {
-                let mut node = Box::from_raw_in(node_ptr, &self.allocator);
-                let _ = Box::from_raw_in(node.value.as_ptr(), &self.allocator);
-                node_ptr = *node.next.get_mut();
+                let _ = Box::from_raw_in((*node_ptr).value.as_ptr(), &self.allocator);
+                let next = *(*node_ptr).next.get_mut();
+                if !self.is_spare(node_ptr) {
+                    drop(Box::from_raw_in(node_ptr, Global));
+                }
+                node_ptr = next;
             }
         }
-        let mut node_ptr = *self.hazptrs.head.get_mut();
+        // Safety: The spare nodes were allocated as a boxed slice, and none is referenced anymore.
+        unsafe {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+                *self.spare.get_mut(),
+                self.spare_len,
+            )));
+        }
+        let mut node_ptr = *self.hazptrs.list.head.get_mut();
         while !node_ptr.is_null() {
-            // Safety: The node with the hazptr was allocated using self.allocator by a Box.
+            // Safety: The node with the hazptr was allocated using Global by a Box.
             unsafe {
-                node_ptr = *Box::from_raw_in(node_ptr, &self.allocator).next.get_mut();
+                node_ptr = *Box::from_raw_in(node_ptr, Global).next.get_mut();
             }
         }
     }
 }
 
+impl<'dom, A> fmt::Debug for ScopedDomain<'dom, A>
+where
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedDomain")
+            .field("hazptrs_active", &self.hazptrs.active())
+            .field("hazptrs_total", &self.hazptrs.count())
+            .field("retired", &self.retired.count.load())
+            .finish()
+    }
+}
+
+/// Runs `f` in a [thread scope], with `state` in a [`ScopedState`] shared by every thread spawned
+/// in it, so that they can read and update it while it borrows data from outside of the scope.
+///
+/// The state lives in its own [`ScopedDomain`], and once every thread spawned in the scope is
+/// joined, both are dropped, draining every value retired to the domain before this returns,
+/// while the borrowed data is still alive.
+///
+/// ```
+/// # use anchorage::domain;
+/// let primary = vec!["/"];
+/// let fallback = vec!["/maintenance"];
+///
+/// domain::scoped::scope(&primary, |s, current| {
+///     s.spawn(move || current.read(|routes| assert!(!routes.is_empty())));
+///     current.store(&fallback);
+/// });
+/// ```
+///
+/// [thread scope]: thread::scope
+///
+#[inline]
+pub fn scope<'env, T, F, R>(state: T, f: F) -> R
+where
+    T: Hazard<'env>,
+    F: for<'scope> FnOnce(&'scope thread::Scope<'scope, '_>, &'scope ScopedState<'env, T>) -> R,
+{
+    scope_in(state, Global, f)
+}
+
+/// Same as [scope], with a domain using `allocator`.
+pub fn scope_in<'env, T, A, F, R>(state: T, allocator: A, f: F) -> R
+where
+    T: Hazard<'env>,
+    A: Allocator + 'env,
+    F: for<'scope> FnOnce(&'scope thread::Scope<'scope, '_>, &'scope ScopedState<'env, T, A>) -> R,
+{
+    let domain = ScopedDomain::builder().build_in(allocator);
+    // Safety: The handle is only reachable through the state, which never hands it out and is
+    // dropped before the domain, right after every thread spawned in the scope is joined.
+    let handle = ScopedDomainRef(unsafe { &*(&domain as *const ScopedDomain<'env, A>) });
+    let state = ScopedState {
+        hazbox: HazBox::new_in(state, handle),
+    };
+
+    let result = thread::scope(|s| f(s, &state));
+    // Dropped explicitly, to make it clear that the domain is drained before returning.
+    drop(state);
+    drop(domain);
+    result
+}
+
+/// State shared by the threads of a [scope], which may borrow data from outside of it.
+///
+/// Values are only ever protected for the duration of a call, so that the domain they are
+/// retired to can be drained as soon as the scope ends.
+///
+pub struct ScopedState<'env, T, A = Global>
+where
+    T: Hazard<'env>,
+    A: Allocator,
+{
+    hazbox: HazBox<'env, T, ScopedDomainRef<'env, A>>,
+}
+
+impl<'env, T, A> ScopedState<'env, T, A>
+where
+    T: Hazard<'env>,
+    A: Allocator,
+{
+    /// Calls `f` with the current value, protected for the duration of the call.
+    pub fn read<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let mut anchor = Anchor::new_in(self.hazbox.domain());
+        f(anchor.moor(&self.hazbox))
+    }
+
+    /// Replaces the value, retiring the old one.
+    #[inline]
+    pub fn store(&self, value: T) {
+        self.hazbox.set(value)
+    }
+
+    /// Replaces the value with the result of `update`, retiring the old one.
+    ///
+    /// If the value is replaced while `update` runs, its result is dropped and `update` is called
+    /// again with the newer value.
+    ///
+    #[inline]
+    pub fn update<F>(&self, mut update: F)
+    where
+        F: FnMut(&T) -> T,
+    {
+        drop(self.hazbox.fetch_update(|current| Some(update(current))))
+    }
+}
+
+impl<'env, T, A> fmt::Debug for ScopedState<'env, T, A>
+where
+    T: Hazard<'env> + fmt::Debug,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.read(|value| f.debug_tuple("ScopedState").field(value).finish())
+    }
+}
+
+/// Builds a [`ScopedDomain`] with storage provisioned up front, so that latency critical code
+/// using it doesn't allocate for [`HazPtrs`][HazPtr] or retired list nodes.
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # use std::alloc::System;
+/// # use anchorage::domain::scoped::ScopedDomain;
+/// let domain = ScopedDomain::builder()
+///     .hazptrs(16)
+///     .retire_capacity(1024)
+///     .build_in(System);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ScopedDomainBuilder {
+    hazptrs: usize,
+    retire_capacity: usize,
+}
+
+impl ScopedDomainBuilder {
+    /// Sets the number of [`HazPtrs`][HazPtr] created up front.
+    #[inline]
+    pub fn hazptrs(mut self, hazptrs: usize) -> Self {
+        self.hazptrs = hazptrs;
+        self
+    }
+
+    /// Sets the number of retirements that can be made without allocating a list node.
+    #[inline]
+    pub fn retire_capacity(mut self, retire_capacity: usize) -> Self {
+        self.retire_capacity = retire_capacity;
+        self
+    }
+
+    #[inline]
+    pub fn build_in<'dom, A>(self, allocator: A) -> ScopedDomain<'dom, A>
+    where
+        A: Allocator,
+    {
+        ScopedDomain::with_capacity_in(self.hazptrs, self.retire_capacity, allocator)
+    }
+
+    #[inline]
+    pub fn build<'dom>(self) -> ScopedDomain<'dom, Global> {
+        self.build_in(Global)
+    }
+}
+
 pub struct ScopedDomainRef<'dom, A>(&'dom ScopedDomain<'dom, A>)
 where
     A: Allocator;
@@ -80,6 +536,15 @@ where
     }
 }
 
+impl<'dom, A> fmt::Debug for ScopedDomainRef<'dom, A>
+where
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
 impl<'dom, A> Clone for ScopedDomainRef<'dom, A>
 where
     A: Allocator,
@@ -101,12 +566,66 @@ where
     }
 
     fn acquire(self) -> Option<&'dom HazPtr> {
-        self.0
-            .try_acquire_existing()
-            .or_else(|| Some(self.0.acquire_new()))
+        if failpoints::hit(Failpoint::Acquire) {
+            return None;
+        }
+        self.0.hazptrs.acquire()
+    }
+
+    #[inline]
+    fn acquire_many<const N: usize>(self) -> Option<[&'dom HazPtr; N]> {
+        if failpoints::hit(Failpoint::Acquire) {
+            return None;
+        }
+        self.0.hazptrs.acquire_many()
+    }
+
+    #[inline]
+    fn release(self, hazptr: &'dom HazPtr) {
+        self.0.hazptrs.release(hazptr)
+    }
+
+    #[inline]
+    fn allocated(self, bytes: usize) {
+        self.0.metrics.allocated(bytes)
+    }
+
+    #[inline]
+    fn deallocated(self, bytes: usize) {
+        self.0.metrics.deallocated(bytes)
     }
 
     unsafe fn retire(self, retired: NonNull<dyn Hazard<'dom>>) {
         self.0.retire(retired)
     }
+
+    unsafe fn retire_all<I>(self, retired: I)
+    where
+        I: IntoIterator<Item = NonNull<dyn Hazard<'dom>>>,
+    {
+        self.0.retire_all(retired)
+    }
+
+    #[inline]
+    fn eager_reclaim(self) -> usize {
+        self.0.eager_reclaim()
+    }
+
+    #[inline]
+    fn verify(self) {
+        self.0.hazptrs.verify()
+    }
+
+    #[inline]
+    fn visit_hazptrs(self, f: &mut dyn FnMut(&'dom HazPtr)) -> bool {
+        self.0.hazptrs.iter().for_each(f);
+        true
+    }
+
+    #[inline]
+    fn metrics(self) -> MetricsSnapshot {
+        self.0
+            .metrics
+            .snapshot(self.0.hazptrs.metrics(MetricsSnapshot::default()))
+    }
 }
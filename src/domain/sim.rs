@@ -0,0 +1,590 @@
+use std::{
+    alloc::{
+        Allocator,
+        Global,
+    },
+    cell::Cell,
+    fmt,
+    marker::PhantomData,
+    mem,
+    ptr,
+    ptr::NonNull,
+    sync::{
+        Condvar,
+        Mutex,
+        MutexGuard,
+    },
+    thread,
+    vec,
+};
+
+use crate::{
+    domain::{
+        Domain,
+        DomainOwner,
+        Reclaimed,
+    },
+    failpoints::{
+        self,
+        Failpoint,
+    },
+    hazptr::HazPtr,
+    metrics::{
+        hazard_size,
+        MetricsSnapshot,
+    },
+    published,
+    Hazard,
+};
+
+/// A step taken by a [`SimDomain`], in the order it was taken.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SimEvent {
+    /// A [`HazPtr`] was acquired from the given slot.
+    Acquire { slot: usize },
+    /// A [`HazPtr`] in the given slot was released.
+    Release { slot: usize },
+    /// A [`Hazard`] at the given address was retired. Unlike the rest of the trace, addresses
+    /// aren't reproducible across runs.
+    Retire { addr: usize },
+    /// A reclamation pass ran.
+    Reclaim {
+        reclaimed: usize,
+        still_retired: usize,
+    },
+    /// The given task of a [run][SimDomainRef::run] was picked to take the next steps.
+    Switch { task: usize },
+}
+
+thread_local! {
+    /// Address of the domain whose run the current thread is a task of, and the index of the task.
+    static TASK: Cell<Option<(usize, usize)>> = const { Cell::new(None) };
+}
+
+struct SimState {
+    rng: u64,
+    // Boxed so that acquired HazPtrs keep their address as more are added.
+    #[allow(clippy::vec_box)]
+    hazptrs: Vec<Box<HazPtr>>,
+    /// Hazards retired to the domain, with their lifetime erased from 'dom so that the domain
+    /// stays covariant over it.
+    retired: Vec<NonNull<dyn Hazard<'static>>>,
+    events: Vec<SimEvent>,
+    /// Whether each task of the current run finished, empty outside of runs.
+    finished: Vec<bool>,
+    /// Task of the current run allowed to take the next step.
+    turn: usize,
+}
+
+impl SimState {
+    /// xorshift64*, which is plenty to pick reclamation points reproducibly.
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        self.rng.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn slot_of(&self, hazptr: &HazPtr) -> usize {
+        self.hazptrs
+            .iter()
+            .position(|hp| ptr::eq(&**hp, hazptr))
+            .expect("HazPtr was not acquired from this domain")
+    }
+
+    /// Takes every unprotected hazard out of the retired ones.
+    fn take_reclaimable(&mut self) -> Vec<NonNull<dyn Hazard<'static>>> {
+        let protected = self
+            .hazptrs
+            .iter()
+            .map(|hp| hp.ptr() as *const u8)
+            .filter(|ptr| !ptr.is_null())
+            .collect::<Vec<_>>();
+
+        let (retired, reclaimable) = mem::take(&mut self.retired)
+            .into_iter()
+            .partition(|&hazard| protected.contains(&(hazard.as_ptr() as *const u8)));
+        self.retired = retired;
+
+        self.events.push(SimEvent::Reclaim {
+            reclaimed: reclaimable.len(),
+            still_retired: self.retired.len(),
+        });
+        reclaimable
+    }
+
+    /// Picks the task taking the next step among the unfinished ones.
+    fn pick_turn(&mut self) {
+        let unfinished = (0..self.finished.len())
+            .filter(|&task| !self.finished[task])
+            .collect::<Vec<_>>();
+        if unfinished.is_empty() {
+            return;
+        }
+
+        let task = unfinished[self.next_random() as usize % unfinished.len()];
+        if task != self.turn {
+            self.turn = task;
+            self.events.push(SimEvent::Switch { task });
+        }
+    }
+}
+
+/// Deterministic domain for reproducible tests of hazard protected structures.
+///
+/// Every interaction with the domain is serialized and recorded as a [`SimEvent`], and whether a
+/// reclamation pass runs after each step is decided by a pseudo random sequence derived from the
+/// seed.
+///
+/// Concurrent scripts are [run][SimDomainRef::run] as tasks taking turns: only one of them runs at
+/// a time, and after each step it takes through the domain, the same sequence picks which task
+/// takes the next one. Running the same script with the same seed thus always interleaves the
+/// tasks and reclaims at the same points, so a race found in a container can be turned into a
+/// regression test by replaying the seed that exposed it.
+///
+/// ```
+/// # use anchorage::{anchor::Anchor, domain::sim::SimDomain, hazbox::HazBox};
+/// # let seed = 7;
+/// let sim = SimDomain::new(seed);
+/// // Safety: The values borrow nothing.
+/// let domain = unsafe { sim.handle() };
+/// let version = HazBox::new_in(0_u64, domain);
+/// domain.run(vec![
+///     Box::new(|_| version.set(1)) as Box<dyn FnOnce(_) + Send>,
+///     Box::new(|domain| {
+///         let mut anchor = Anchor::new_in(domain);
+///         let seen = *anchor.moor(&version);
+///         domain.yield_now();
+///         assert!(*anchor.moor(&version) >= seen);
+///     }),
+/// ]);
+/// ```
+///
+pub struct SimDomain<'dom, A = Global>
+where
+    A: Allocator,
+{
+    state: Mutex<SimState>,
+    /// Signalled whenever the turn is handed to another task.
+    turns: Condvar,
+    reclaim_one_in: u64,
+    allocator: A,
+    __mk: PhantomData<&'dom ()>,
+}
+
+// Safety: All access to the retired hazards is serialized by the mutex, and hazards are Send + Sync.
+unsafe impl<'dom, A> Send for SimDomain<'dom, A> where A: Allocator + Send {}
+unsafe impl<'dom, A> Sync for SimDomain<'dom, A> where A: Allocator + Sync {}
+
+impl<'dom> SimDomain<'dom, Global> {
+    #[inline]
+    pub fn new(seed: u64) -> Self {
+        Self::new_in(seed, Global)
+    }
+}
+
+impl<'dom, A> SimDomain<'dom, A>
+where
+    A: Allocator,
+{
+    pub fn new_in(seed: u64, allocator: A) -> Self {
+        Self {
+            state: Mutex::new(SimState {
+                // xorshift gets stuck on 0.
+                rng: seed | 1,
+                hazptrs: Vec::new(),
+                retired: Vec::new(),
+                events: Vec::new(),
+                finished: Vec::new(),
+                turn: 0,
+            }),
+            turns: Condvar::new(),
+            reclaim_one_in: 2,
+            allocator,
+            __mk: PhantomData,
+        }
+    }
+
+    /// Sets the odds of a reclamation pass running after each step to one in `n`.
+    /// With `n == 0` passes only run when [`SimDomain::reclaim`] is called.
+    #[inline]
+    pub fn reclaim_one_in(mut self, n: u64) -> Self {
+        self.reclaim_one_in = n;
+        self
+    }
+
+    /// Returns a handle borrowing this domain, which lives no longer than the borrow, like
+    /// [`ScopedDomain::handle`][crate::domain::scoped::ScopedDomain::handle].
+    ///
+    /// # Safety
+    ///
+    /// * Values retired through the handle must not borrow data that may be dropped before the
+    /// domain is.
+    ///
+    #[inline]
+    pub unsafe fn handle(&self) -> SimDomainRef<'_, A> {
+        SimDomainRef(self)
+    }
+
+    /// Returns all the steps taken so far.
+    pub fn events(&self) -> Vec<SimEvent> {
+        self.lock().events.clone()
+    }
+
+    /// Number of hazards that were retired and not reclaimed yet.
+    pub fn retired(&self) -> usize {
+        self.lock().retired.len()
+    }
+
+    /// Forces a reclamation pass, returning how many hazards were reclaimed.
+    pub fn reclaim(&self) -> usize {
+        let mut state = self.lock();
+        let reclaimable = self.take_reclaimable(&mut state);
+        drop(self.yield_turn(state));
+        self.reclaim_all(reclaimable)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SimState> {
+        // A panicking test must not hide the state from the following assertions.
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Index of the task of a run on this domain the current thread is, if any.
+    fn current_task(&self) -> Option<usize> {
+        let addr = self as *const Self as usize;
+        TASK.with(Cell::get)
+            .filter(|&(domain, _)| domain == addr)
+            .map(|(_, task)| task)
+    }
+
+    /// Blocks the current thread until its task is picked to take the next step. Does nothing for
+    /// threads that aren't tasks of a run.
+    fn wait_turn<'s>(&'s self, mut state: MutexGuard<'s, SimState>) -> MutexGuard<'s, SimState> {
+        if let Some(task) = self.current_task() {
+            while state.turn != task {
+                state = self
+                    .turns
+                    .wait(state)
+                    .unwrap_or_else(|err| err.into_inner());
+            }
+        }
+        state
+    }
+
+    /// Ends the step of the current task, letting the seed pick the task taking the next one and
+    /// waiting until it is picked again. Does nothing for threads that aren't tasks of a run.
+    fn yield_turn<'s>(&'s self, mut state: MutexGuard<'s, SimState>) -> MutexGuard<'s, SimState> {
+        if self.current_task().is_none() {
+            return state;
+        }
+        state.pick_turn();
+        self.turns.notify_all();
+        self.wait_turn(state)
+    }
+
+    /// Records a reclamation pass, returning the hazards it found unprotected, which must only be
+    /// dropped once the lock is released, since their destructors may retire more of them.
+    fn take_reclaimable(&self, state: &mut SimState) -> Vec<NonNull<dyn Hazard<'static>>> {
+        // Tasks of a run are serialized by the lock, but other threads may be protecting pointers
+        // concurrently.
+        if self.current_task().is_none() {
+            crate::asymmetric_fence::heavy();
+        }
+        state.take_reclaimable()
+    }
+
+    /// Drops hazards returned by [`SimDomain::take_reclaimable`], returning how many there were.
+    fn reclaim_all(&self, hazards: Vec<NonNull<dyn Hazard<'static>>>) -> usize {
+        let reclaimed = hazards.len();
+        for hazard in hazards {
+            // Safety: The hazard is not protected and was allocated using the domain's allocator.
+            drop(unsafe { Box::from_raw_in(hazard.as_ptr(), &self.allocator) })
+        }
+        reclaimed
+    }
+
+    fn step(&self, mut state: MutexGuard<'_, SimState>, event: SimEvent) {
+        state.events.push(event);
+        let reclaimable = if state.next_random().checked_rem(self.reclaim_one_in) == Some(0) {
+            self.take_reclaimable(&mut state)
+        } else {
+            Vec::new()
+        };
+        drop(self.yield_turn(state));
+        self.reclaim_all(reclaimable);
+    }
+}
+
+// Safety: The handle only borrows the domain.
+unsafe impl<'h, 'dom, A> DomainOwner<'h> for SimDomain<'dom, A>
+where
+    A: Allocator + 'h,
+{
+    type Handle = SimDomainRef<'h, A>;
+
+    #[inline]
+    unsafe fn handle(&'h self) -> Self::Handle {
+        SimDomainRef(self)
+    }
+}
+
+impl<'dom, A> Drop for SimDomain<'dom, A>
+where
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(|err| err.into_inner());
+        for hazard in state.retired.drain(..) {
+            // Safety: Nothing can be protecting anything once the domain is dropped.
+            drop(unsafe { Box::from_raw_in(hazard.as_ptr(), &self.allocator) });
+        }
+    }
+}
+
+impl<'dom, A> fmt::Debug for SimDomain<'dom, A>
+where
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("SimDomain")
+            .field("hazptrs_total", &state.hazptrs.len())
+            .field("retired", &state.retired.len())
+            .field("steps", &state.events.len())
+            .finish()
+    }
+}
+
+pub struct SimDomainRef<'dom, A = Global>(&'dom SimDomain<'dom, A>)
+where
+    A: Allocator;
+
+impl<'dom, A> SimDomainRef<'dom, A>
+where
+    A: Allocator + Sync,
+{
+    /// Runs every task on its own thread, taking turns as scripted by the seed of the domain, and
+    /// returns once all of them finished.
+    ///
+    /// Only one task runs at a time: the one picked runs until its next step through the domain,
+    /// such as acquiring or releasing a [`HazPtr`], retiring a [`Hazard`] or reclaiming, after
+    /// which the seed picks the task taking the next step. Protecting doesn't go through the
+    /// domain, thus tasks can call [`SimDomainRef::yield_now`] to let others run, e.g. between
+    /// loading a pointer and protecting it. Each switch is recorded as a [`SimEvent::Switch`].
+    ///
+    /// Tasks must not wait on each other other than through their steps, e.g. by spinning until
+    /// another task stops protecting a value, since the others can't run meanwhile.
+    ///
+    /// # Panics
+    ///
+    /// * If the domain is already running tasks.
+    /// * If any task panics, once all of them finished.
+    ///
+    pub fn run<F>(self, tasks: Vec<F>)
+    where
+        F: FnOnce(Self) + Send,
+    {
+        if tasks.is_empty() {
+            return;
+        }
+        let addr = self.0 as *const SimDomain<'dom, A> as usize;
+        {
+            let mut state = self.0.lock();
+            assert!(
+                state.finished.is_empty(),
+                "SimDomain is already running tasks"
+            );
+            state.finished = vec![false; tasks.len()];
+            state.turn = usize::MAX;
+            state.pick_turn();
+        }
+
+        thread::scope(|scope| {
+            for (index, task) in tasks.into_iter().enumerate() {
+                scope.spawn(move || {
+                    TASK.with(|current| current.set(Some((addr, index))));
+                    let _finish = FinishTask(self.0, index);
+                    drop(self.0.wait_turn(self.0.lock()));
+                    task(self);
+                });
+            }
+        });
+
+        self.0.lock().finished.clear();
+    }
+
+    /// Ends the step of the current task, letting the seed pick the task taking the next one.
+    /// Does nothing outside of [`SimDomainRef::run`].
+    #[inline]
+    pub fn yield_now(self) {
+        drop(self.0.yield_turn(self.0.lock()));
+    }
+}
+
+/// Marks a task as finished, even if it panicked, handing the turn to the remaining ones.
+struct FinishTask<'s, 'dom, A>(&'s SimDomain<'dom, A>, usize)
+where
+    A: Allocator;
+
+impl<'s, 'dom, A> Drop for FinishTask<'s, 'dom, A>
+where
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        let mut state = self.0.lock();
+        state.finished[self.1] = true;
+        state.pick_turn();
+        self.0.turns.notify_all();
+        TASK.with(|current| current.set(None));
+    }
+}
+
+impl<'dom, A> Eq for SimDomainRef<'dom, A> where A: Allocator {}
+
+impl<'dom, A> Copy for SimDomainRef<'dom, A> where A: Allocator {}
+
+impl<'dom, A> PartialEq for SimDomainRef<'dom, A>
+where
+    A: Allocator,
+{
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.0, other.0)
+    }
+}
+
+impl<'dom, A> Clone for SimDomainRef<'dom, A>
+where
+    A: Allocator,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'dom, A> fmt::Debug for SimDomainRef<'dom, A>
+where
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+unsafe impl<'dom, A> Domain<'dom> for SimDomainRef<'dom, A>
+where
+    A: Allocator,
+{
+    type Alloc = A;
+
+    #[inline]
+    fn allocator(self) -> &'dom Self::Alloc {
+        &self.0.allocator
+    }
+
+    fn acquire(self) -> Option<&'dom HazPtr> {
+        if failpoints::hit(Failpoint::Acquire) {
+            return None;
+        }
+        let mut state = self.0.lock();
+
+        let slot = match state.hazptrs.iter().position(|hp| hp.try_acquire()) {
+            Some(slot) => slot,
+            None => {
+                state.hazptrs.push(Box::new(HazPtr::new(true)));
+                state.hazptrs.len() - 1
+            }
+        };
+        // Safety: HazPtrs are boxed and only deallocated when the domain is dropped.
+        let hazptr = unsafe { &*(&*state.hazptrs[slot] as *const HazPtr) };
+
+        self.0.step(state, SimEvent::Acquire { slot });
+        Some(hazptr)
+    }
+
+    fn release(self, hazptr: &'dom HazPtr) {
+        let state = self.0.lock();
+        let slot = state.slot_of(hazptr);
+        hazptr.release();
+
+        self.0.step(state, SimEvent::Release { slot });
+    }
+
+    fn eager_reclaim(self) -> usize {
+        self.0.reclaim()
+    }
+
+    /// Records the pass as a [`SimEvent::Reclaim`], like any other.
+    fn drain_reclaimable(self) -> vec::IntoIter<Reclaimed<'dom, Self::Alloc>> {
+        let mut state = self.0.lock();
+        let reclaimable = self.0.take_reclaimable(&mut state);
+        drop(self.0.yield_turn(state));
+
+        let drained = reclaimable.into_iter().map(|hazard| {
+            // Safety: Restores the lifetime erased in retire, the hazard is not protected and was
+            // allocated using the domain's allocator.
+            unsafe {
+                let hazard = mem::transmute::<
+                    NonNull<dyn Hazard<'static>>,
+                    NonNull<dyn Hazard<'dom>>,
+                >(hazard);
+                Box::from_raw_in(hazard.as_ptr(), self.allocator())
+            }
+        });
+        drained.collect::<Vec<_>>().into_iter()
+    }
+
+    fn verify(self) {
+        let state = self.0.lock();
+        let mut addrs = state
+            .retired
+            .iter()
+            .map(|hazard| hazard.as_ptr() as *const u8)
+            .collect::<Vec<_>>();
+        addrs.sort_unstable();
+        if let Some(pair) = addrs.windows(2).find(|pair| pair[0] == pair[1]) {
+            panic!("hazard at {:p} was retired twice", pair[0]);
+        }
+    }
+
+    fn visit_hazptrs(self, f: &mut dyn FnMut(&'dom HazPtr)) -> bool {
+        let hazptrs = self
+            .0
+            .lock()
+            .hazptrs
+            .iter()
+            // Safety: HazPtrs are boxed and only deallocated when the domain is dropped.
+            .map(|hp| unsafe { &*(&**hp as *const HazPtr) })
+            .collect::<Vec<&'dom HazPtr>>();
+        hazptrs.into_iter().for_each(f);
+        true
+    }
+
+    fn metrics(self) -> MetricsSnapshot {
+        let state = self.0.lock();
+        let count = |pred: fn(&SimEvent) -> bool| state.events.iter().filter(|e| pred(e)).count();
+
+        let mut snapshot = MetricsSnapshot::default();
+        snapshot.hazptrs_total = state.hazptrs.len();
+        snapshot.hazptrs_active = count(|e| matches!(e, SimEvent::Acquire { .. }))
+            - count(|e| matches!(e, SimEvent::Release { .. }));
+        snapshot.retired = state.retired.len();
+        snapshot.retired_bytes = state.retired.iter().map(|&h| hazard_size(h)).sum();
+        snapshot.total_retired = count(|e| matches!(e, SimEvent::Retire { .. })) as u64;
+        snapshot.total_reclaimed = snapshot.total_retired - snapshot.retired as u64;
+        snapshot.total_reclaim_passes = count(|e| matches!(e, SimEvent::Reclaim { .. })) as u64;
+        snapshot
+    }
+
+    unsafe fn retire(self, retired: NonNull<dyn Hazard<'dom>>) {
+        published::check_retired(retired);
+        let mut state = self.0.lock();
+        // Safety: Only the lifetime is erased, the hazard is dropped before the domain, thus
+        // before 'dom ends.
+        state.retired.push(unsafe {
+            mem::transmute::<NonNull<dyn Hazard<'dom>>, NonNull<dyn Hazard<'static>>>(retired)
+        });
+
+        let addr = retired.as_ptr() as *const u8 as usize;
+        self.0.step(state, SimEvent::Retire { addr });
+    }
+}
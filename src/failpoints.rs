@@ -0,0 +1,102 @@
+//! Failure injection for tests, enabled by the `failpoints` feature.
+//!
+//! Lets tests force allocations and [`HazPtr`][crate::hazptr::HazPtr] acquisitions to fail at
+//! chosen points, so error handling paths that are nearly impossible to hit naturally can be
+//! exercised. Failpoints are armed per thread, so tests running in parallel don't interfere.
+
+/// A point where failures can be injected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Failpoint {
+    /// Allocation of the storage for a value, by [`HazBox::try_new_in`],
+    /// [`HazBox::try_new_with_in`], [`HazBox::try_swap`] and their
+    /// [`CompactBox`][crate::compact::CompactBox] counterparts, which then return an
+    /// [`AllocError`][std::alloc::AllocError].
+    ///
+    /// [`HazBox::try_new_in`]: crate::hazbox::HazBox::try_new_in
+    /// [`HazBox::try_new_with_in`]: crate::hazbox::HazBox::try_new_with_in
+    /// [`HazBox::try_swap`]: crate::hazbox::HazBox::try_swap
+    Alloc,
+    /// Acquisition of [`HazPtrs`][crate::hazptr::HazPtr] by the [`Domain::acquire`] and
+    /// [`Domain::acquire_many`] of the bundled domains, which then return [None], as do the
+    /// [`Anchor::try_new_in`] and [`AnchorSet::try_new_in`] calling them.
+    ///
+    /// [`Domain::acquire`]: crate::domain::Domain::acquire
+    /// [`Domain::acquire_many`]: crate::domain::Domain::acquire_many
+    /// [`Anchor::try_new_in`]: crate::anchor::Anchor::try_new_in
+    /// [`AnchorSet::try_new_in`]: crate::anchor::AnchorSet::try_new_in
+    Acquire,
+    /// Allocation of the list nodes holding new [`HazPtrs`][crate::hazptr::HazPtr], which then
+    /// makes acquiring them return [None], like [`Failpoint::Acquire`] does.
+    NodeAlloc,
+}
+
+#[cfg(feature = "failpoints")]
+mod imp {
+    use std::cell::Cell;
+
+    use super::Failpoint;
+
+    const POINTS: usize = 3;
+
+    thread_local! {
+        /// Number of hits left before each failpoint triggers, or None if it's disarmed.
+        static ARMED: [Cell<Option<usize>>; POINTS] = Default::default();
+    }
+
+    fn index(point: Failpoint) -> usize {
+        match point {
+            Failpoint::Alloc => 0,
+            Failpoint::Acquire => 1,
+            Failpoint::NodeAlloc => 2,
+        }
+    }
+
+    /// Makes the `nth` next hit of `point` on this thread fail, counting from 0.
+    pub fn fail_nth(point: Failpoint, nth: usize) {
+        ARMED.with(|armed| armed[index(point)].set(Some(nth)));
+    }
+
+    /// Makes the next hit of `point` on this thread fail.
+    #[inline]
+    pub fn fail_next(point: Failpoint) {
+        fail_nth(point, 0)
+    }
+
+    /// Disarms `point` on this thread.
+    pub fn clear(point: Failpoint) {
+        ARMED.with(|armed| armed[index(point)].set(None));
+    }
+
+    /// Disarms all failpoints on this thread.
+    pub fn clear_all() {
+        ARMED.with(|armed| armed.iter().for_each(|cell| cell.set(None)));
+    }
+
+    pub(crate) fn hit(point: Failpoint) -> bool {
+        ARMED
+            .try_with(|armed| {
+                let cell = &armed[index(point)];
+                match cell.get() {
+                    Some(0) => {
+                        cell.set(None);
+                        true
+                    }
+                    Some(n) => {
+                        cell.set(Some(n - 1));
+                        false
+                    }
+                    None => false,
+                }
+            })
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "failpoints")]
+pub use imp::*;
+
+#[cfg(not(feature = "failpoints"))]
+#[inline(always)]
+pub(crate) fn hit(_point: Failpoint) -> bool {
+    false
+}
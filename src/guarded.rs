@@ -0,0 +1,100 @@
+use std::iter::FromIterator;
+
+/// Number of guarded addresses below which a linear scan beats a binary search.
+const LINEAR_SCAN_MAX: usize = 64;
+
+/// Width of the chunks compared at once during linear scans.
+///
+/// The inner comparison is branch free, so it can be lowered to wide compares where available.
+const LANES: usize = 8;
+
+/// Set of addresses currently protected by the [`HazPtrs`][crate::hazptr::HazPtr] of a domain.
+///
+/// Stored as a contiguous array of addresses, sorted when large enough to need a binary search,
+/// so that matching retired pointers against it never chases pointers or hashes.
+pub(crate) struct GuardedSet {
+    addrs: Vec<usize>,
+}
+
+impl GuardedSet {
+    #[inline]
+    pub fn contains(&self, ptr: *const u8) -> bool {
+        let addr = ptr as usize;
+
+        if self.addrs.len() > LINEAR_SCAN_MAX {
+            return self.addrs.binary_search(&addr).is_ok();
+        }
+
+        let mut chunks = self.addrs.chunks_exact(LANES);
+        let rest = chunks.remainder();
+
+        chunks.any(|chunk| chunk.iter().fold(false, |found, &a| found | (a == addr)))
+            || rest.contains(&addr)
+    }
+}
+
+impl GuardedSet {
+    /// Collects the non null addresses in `iter`, or returns None if the scratch storage for them
+    /// can't be allocated, in which case callers fall back to scanning their
+    /// [`HazPtrs`][crate::hazptr::HazPtr] in place for every retired pointer.
+    pub fn try_collect<I>(iter: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = *const u8>,
+    {
+        let iter = iter.into_iter();
+        let mut addrs = Vec::new();
+        addrs.try_reserve(iter.size_hint().0).ok()?;
+
+        for addr in iter.map(|ptr| ptr as usize).filter(|&addr| addr != 0) {
+            if addrs.len() == addrs.capacity() {
+                addrs.try_reserve(1).ok()?;
+            }
+            addrs.push(addr);
+        }
+
+        if addrs.len() > LINEAR_SCAN_MAX {
+            addrs.sort_unstable();
+            addrs.dedup();
+        }
+
+        Some(Self { addrs })
+    }
+}
+
+impl FromIterator<*const u8> for GuardedSet {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = *const u8>,
+    {
+        match Self::try_collect(iter) {
+            Some(set) => set,
+            None => std::alloc::handle_alloc_error(std::alloc::Layout::new::<usize>()),
+        }
+    }
+}
+
+/// Reorders `items` in place so that those matching `pred` come first, returning how many did.
+///
+/// Used instead of partitioning into new vectors, so that reclamation needs no scratch storage.
+pub(crate) fn partition_in_place<T, F>(items: &mut [T], mut pred: F) -> usize
+where
+    F: FnMut(&T) -> bool,
+{
+    let mut matching = 0;
+    for i in 0..items.len() {
+        if pred(&items[i]) {
+            items.swap(matching, i);
+            matching += 1;
+        }
+    }
+    matching
+}
+
+/// Moves every element of `src` into `dst`, reusing whichever of both already has room for all of
+/// them before allocating.
+pub(crate) fn merge<T>(dst: &mut Vec<T>, mut src: Vec<T>) {
+    if dst.capacity() - dst.len() < src.len() && src.capacity() - src.len() >= dst.len() {
+        std::mem::swap(dst, &mut src);
+    }
+    dst.append(&mut src);
+}
@@ -2,10 +2,18 @@ use std::{
     alloc::{
         handle_alloc_error,
         AllocError,
+        Global,
         Layout,
     },
+    convert::Infallible,
     marker::PhantomData,
-    mem::MaybeUninit,
+    mem::{
+        self,
+        ManuallyDrop,
+        MaybeUninit,
+    },
+    ops::Deref,
+    ptr,
     sync::atomic::{
         AtomicPtr,
         Ordering,
@@ -13,10 +21,17 @@ use std::{
 };
 
 use crate::{
+    anchor::Anchor,
     domain::{
         global::GlobalDomain,
+        scoped::ScopedDomainRef,
         Domain,
     },
+    failpoints::{
+        self,
+        Failpoint,
+    },
+    published,
     retire::Retire,
     Hazard,
 };
@@ -30,6 +45,19 @@ use crate::{
 /// [`Hazards`][Hazard] can be replaced via [`HazBox::swap`] into a [`Retire`] that
 /// holds the swapped [`Hazard`] until it is sent to the domain to be [retired].
 ///
+/// The low bits of the pointer, which are always zero due to the alignment of `T`, can hold a
+/// tag, e.g. for the logical deletion marks of lock free lists, see [`HazBox::fetch_or_tag`].
+/// Tags are stripped before the pointer is ever dereferenced, and [`Anchor::moor_tagged`] returns
+/// them alongside the value.
+///
+/// Boxes are invariant over `T`, like [`Cells`][std::cell::Cell], since values can be swapped in
+/// through shared references. They are dropped like a [`Box`] though, only requiring the destructor
+/// of `T` to be able to run, while borrowed data still has to outlive the domain.
+///
+/// Zero sized types are never allocated, thus boxes of marker types cost no more than the
+/// [`AtomicPtr`] itself, and retiring them is a no-op unless they implement [`Drop`], in which
+/// case they are still retired so that they aren't dropped while protected.
+///
 /// [*currently allocated*]: Allocator#currently-allocated-memory
 /// [equal]: PartialEq::eq
 /// [protected]: Anchor::moor
@@ -43,9 +71,41 @@ where
 {
     pub(crate) ptr: AtomicPtr<T>,
     pub(crate) domain: D,
-    __mk: PhantomData<&'dom D>,
+    // Owns a T, for dropck to check that its destructor may run when the box is dropped.
+    __mk: PhantomData<(&'dom D, T)>,
+}
+
+/// Strips the tag from a pointer loaded from a [`HazBox`].
+#[inline]
+pub(crate) fn untagged<T>(ptr: *mut T) -> *mut T {
+    (ptr as usize & !(mem::align_of::<T>() - 1)) as *mut T
 }
 
+/// Returns the tag of a pointer loaded from a [`HazBox`].
+#[inline]
+pub(crate) fn tag_of<T>(ptr: *mut T) -> usize {
+    ptr as usize & (mem::align_of::<T>() - 1)
+}
+
+#[inline]
+pub(crate) fn tagged<T>(ptr: *mut T, tag: usize) -> *mut T {
+    assert!(
+        tag < mem::align_of::<T>(),
+        "Tag doesn't fit in the alignment bits"
+    );
+    (ptr as usize | tag) as *mut T
+}
+
+/// A [`HazBox`] in the [`GlobalDomain`].
+pub type GlobalHazBox<T> = HazBox<'static, T, GlobalDomain>;
+
+/// A [`HazBox`] in a [`ScopedDomain`][crate::domain::scoped::ScopedDomain].
+pub type ScopedHazBox<'dom, T, A = Global> = HazBox<'dom, T, ScopedDomainRef<'dom, A>>;
+
+/// Outcome of a tagged compare exchange, either the value swapped out or the tagged pointer the
+/// box held instead.
+type TaggedExchange<'dom, T, D> = Result<Retire<'dom, T, D>, (*mut T, usize)>;
+
 impl<T> HazBox<'static, T, GlobalDomain>
 where
     T: Hazard<'static>,
@@ -54,6 +114,44 @@ where
     pub fn new(obj: T) -> Self {
         Self::new_in(obj, GlobalDomain)
     }
+
+    #[inline]
+    pub fn new_with<F>(init: F) -> Self
+    where
+        F: FnOnce(&mut MaybeUninit<T>) -> &mut T,
+    {
+        Self::new_with_in(init, GlobalDomain)
+    }
+}
+
+impl<T> From<T> for HazBox<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    fn from(obj: T) -> Self {
+        Self::new(obj)
+    }
+}
+
+impl<T> From<Box<T>> for HazBox<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    /// Takes ownership of the storage of `obj` without reallocating, since [`GlobalDomain`]
+    /// allocates with [`Global`] too.
+    #[inline]
+    fn from(obj: Box<T>) -> Self {
+        let ptr = Box::into_raw(obj);
+        GlobalDomain.allocated(mem::size_of::<T>());
+        published::publish(ptr);
+
+        Self {
+            ptr: AtomicPtr::new(ptr),
+            domain: GlobalDomain,
+            __mk: PhantomData,
+        }
+    }
 }
 
 impl<'dom, T, D> HazBox<'dom, T, D>
@@ -61,16 +159,155 @@ where
     D: Domain<'dom>,
     T: Hazard<'dom>,
 {
+    /// Fails to evaluate unless the domain's allocator is stateless, thus any instance of it can
+    /// deallocate what another one allocated.
+    const STATELESS_ALLOC: () = assert!(
+        mem::size_of::<D::Alloc>() == 0,
+        "Boxes can only be adopted by domains with a stateless allocator"
+    );
+
     pub fn try_new_in(obj: T, domain: D) -> Result<Self, AllocError> {
-        let ptr = Box::try_new_in(obj, domain.allocator())?;
+        let ptr = Self::try_alloc(obj, domain)?;
+        published::publish(ptr);
 
         Ok(Self {
-            ptr: AtomicPtr::new(Box::into_raw(ptr)),
+            ptr: AtomicPtr::new(ptr),
             domain,
             __mk: PhantomData,
         })
     }
 
+    /// Takes ownership of the storage of `boxed` without reallocating, returning it back if it
+    /// wasn't allocated by the domain's allocator.
+    pub fn try_new_from_box(
+        boxed: Box<T, &'dom D::Alloc>,
+        domain: D,
+    ) -> Result<Self, Box<T, &'dom D::Alloc>> {
+        if !Self::allocated_by(*Box::allocator(&boxed), domain) {
+            return Err(boxed);
+        }
+        let (ptr, _) = Box::into_raw_with_allocator(boxed);
+        domain.allocated(mem::size_of::<T>());
+        published::publish(ptr);
+
+        Ok(Self {
+            ptr: AtomicPtr::new(ptr),
+            domain,
+            __mk: PhantomData,
+        })
+    }
+
+    /// Takes ownership of the storage of `boxed` without reallocating.
+    ///
+    /// Only compiles for domains whose allocator is stateless, since the storage of `boxed` is
+    /// otherwise allocated by another instance of it. Use
+    /// [`try_new_from_box`][Self::try_new_from_box] with a box allocated by
+    /// [`Domain::allocator`] instead.
+    ///
+    pub fn from_box_in(boxed: Box<T, D::Alloc>, domain: D) -> Self {
+        let () = Self::STATELESS_ALLOC;
+        let (ptr, _) = Box::into_raw_with_allocator(boxed);
+        domain.allocated(mem::size_of::<T>());
+        published::publish(ptr);
+
+        Self {
+            ptr: AtomicPtr::new(ptr),
+            domain,
+            __mk: PhantomData,
+        }
+    }
+
+    /// Constructs the value directly in storage allocated using the domain's allocator, instead of
+    /// moving it there from the stack, which matters for large values.
+    ///
+    /// `init` must initialize the slot it is given and return it, usually with
+    /// [`MaybeUninit::write`].
+    ///
+    /// # Panics
+    ///
+    /// * If `init` returns a reference to anything other than the slot it was given.
+    ///
+    #[inline]
+    pub fn new_with_in<F>(init: F, domain: D) -> Self
+    where
+        F: FnOnce(&mut MaybeUninit<T>) -> &mut T,
+    {
+        let slot = match Self::try_alloc_uninit(domain) {
+            Ok(slot) => slot,
+            Err(AllocError) => handle_alloc_error(Layout::new::<T>()),
+        };
+        match Self::init_slot(slot, |slot| Ok::<_, Infallible>(init(slot)), domain) {
+            Ok(haz) => haz,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Same as [`HazBox::new_with_in`], but `init` may fail, in which case the storage is
+    /// deallocated and its error returned.
+    ///
+    /// If the storage can't be allocated, `init` isn't called and the [`AllocError`] is returned
+    /// converted into `E`.
+    ///
+    /// # Panics
+    ///
+    /// * Same as [`HazBox::new_with_in`].
+    ///
+    pub fn try_new_with_in<F, E>(init: F, domain: D) -> Result<Self, E>
+    where
+        F: FnOnce(&mut MaybeUninit<T>) -> Result<&mut T, E>,
+        E: From<AllocError>,
+    {
+        let slot = Self::try_alloc_uninit(domain)?;
+        Self::init_slot(slot, init, domain)
+    }
+
+    fn try_alloc_uninit(domain: D) -> Result<Box<MaybeUninit<T>, &'dom D::Alloc>, AllocError> {
+        if mem::size_of::<T>() != 0 && failpoints::hit(Failpoint::Alloc) {
+            return Err(AllocError);
+        }
+        Box::try_new_uninit_in(domain.allocator())
+    }
+
+    fn init_slot<F, E>(
+        mut slot: Box<MaybeUninit<T>, &'dom D::Alloc>,
+        init: F,
+        domain: D,
+    ) -> Result<Self, E>
+    where
+        F: FnOnce(&mut MaybeUninit<T>) -> Result<&mut T, E>,
+    {
+        let expected = slot.as_mut_ptr();
+        let init = init(&mut slot)? as *mut T;
+        assert!(
+            ptr::eq(init, expected),
+            "Initializer returned another value"
+        );
+
+        // Safety: The slot is initialized, since init returned an exclusive reference to it.
+        let (ptr, _) = Box::into_raw_with_allocator(unsafe { slot.assume_init() });
+        domain.allocated(mem::size_of::<T>());
+        published::publish(ptr);
+
+        Ok(Self {
+            ptr: AtomicPtr::new(ptr),
+            domain,
+            __mk: PhantomData,
+        })
+    }
+
+    /// Creates a box publishing `ptr` without owning it, for a [`HazStatic`][crate::hazstatic::HazStatic] to keep from being
+    /// dropped while it is published.
+    #[inline]
+    pub(crate) fn from_static_ptr(ptr: *mut T, domain: D) -> Self {
+        published::publish(ptr);
+
+        Self {
+            ptr: AtomicPtr::new(ptr),
+            domain,
+            __mk: PhantomData,
+        }
+    }
+
     #[inline]
     pub fn new_in(obj: T, domain: D) -> Self {
         match Self::try_new_in(obj, domain) {
@@ -84,27 +321,658 @@ where
         self.domain
     }
 
+    /// Returns a clone of the current value, protecting it with a new [`Anchor`] only while it is
+    /// cloned.
+    ///
+    /// Simpler than mooring the value for small read mostly values, like configurations, but
+    /// acquires a [`HazPtr`][crate::hazptr::HazPtr] on every call, thus loops should hold on to
+    /// an [`Anchor`] instead.
+    ///
+    #[inline]
+    pub fn load_cloned(&self) -> T
+    where
+        T: Clone,
+    {
+        Anchor::new_in(self.domain).moor(self).clone()
+    }
+
+    /// Returns a new box in the same domain holding a clone of the current value, e.g. to fork a
+    /// state machine into copies that can each be swapped on their own.
     #[inline]
-    pub fn swap(&self, with: &mut T) -> Retire<'dom, T, D> {
-        let old = self.ptr.swap(with as *mut T, Ordering::Relaxed);
+    pub fn clone_inner(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::new_in(self.load_cloned(), self.domain)
+    }
 
-        Retire::new_in(old, self.domain)
+    /// Returns the address of the current value, without its tag and without protecting it,
+    /// e.g. to pass to [`HazBox::compare_exchange`] or compare against later.
+    ///
+    /// The value may be replaced and reclaimed right after, thus the pointer must not be
+    /// dereferenced unless it is protected by an [`Anchor`].
+    ///
+    #[inline]
+    pub fn as_ptr(&self, order: Ordering) -> *mut T {
+        untagged(self.ptr.load(order))
+    }
+
+    /// Mask of the low bits of the pointer available for tags, those always zero due to the
+    /// alignment of `T`.
+    #[inline]
+    pub const fn tag_mask() -> usize {
+        mem::align_of::<T>() - 1
     }
 
+    /// Returns the tag of the current value.
     #[inline]
-    pub fn set(&self, to: &mut T) {
+    pub fn tag(&self, order: Ordering) -> usize {
+        tag_of(self.ptr.load(order))
+    }
+
+    /// Sets the bits of `tag` in the tag of the current value, without replacing it, returning the
+    /// previous tag. Meant for marking the value, e.g. as logically deleted.
+    ///
+    /// # Panics
+    ///
+    /// * If `tag` doesn't fit in [`HazBox::tag_mask`].
+    ///
+    #[inline]
+    pub fn fetch_or_tag(&self, tag: usize, order: Ordering) -> usize {
+        assert!(
+            tag <= Self::tag_mask(),
+            "Tag doesn't fit in the alignment bits"
+        );
+        tag_of(self.ptr.fetch_or(tag, order))
+    }
+
+    /// Whether `value`, usually a value previously [moored][Anchor::moor] from this box, is still
+    /// the current one, without protecting anything, e.g. to cheaply poll for changes.
+    ///
+    /// Taking a reference, rather than a pointer, ensures `value` is still protected, thus its
+    /// storage can't have been reused by a newer value that would compare equal to it.
+    ///
+    /// ```
+    /// # use anchorage::{anchor::Anchor, hazbox::HazBox};
+    /// let config = HazBox::new(String::from("initial"));
+    /// let mut anchor = Anchor::new();
+    ///
+    /// let seen = anchor.moor(&config);
+    /// assert!(config.ptr_eq(seen));
+    /// config.set(String::from("updated"));
+    /// assert!(!config.ptr_eq(seen));
+    /// ```
+    ///
+    #[inline]
+    pub fn ptr_eq(&self, value: &T) -> bool {
+        ptr::eq(self.as_ptr(Ordering::Acquire), value)
+    }
+
+    /// Takes the value back out of the box, e.g. to drain a structure at shutdown.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        *self.into_box()
+    }
+
+    /// Takes the storage of the value back out of the box, without reallocating.
+    pub fn into_box(self) -> Box<T, &'dom D::Alloc> {
+        let mut this = ManuallyDrop::new(self);
+        let ptr = untagged(*this.ptr.get_mut());
+        published::unpublish(ptr);
+        this.domain.deallocated(mem::size_of::<T>());
+        // Safety: Same as in drop, owning the box means no anchor can be protecting its value.
+        unsafe { Box::from_raw_in(ptr, this.domain.allocator()) }
+    }
+
+    /// Gives up ownership of the value, without its tag, returning its pointer along with the
+    /// domain, e.g. to stash it in a C struct or an intrusive node until it is reconstituted with
+    /// [`HazBox::from_raw`].
+    #[inline]
+    pub fn into_raw(self) -> (*mut T, D) {
+        let mut this = ManuallyDrop::new(self);
+        let ptr = untagged(*this.ptr.get_mut());
+        published::unpublish(ptr);
+        this.domain.deallocated(mem::size_of::<T>());
+        (ptr, this.domain)
+    }
+
+    /// Takes ownership of a value previously given up by [`HazBox::into_raw`].
+    ///
+    /// # Safety
+    ///
+    /// * `ptr` must point to a valid `T` allocated by `domain`'s [allocator][Domain::allocator]
+    /// with the layout of `T`, e.g. returned by [`HazBox::into_raw`] for the same domain.
+    /// * `ptr` must be exclusively owned, thus not be owned by another box, retired, nor be in use
+    /// anywhere else once passed here.
+    ///
+    #[inline]
+    pub unsafe fn from_raw(ptr: *mut T, domain: D) -> Self {
+        domain.allocated(mem::size_of::<T>());
+        published::publish(ptr);
+
+        Self {
+            ptr: AtomicPtr::new(ptr),
+            domain,
+            __mk: PhantomData,
+        }
+    }
+
+    /// Moves `with` into storage allocated using the domain's allocator and publishes it,
+    /// returning the old value to be retired.
+    #[inline]
+    pub fn swap(&self, with: T) -> Retire<'dom, T, D> {
+        match self.try_swap(with) {
+            Ok(retire) => retire,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazBox::swap`], but retires the old value immediately.
+    #[inline]
+    pub fn set(&self, to: T) {
         let _ = self.swap(to);
     }
+
+    /// Publishes a value swapped out of another box in the same domain, cancelling its
+    /// retirement, and returns the value it replaces to be retired. The value is thus moved
+    /// between boxes without being reallocated.
+    ///
+    /// Boxes free their value right away once they are dropped, thus this blocks the current
+    /// thread until no reader still protects the value through the other box, like
+    /// [`Retire::retire_by`] does. Values from domains that can't [enumerate] their
+    /// [`HazPtrs`][crate::hazptr::HazPtr] are handed back instead, since whether they are
+    /// protected can't be checked.
+    ///
+    /// [enumerate]: Domain::visit_hazptrs
+    ///
+    /// # Panics
+    ///
+    /// * If `retired` is not from the same domain as this box.
+    /// * If `retired` is [static][Retire::is_static].
+    ///
+    pub fn swap_from(
+        &self,
+        retired: Retire<'dom, T, D>,
+    ) -> Result<Retire<'dom, T, D>, Retire<'dom, T, D>> {
+        assert!(self.domain == retired.domain());
+        assert!(
+            !retired.is_static(),
+            "Static value can't be moved between boxes"
+        );
+        if !retired.wait_unprotected() {
+            return Err(retired);
+        }
+        let new = retired.into_raw().as_ptr();
+        published::publish(new);
+        let old = untagged(self.ptr.swap(new, Ordering::AcqRel));
+        published::unpublish(old);
+
+        Ok(Retire::new_in(old, self.domain))
+    }
+
+    /// Publishes the storage of `with` without reallocating, returning the old value to be
+    /// retired. Large values can thus be built in place ahead of time, and published without
+    /// being copied.
+    ///
+    /// # Panics
+    ///
+    /// * If `with` wasn't allocated by the domain's allocator.
+    ///
+    pub fn swap_boxed(&self, with: Box<T, &'dom D::Alloc>) -> Retire<'dom, T, D> {
+        assert!(Self::allocated_by(*Box::allocator(&with), self.domain));
+        let (new, _) = Box::into_raw_with_allocator(with);
+        self.domain.allocated(mem::size_of::<T>());
+        published::publish(new);
+        let old = untagged(self.ptr.swap(new, Ordering::AcqRel));
+        published::unpublish(old);
+
+        Retire::new_in(old, self.domain)
+    }
+
+    /// Allocates storage for `with` using the domain's allocator and publishes it, returning the
+    /// old value to be retired.
+    ///
+    /// Returns an [`AllocError`] instead of calling [`handle_alloc_error`] if the allocation
+    /// fails, in which case the box is left unchanged.
+    pub fn try_swap(&self, with: T) -> Result<Retire<'dom, T, D>, AllocError> {
+        let new = Self::try_alloc(with, self.domain)?;
+        published::publish(new);
+        let old = untagged(self.ptr.swap(new, Ordering::AcqRel));
+        published::unpublish(old);
+
+        Ok(Retire::new_in(old, self.domain))
+    }
+
+    /// Same as [`HazBox::swap`], but tags the new value with `tag`, returning the tag of the old
+    /// value along with it.
+    ///
+    /// # Panics
+    ///
+    /// * If `tag` doesn't fit in [`HazBox::tag_mask`].
+    ///
+    pub fn swap_tagged(&self, with: T, tag: usize) -> (Retire<'dom, T, D>, usize) {
+        let new = match Self::try_alloc(with, self.domain) {
+            Ok(new) => new,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        };
+        published::publish(new);
+        let old = self.ptr.swap(tagged(new, tag), Ordering::AcqRel);
+        published::unpublish(untagged(old));
+
+        (Retire::new_in(untagged(old), self.domain), tag_of(old))
+    }
+
+    /// Publishes `new` only if the box still holds `expected`, usually the address of a value
+    /// previously [moored][Anchor::moor] from it, untagged, returning the old value to be retired.
+    ///
+    /// Otherwise `new` is dropped and the value currently held is returned.
+    ///
+    #[inline]
+    pub fn compare_exchange(&self, expected: *mut T, new: T) -> Result<Retire<'dom, T, D>, *mut T> {
+        match self.try_compare_exchange(expected, new) {
+            Ok(exchanged) => exchanged,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazBox::compare_exchange`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`] if allocating `new` fails, in which case the box is left unchanged.
+    #[inline]
+    pub fn try_compare_exchange(
+        &self,
+        expected: *mut T,
+        new: T,
+    ) -> Result<Result<Retire<'dom, T, D>, *mut T>, AllocError> {
+        self.try_compare_exchange_tagged(expected, 0, new, 0)
+            .map(|exchanged| exchanged.map_err(|(actual, _)| actual))
+    }
+
+    /// Same as [`HazBox::compare_exchange`], but only publishes `new` tagged with `new_tag` if the
+    /// current value is also still tagged with `expected_tag`, returning the current value and
+    /// its tag otherwise.
+    ///
+    /// # Panics
+    ///
+    /// * If either tag doesn't fit in [`HazBox::tag_mask`].
+    ///
+    pub fn compare_exchange_tagged(
+        &self,
+        expected: *mut T,
+        expected_tag: usize,
+        new: T,
+        new_tag: usize,
+    ) -> TaggedExchange<'dom, T, D> {
+        match self.try_compare_exchange_tagged(expected, expected_tag, new, new_tag) {
+            Ok(exchanged) => exchanged,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazBox::compare_exchange_tagged`], but returns an [`AllocError`] instead of
+    /// calling [`handle_alloc_error`] if allocating `new` fails, in which case the box is left
+    /// unchanged.
+    ///
+    /// # Panics
+    ///
+    /// * If either tag doesn't fit in [`HazBox::tag_mask`].
+    ///
+    pub fn try_compare_exchange_tagged(
+        &self,
+        expected: *mut T,
+        expected_tag: usize,
+        new: T,
+        new_tag: usize,
+    ) -> Result<TaggedExchange<'dom, T, D>, AllocError> {
+        self.try_compare_exchange_with(
+            tagged(expected, expected_tag),
+            new,
+            new_tag,
+            |ptr, current, new| {
+                ptr.compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)
+            },
+        )
+    }
+
+    /// Same as [`HazBox::compare_exchange`], but may fail even if the box holds `expected`, which
+    /// is cheaper on some platforms when called in a loop, like [`AtomicPtr::compare_exchange_weak`].
+    #[inline]
+    pub fn compare_exchange_weak(
+        &self,
+        expected: *mut T,
+        new: T,
+    ) -> Result<Retire<'dom, T, D>, *mut T> {
+        match self.try_compare_exchange_weak(expected, new) {
+            Ok(exchanged) => exchanged,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazBox::compare_exchange_weak`], but returns an [`AllocError`] instead of
+    /// calling [`handle_alloc_error`] if allocating `new` fails, in which case the box is left
+    /// unchanged.
+    #[inline]
+    pub fn try_compare_exchange_weak(
+        &self,
+        expected: *mut T,
+        new: T,
+    ) -> Result<Result<Retire<'dom, T, D>, *mut T>, AllocError> {
+        self.try_compare_exchange_with(expected, new, 0, |ptr, current, new| {
+            ptr.compare_exchange_weak(current, new, Ordering::AcqRel, Ordering::Acquire)
+        })
+        .map(|exchanged| exchanged.map_err(|(actual, _)| actual))
+    }
+
+    /// Allocates `new` and publishes it tagged with `new_tag` using `exchange`, only if the box
+    /// holds `expected`, dropping `new` if it doesn't.
+    fn try_compare_exchange_with<X>(
+        &self,
+        expected: *mut T,
+        new: T,
+        new_tag: usize,
+        exchange: X,
+    ) -> Result<TaggedExchange<'dom, T, D>, AllocError>
+    where
+        X: FnOnce(&AtomicPtr<T>, *mut T, *mut T) -> Result<*mut T, *mut T>,
+    {
+        let new = Self::try_alloc(new, self.domain)?;
+
+        published::publish(new);
+        Ok(match exchange(&self.ptr, expected, tagged(new, new_tag)) {
+            Ok(old) => {
+                published::unpublish(untagged(old));
+                Ok(Retire::new_in(untagged(old), self.domain))
+            }
+            Err(actual) => {
+                published::unpublish(new);
+                self.domain.deallocated(mem::size_of::<T>());
+                // Safety: new was never visible to other threads, thus it can't be protected.
+                drop(unsafe { Box::from_raw_in(new, self.domain.allocator()) });
+                Err((untagged(actual), tag_of(actual)))
+            }
+        })
+    }
+
+    /// Replaces the value with the one built by `update` from the current one, returning the old
+    /// value to be retired, or [`None`] if `update` returned [`None`].
+    ///
+    /// If the value is replaced while `update` runs, its result is dropped and `update` is called
+    /// again with the newer value, like read-copy-update. The new value keeps the tag of the old.
+    ///
+    pub fn fetch_update<F>(&self, update: F) -> Option<Retire<'dom, T, D>>
+    where
+        F: FnMut(&T) -> Option<T>,
+    {
+        match self.try_fetch_update(update) {
+            Ok(old) => old,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazBox::fetch_update`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`] if allocating a new value fails, in which case the box is left
+    /// unchanged.
+    pub fn try_fetch_update<F>(
+        &self,
+        mut update: F,
+    ) -> Result<Option<Retire<'dom, T, D>>, AllocError>
+    where
+        F: FnMut(&T) -> Option<T>,
+    {
+        let mut anchor = Anchor::new_in(self.domain);
+
+        loop {
+            let (current, tag) = anchor.moor_tagged(self);
+            let expected = current as *const T as *mut T;
+            let new = match update(current) {
+                Some(new) => new,
+                None => return Ok(None),
+            };
+            if let Ok(old) = self.try_compare_exchange_tagged(expected, tag, new, tag)? {
+                return Ok(Some(old));
+            }
+        }
+    }
+
+    /// Publishes `new` only if `pred` holds for the current value, returning the old value to be
+    /// retired, or `new` back if it doesn't.
+    ///
+    /// If the value is replaced while `pred` runs, `pred` is called again with the newer value.
+    /// `new` is allocated only once, and keeps the tag of the value it replaces.
+    ///
+    pub fn swap_if<P>(&self, new: T, pred: P) -> Result<Retire<'dom, T, D>, T>
+    where
+        P: Fn(&T) -> bool,
+    {
+        match self.try_swap_if(new, pred) {
+            Ok(swapped) => swapped,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazBox::swap_if`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`] if allocating `new` fails, in which case the box is left unchanged.
+    pub fn try_swap_if<P>(
+        &self,
+        new: T,
+        pred: P,
+    ) -> Result<Result<Retire<'dom, T, D>, T>, AllocError>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let new = Self::try_alloc(new, self.domain)?;
+        let mut anchor = Anchor::new_in(self.domain);
+
+        loop {
+            let (current, tag) = anchor.moor_tagged(self);
+            if !pred(current) {
+                self.domain.deallocated(mem::size_of::<T>());
+                // Safety: new was never visible to other threads, thus it can't be protected.
+                return Ok(Err(*unsafe {
+                    Box::from_raw_in(new, self.domain.allocator())
+                }));
+            }
+
+            let expected = tagged(current as *const T as *mut T, tag);
+            published::publish(new);
+            match self.ptr.compare_exchange(
+                expected,
+                tagged(new, tag),
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            ) {
+                Ok(old) => {
+                    published::unpublish(untagged(old));
+                    return Ok(Ok(Retire::new_in(untagged(old), self.domain)));
+                }
+                Err(_) => published::unpublish(new),
+            }
+        }
+    }
+
+    /// Replaces the value with the one built by `replace` from the current one, returning the old
+    /// value to be retired.
+    ///
+    /// Unlike [`HazBox::fetch_update`], the new value is published unconditionally, thus meant for
+    /// a single writer, otherwise the update of another writer in the meantime is lost.
+    ///
+    pub fn replace_with<F>(&self, replace: F) -> Retire<'dom, T, D>
+    where
+        F: FnOnce(&T) -> T,
+    {
+        match self.try_replace_with(replace) {
+            Ok(old) => old,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazBox::replace_with`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`] if allocating the new value fails, in which case the box is left
+    /// unchanged.
+    pub fn try_replace_with<F>(&self, replace: F) -> Result<Retire<'dom, T, D>, AllocError>
+    where
+        F: FnOnce(&T) -> T,
+    {
+        let new = {
+            let mut anchor = Anchor::new_in(self.domain);
+            replace(anchor.moor(self))
+        };
+        self.try_swap(new)
+    }
+
+    /// Same as [`HazBox::try_swap`], but retires the old value immediately.
+    #[inline]
+    pub fn try_set(&self, to: T) -> Result<(), AllocError> {
+        self.try_swap(to).map(drop)
+    }
+
+    /// Whether storage allocated by `alloc` can be deallocated by the domain.
+    #[inline]
+    fn allocated_by(alloc: &D::Alloc, domain: D) -> bool {
+        // Stateless allocators have no address to compare, any instance deallocates the same.
+        mem::size_of::<D::Alloc>() == 0 || ptr::eq(alloc, domain.allocator())
+    }
+
+    #[inline]
+    pub(crate) fn try_alloc(obj: T, domain: D) -> Result<*mut T, AllocError> {
+        if mem::size_of::<T>() == 0 {
+            // Boxes of zero sized types never call their allocator, thus can't fail.
+            let (ptr, _) = Box::into_raw_with_allocator(Box::new_in(obj, domain.allocator()));
+            return Ok(ptr);
+        }
+        if failpoints::hit(Failpoint::Alloc) {
+            return Err(AllocError);
+        }
+        let (ptr, _) = Box::into_raw_with_allocator(Box::try_new_in(obj, domain.allocator())?);
+        domain.allocated(mem::size_of::<T>());
+        Ok(ptr)
+    }
+
+    /// Protects the current value with a new [`Anchor`] and returns a guard holding both, which
+    /// can be iterated by reference for as long as it is held.
+    ///
+    /// ```
+    /// # use anchorage::hazbox::HazBox;
+    /// let hazbox = HazBox::new(vec![1, 2, 3]);
+    ///
+    /// let mut sum = 0;
+    /// for item in &hazbox.iter_protected() {
+    ///     sum += item;
+    /// }
+    /// assert_eq!(sum, 6);
+    /// ```
+    ///
+    pub fn iter_protected(&self) -> IterGuard<'_, 'dom, T, D>
+    where
+        for<'a> &'a T: IntoIterator,
+    {
+        let mut anchor = Anchor::new_in(self.domain);
+        let value = anchor.moor(self) as *const T;
+
+        IterGuard {
+            value,
+            _anchor: anchor,
+            __mk: PhantomData,
+        }
+    }
 }
 
-impl<'dom, T, D> Drop for HazBox<'dom, T, D>
+// Safety: Like for a Box, the value is only dropped and deallocated, not otherwise accessed, thus
+// only its destructor must be able to run, which the PhantomData tells dropck.
+unsafe impl<'dom, #[may_dangle] T, D> Drop for HazBox<'dom, T, D>
 where
     D: Domain<'dom>,
     T: Hazard<'dom>,
 {
     fn drop(&mut self) {
+        let ptr = untagged(*self.ptr.get_mut());
+        published::unpublish(ptr);
+        self.domain.deallocated(mem::size_of::<T>());
         // Safety: We own self.ptr and have exclusive access to it, thus no anchor can be protecting
         // it, thus we can just drop it here, without retiring to the domain.
-        let _ = unsafe { Box::from_raw_in(self.ptr.get_mut(), self.domain.allocator()) };
+        let _ = unsafe { Box::from_raw_in(ptr, self.domain.allocator()) };
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'dom, T, D> serde::Serialize for HazBox<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom> + serde::Serialize,
+{
+    /// Serializes the current value, protected by a new [`Anchor`] while it is serialized.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Anchor::new_in(self.domain).moor(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for HazBox<'static, T, GlobalDomain>
+where
+    T: Hazard<'static> + serde::Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+    where
+        De: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// Guard returned by [`HazBox::iter_protected`], keeping a snapshot of the value protected by its
+/// own [`Anchor`] for as long as it is held.
+pub struct IterGuard<'b, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    value: *const T,
+    _anchor: Anchor<'dom, D>,
+    __mk: PhantomData<&'b HazBox<'dom, T, D>>,
+}
+
+impl<'b, 'dom, T, D> IterGuard<'b, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    #[inline]
+    pub fn iter<'g>(&'g self) -> <&'g T as IntoIterator>::IntoIter
+    where
+        &'g T: IntoIterator,
+    {
+        (**self).into_iter()
+    }
+}
+
+impl<'b, 'dom, T, D> Deref for IterGuard<'b, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Safety: The value was moored by our anchor, which keeps protecting it until it's dropped.
+        unsafe { &*self.value }
+    }
+}
+
+impl<'g, 'b, 'dom, T, D> IntoIterator for &'g IterGuard<'b, 'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+    &'g T: IntoIterator,
+{
+    type IntoIter = <&'g T as IntoIterator>::IntoIter;
+    type Item = <&'g T as IntoIterator>::Item;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
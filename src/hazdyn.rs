@@ -0,0 +1,546 @@
+use std::{
+    alloc::{
+        handle_alloc_error,
+        AllocError,
+        Allocator,
+        Layout,
+    },
+    iter::FromIterator,
+    marker::{
+        PhantomData,
+        Unsize,
+    },
+    mem::{
+        self,
+        MaybeUninit,
+    },
+    ptr::{
+        self,
+        NonNull,
+    },
+    sync::atomic::{
+        AtomicPtr,
+        Ordering,
+    },
+};
+
+use crate::{
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    failpoints::{
+        self,
+        Failpoint,
+    },
+    metrics::hazard_size,
+    published,
+    Hazard,
+};
+
+/// Start of every allocation made by a [`HazDyn`], pointing at the rest of it.
+pub(crate) struct Header<'dom, U>
+where
+    U: ?Sized,
+{
+    /// The value, as the unsized type readers see.
+    pub(crate) value: NonNull<U>,
+    /// The whole allocation, as the type it was allocated with, to retire it with its layout.
+    node: NonNull<dyn Hazard<'dom> + 'dom>,
+}
+
+#[repr(C)]
+struct Node<'dom, U, T>
+where
+    U: ?Sized,
+{
+    /// First, so that the header is found at the start of the allocation whatever T is.
+    /// Only written once the node is in its final place, since it points into it.
+    header: MaybeUninit<Header<'dom, U>>,
+    value: T,
+}
+
+// Safety: The header only points into the node itself, which is shared like the value is.
+unsafe impl<'dom, U, T> Send for Node<'dom, U, T>
+where
+    U: ?Sized,
+    T: Send,
+{
+}
+
+// Safety: Same as for Send.
+unsafe impl<'dom, U, T> Sync for Node<'dom, U, T>
+where
+    U: ?Sized,
+    T: Sync,
+{
+}
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Dropping the node only drops the value.
+unsafe impl<'dom, U, T> Hazard<'dom> for Node<'dom, U, T>
+where
+    U: ?Sized + 'dom,
+    T: Hazard<'dom>,
+{
+}
+
+/// Start of the single allocation holding a slice or `str` whose length is only known at runtime,
+/// followed by its elements.
+#[repr(C)]
+struct InlineHead<'dom, U, A>
+where
+    U: ?Sized,
+    A: Allocator,
+{
+    /// First, like in a [`Node`].
+    header: Header<'dom, U>,
+    /// The whole allocation, elements included.
+    layout: Layout,
+    allocator: &'dom A,
+}
+
+/// Retired in place of a slice or `str` stored inline, from the start of its allocation.
+///
+/// Values whose layout is only known at runtime can't be retired as a `dyn Hazard`, whose vtable
+/// holds a single size. This marker is zero sized instead, thus never deallocated by the box it is
+/// reclaimed through, and when dropped it drops the value and deallocates the whole allocation
+/// itself, with the layout recorded in the [`InlineHead`] it is found at.
+struct Inline<'dom, U, A>(PhantomData<(Box<U>, &'dom A)>)
+where
+    U: ?Sized,
+    A: Allocator;
+
+// Safety: The allocator is only used to deallocate the storage, which domains already do from
+// whichever thread reclaims it.
+unsafe impl<'dom, U, A> Send for Inline<'dom, U, A>
+where
+    U: ?Sized + Send,
+    A: Allocator,
+{
+}
+
+// Safety: The marker itself can't be used to reach the value.
+unsafe impl<'dom, U, A> Sync for Inline<'dom, U, A>
+where
+    U: ?Sized + Sync,
+    A: Allocator,
+{
+}
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Dropping it only drops the value and deallocates its storage.
+unsafe impl<'dom, U, A> Hazard<'dom> for Inline<'dom, U, A>
+where
+    U: ?Sized + Hazard<'dom>,
+    A: Allocator + 'dom,
+{
+}
+
+impl<'dom, U, A> Drop for Inline<'dom, U, A>
+where
+    U: ?Sized,
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        let head = (self as *mut Self).cast::<InlineHead<'dom, U, A>>();
+        // Safety: Markers are only ever retired from the start of a fully initialized head, and
+        // dropped once, along with the value and the allocation they stand for.
+        unsafe {
+            let InlineHead {
+                header,
+                layout,
+                allocator,
+            } = ptr::read(head);
+            ptr::drop_in_place(header.value.as_ptr());
+            allocator.deallocate(NonNull::new_unchecked(head.cast()), layout);
+        }
+    }
+}
+
+/// A [`HazBox`][crate::hazbox::HazBox] holding an unsized value, like a trait object, e.g. to hot
+/// swap plugin implementations.
+///
+/// Each value is stored along with its metadata in a single allocation behind a thin pointer, so
+/// that it can still be swapped atomically, and readers don't go through a second indirection like
+/// with a `HazBox<Box<dyn Trait>>`. Values are retired as the concrete type they were stored as,
+/// thus deallocated with its layout.
+///
+/// Slices and `str` of any length can be built with [`HazDyn::from_iter_in`],
+/// [`HazDyn::from_slice_in`] and [`HazDyn::from_str_in`], e.g. to publish a whole routing table
+/// that readers index into directly. Their elements are stored inline after the header too, and
+/// since their layout is only known at runtime, it is recorded along with them to deallocate them
+/// once reclaimed.
+///
+/// Values are protected with [`Anchor::moor_dyn`][crate::anchor::Anchor::moor_dyn].
+///
+/// ```
+/// # use anchorage::{anchor::Anchor, hazdyn::HazDyn};
+/// trait Plugin {
+///     fn run(&self, request: &str) -> String;
+/// }
+///
+/// struct Noop;
+/// struct Tracing {
+///     prefix: String,
+/// }
+/// # #[cfg(feature = "explicit-hazard")]
+/// # unsafe impl<'dom> anchorage::Hazard<'dom> for Noop {}
+/// # #[cfg(feature = "explicit-hazard")]
+/// # unsafe impl<'dom> anchorage::Hazard<'dom> for Tracing {}
+/// # impl Plugin for Noop {
+/// #     fn run(&self, request: &str) -> String {
+/// #         request.to_owned()
+/// #     }
+/// # }
+/// # impl Plugin for Tracing {
+/// #     fn run(&self, request: &str) -> String {
+/// #         format!("{}{}", self.prefix, request)
+/// #     }
+/// # }
+///
+/// let plugin: HazDyn<dyn Plugin + Send + Sync, _> = HazDyn::new(Noop);
+///
+/// plugin.set(Tracing {
+///     prefix: String::from("traced "),
+/// });
+///
+/// let mut anchor = Anchor::new();
+/// assert_eq!(anchor.moor_dyn(&plugin).run("/"), "traced /");
+/// ```
+///
+pub struct HazDyn<'dom, U, D>
+where
+    D: Domain<'dom>,
+    U: ?Sized + Send + Sync + 'dom,
+{
+    pub(crate) ptr: AtomicPtr<Header<'dom, U>>,
+    pub(crate) domain: D,
+    __mk: PhantomData<(&'dom D, Box<U>)>,
+}
+
+impl<U> HazDyn<'static, U, GlobalDomain>
+where
+    U: ?Sized + Send + Sync + 'static,
+{
+    #[inline]
+    pub fn new<T>(obj: T) -> Self
+    where
+        T: Hazard<'static> + Unsize<U>,
+    {
+        Self::new_in(obj, GlobalDomain)
+    }
+}
+
+impl<'dom, U, D> HazDyn<'dom, U, D>
+where
+    D: Domain<'dom>,
+    U: ?Sized + Send + Sync + 'dom,
+{
+    pub fn try_new_in<T>(obj: T, domain: D) -> Result<Self, AllocError>
+    where
+        T: Hazard<'dom> + Unsize<U>,
+    {
+        Ok(Self {
+            ptr: AtomicPtr::new(Self::try_alloc(obj, domain)?),
+            domain,
+            __mk: PhantomData,
+        })
+    }
+
+    #[inline]
+    pub fn new_in<T>(obj: T, domain: D) -> Self
+    where
+        T: Hazard<'dom> + Unsize<U>,
+    {
+        match Self::try_new_in(obj, domain) {
+            Ok(haz) => haz,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    /// Publishes `obj`, which may be of a different type than the current value, and retires the
+    /// current value.
+    pub fn set<T>(&self, obj: T)
+    where
+        T: Hazard<'dom> + Unsize<U>,
+    {
+        let new = match Self::try_alloc(obj, self.domain) {
+            Ok(new) => new,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        };
+        // Safety: The node was just allocated with our domain.
+        unsafe { self.publish(new) }
+    }
+
+    /// Swaps in the already published `new` node and retires the current one.
+    ///
+    /// # Safety
+    ///
+    /// * `new` must have been allocated by [`HazDyn::try_alloc_node`] with this box's domain.
+    ///
+    unsafe fn publish(&self, new: *mut Header<'dom, U>) {
+        let old = self.ptr.swap(new, Ordering::AcqRel);
+        published::unpublish(old);
+
+        // Safety: The node was allocated using the domain's allocator, and was just swapped out,
+        // so no new anchor can start protecting it.
+        unsafe { self.domain.retire((*old).node) }
+    }
+
+    #[inline]
+    fn try_alloc<T>(obj: T, domain: D) -> Result<*mut Header<'dom, U>, AllocError>
+    where
+        T: Hazard<'dom> + Unsize<U>,
+    {
+        Self::try_alloc_node(obj, domain, |value| value)
+    }
+
+    /// Allocates a node holding `obj`, whose header points at the value returned by `project`
+    /// from a pointer to `obj` in its final place.
+    fn try_alloc_node<T, P>(
+        obj: T,
+        domain: D,
+        project: P,
+    ) -> Result<*mut Header<'dom, U>, AllocError>
+    where
+        T: Hazard<'dom>,
+        P: FnOnce(*mut T) -> *mut U,
+    {
+        if failpoints::hit(Failpoint::Alloc) {
+            return Err(AllocError);
+        }
+        let node = Box::try_new_in(
+            Node::<'dom, U, T> {
+                header: MaybeUninit::uninit(),
+                value: obj,
+            },
+            domain.allocator(),
+        )?;
+        let (node, _) = Box::into_raw_with_allocator(node);
+        domain.allocated(mem::size_of::<Node<'dom, U, T>>());
+
+        // Safety: The node was just allocated, and isn't shared yet.
+        unsafe {
+            (*node).header.write(Header {
+                value: NonNull::new_unchecked(project(ptr::addr_of_mut!((*node).value))),
+                node: NonNull::new_unchecked(node as *mut (dyn Hazard<'dom> + 'dom)),
+            });
+        }
+        let header = node.cast::<Header<'dom, U>>();
+        published::publish(header);
+        Ok(header)
+    }
+}
+
+impl<T> HazDyn<'static, [T], GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    pub fn from_slice(slice: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_slice_in(slice, GlobalDomain)
+    }
+}
+
+impl<T> FromIterator<T> for HazDyn<'static, [T], GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::from_iter_in(iter, GlobalDomain)
+    }
+}
+
+impl<'dom, T, D> HazDyn<'dom, [T], D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    /// Creates a box holding every item of `iter`, in order, as a single slice.
+    pub fn from_iter_in<I>(iter: I, domain: D) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::from_header(
+            Self::alloc_items(iter.into_iter().collect(), domain),
+            domain,
+        )
+    }
+
+    #[inline]
+    pub fn from_slice_in(slice: &[T], domain: D) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_iter_in(slice.iter().cloned(), domain)
+    }
+
+    /// Publishes every item of `iter` as the new slice, which may be of a different length than
+    /// the current one, and retires the current slice.
+    pub fn set_from_iter<I>(&self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let new = Self::alloc_items(iter.into_iter().collect(), self.domain);
+        // Safety: The node was just allocated with our domain.
+        unsafe { self.publish(new) }
+    }
+
+    #[inline]
+    pub fn set_from_slice(&self, slice: &[T])
+    where
+        T: Clone,
+    {
+        self.set_from_iter(slice.iter().cloned())
+    }
+
+    fn alloc_items(mut items: Vec<T>, domain: D) -> *mut Header<'dom, [T]> {
+        // Safety: The items are moved into the node, and forgotten by the vector if they were.
+        match unsafe { Self::try_alloc_inline(items.as_ptr(), items.len(), domain, |items| items) }
+        {
+            Ok(new) => {
+                // Safety: The items are now owned by the node.
+                unsafe { items.set_len(0) };
+                new
+            }
+            Err(_) => handle_alloc_error(Layout::for_value(&*items)),
+        }
+    }
+}
+
+impl From<&str> for HazDyn<'static, str, GlobalDomain> {
+    #[inline]
+    fn from(text: &str) -> Self {
+        Self::from_str_in(text, GlobalDomain)
+    }
+}
+
+impl<'dom, D> HazDyn<'dom, str, D>
+where
+    D: Domain<'dom>,
+{
+    #[inline]
+    pub fn from_str_in(text: &str, domain: D) -> Self {
+        Self::from_header(Self::alloc_str(text, domain), domain)
+    }
+
+    /// Publishes a copy of `text` and retires the current one.
+    pub fn set_str(&self, text: &str) {
+        let new = Self::alloc_str(text, self.domain);
+        // Safety: The node was just allocated with our domain.
+        unsafe { self.publish(new) }
+    }
+
+    fn alloc_str(text: &str, domain: D) -> *mut Header<'dom, str> {
+        let bytes = text.as_bytes();
+        // Safety: The bytes are only copied, and valid UTF-8 since they come from a str.
+        let project = |bytes: *mut [u8]| bytes as *mut str;
+        match unsafe { Self::try_alloc_inline(bytes.as_ptr(), bytes.len(), domain, project) } {
+            Ok(new) => new,
+            Err(_) => handle_alloc_error(Layout::for_value(bytes)),
+        }
+    }
+}
+
+impl<'dom, U, D> HazDyn<'dom, U, D>
+where
+    D: Domain<'dom>,
+    U: ?Sized + Hazard<'dom>,
+{
+    fn from_header(header: *mut Header<'dom, U>, domain: D) -> Self {
+        Self {
+            ptr: AtomicPtr::new(header),
+            domain,
+            __mk: PhantomData,
+        }
+    }
+
+    /// Allocates a node holding a bitwise copy of the `len` items at `items`, stored inline after
+    /// its head, whose header points at the value returned by `project` from the copied items.
+    ///
+    /// The node isn't counted as [allocated][Domain::allocated], since the marker it is retired as
+    /// is zero sized, and thus reclaimed as such.
+    ///
+    /// # Safety
+    ///
+    /// * `items` must be valid for reads of `len` items.
+    ///
+    /// * If this succeeds the items are owned by the node, and must not be dropped by the caller.
+    ///
+    /// * `project` must return a valid value of `U` from the copied items.
+    ///
+    unsafe fn try_alloc_inline<T, P>(
+        items: *const T,
+        len: usize,
+        domain: D,
+        project: P,
+    ) -> Result<*mut Header<'dom, U>, AllocError>
+    where
+        P: FnOnce(*mut [T]) -> *mut U,
+    {
+        if failpoints::hit(Failpoint::Alloc) {
+            return Err(AllocError);
+        }
+        let elements = Layout::array::<T>(len).map_err(|_| AllocError)?;
+        let (layout, offset) = Layout::new::<InlineHead<'dom, U, D::Alloc>>()
+            .extend(elements)
+            .map_err(|_| AllocError)?;
+        let layout = layout.pad_to_align();
+        let allocator = domain.allocator();
+        let head = allocator
+            .allocate(layout)?
+            .cast::<InlineHead<'dom, U, D::Alloc>>()
+            .as_ptr();
+
+        // Safety: The allocation fits the head followed by the items, and isn't shared yet.
+        unsafe {
+            let start = head.cast::<u8>().add(offset).cast::<T>();
+            ptr::copy_nonoverlapping(items, start, len);
+            let marker = head.cast::<Inline<'dom, U, D::Alloc>>();
+            head.write(InlineHead {
+                header: Header {
+                    value: NonNull::new_unchecked(project(ptr::slice_from_raw_parts_mut(
+                        start, len,
+                    ))),
+                    node: NonNull::new_unchecked(marker as *mut (dyn Hazard<'dom> + 'dom)),
+                },
+                layout,
+                allocator,
+            });
+        }
+        let header = head.cast::<Header<'dom, U>>();
+        published::publish(header);
+        Ok(header)
+    }
+}
+
+impl<'dom, U, D> Drop for HazDyn<'dom, U, D>
+where
+    D: Domain<'dom>,
+    U: ?Sized + Send + Sync + 'dom,
+{
+    fn drop(&mut self) {
+        let header = *self.ptr.get_mut();
+        published::unpublish(header);
+        // Safety: Same as for HazBox, owning the box means no anchor can be protecting its value,
+        // and the node was allocated using the domain's allocator.
+        unsafe {
+            let node = (*header).node;
+            self.domain.deallocated(hazard_size(node));
+            let _ = Box::from_raw_in(node.as_ptr(), self.domain.allocator());
+        }
+    }
+}
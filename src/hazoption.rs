@@ -0,0 +1,176 @@
+use std::{
+    alloc::{
+        handle_alloc_error,
+        AllocError,
+        Layout,
+    },
+    marker::PhantomData,
+    mem::{
+        self,
+        MaybeUninit,
+    },
+    ptr,
+    sync::atomic::{
+        AtomicPtr,
+        Ordering,
+    },
+};
+
+use crate::{
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazbox::HazBox,
+    published,
+    retire::Retire,
+    Hazard,
+};
+
+/// A [`HazBox`] that may be empty, holding a null pointer instead of a value.
+///
+/// Meant for links that are often empty, like the next pointer of the last node in a queue, which
+/// would otherwise need a sentinel value allocated to stand in for the missing one.
+///
+/// Values are protected with [`Anchor::moor_opt`][crate::anchor::Anchor::moor_opt], which doesn't
+/// protect anything while the box is empty.
+///
+pub struct HazOption<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    pub(crate) ptr: AtomicPtr<T>,
+    pub(crate) domain: D,
+    __mk: PhantomData<&'dom D>,
+}
+
+impl<T> HazOption<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    pub fn new(obj: Option<T>) -> Self {
+        Self::new_in(obj, GlobalDomain)
+    }
+}
+
+impl<T> Default for HazOption<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::none_in(GlobalDomain)
+    }
+}
+
+impl<'dom, T, D> HazOption<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    pub fn try_new_in(obj: Option<T>, domain: D) -> Result<Self, AllocError> {
+        let ptr = Self::try_alloc(obj, domain)?;
+        Ok(Self {
+            ptr: AtomicPtr::new(ptr),
+            domain,
+            __mk: PhantomData,
+        })
+    }
+
+    #[inline]
+    pub fn new_in(obj: Option<T>, domain: D) -> Self {
+        match Self::try_new_in(obj, domain) {
+            Ok(haz) => haz,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Creates an empty box, which allocates nothing.
+    #[inline]
+    pub const fn none_in(domain: D) -> Self {
+        Self {
+            ptr: AtomicPtr::new(ptr::null_mut()),
+            domain,
+            __mk: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    /// Whether the box is currently empty. It may be filled or emptied right after.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.ptr.load(Ordering::Relaxed).is_null()
+    }
+
+    #[inline]
+    pub fn is_some(&self) -> bool {
+        !self.is_none()
+    }
+
+    /// Publishes `with`, or empties the box if it is [`None`], returning the old value to be
+    /// retired, if there was one.
+    #[inline]
+    pub fn swap_opt(&self, with: Option<T>) -> Option<Retire<'dom, T, D>> {
+        match self.try_swap_opt(with) {
+            Ok(retire) => retire,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazOption::swap_opt`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`] if the allocation fails, in which case the box is left unchanged.
+    pub fn try_swap_opt(&self, with: Option<T>) -> Result<Option<Retire<'dom, T, D>>, AllocError> {
+        let new = Self::try_alloc(with, self.domain)?;
+        let old = self.ptr.swap(new, Ordering::AcqRel);
+        if old.is_null() {
+            return Ok(None);
+        }
+        published::unpublish(old);
+
+        Ok(Some(Retire::new_in(old, self.domain)))
+    }
+
+    #[inline]
+    pub fn swap(&self, with: T) -> Option<Retire<'dom, T, D>> {
+        self.swap_opt(Some(with))
+    }
+
+    /// Empties the box, returning the old value to be retired, if there was one.
+    #[inline]
+    pub fn take(&self) -> Option<Retire<'dom, T, D>> {
+        // Never allocates, thus never fails.
+        self.try_swap_opt(None).ok().flatten()
+    }
+
+    fn try_alloc(obj: Option<T>, domain: D) -> Result<*mut T, AllocError> {
+        let ptr = match obj {
+            Some(obj) => HazBox::try_alloc(obj, domain)?,
+            None => return Ok(ptr::null_mut()),
+        };
+        published::publish(ptr);
+        Ok(ptr)
+    }
+}
+
+impl<'dom, T, D> Drop for HazOption<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    fn drop(&mut self) {
+        let ptr = *self.ptr.get_mut();
+        if ptr.is_null() {
+            return;
+        }
+        published::unpublish(ptr);
+        self.domain.deallocated(mem::size_of::<T>());
+        // Safety: Same as for HazBox, owning the box means no anchor can be protecting its value.
+        let _ = unsafe { Box::from_raw_in(ptr, self.domain.allocator()) };
+    }
+}
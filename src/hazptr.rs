@@ -1,15 +1,38 @@
+#[cfg(any(feature = "owner-tags", feature = "watchdog"))]
+use std::sync::atomic::AtomicU64;
+#[cfg(feature = "watchdog")]
+use std::sync::atomic::AtomicUsize;
 use std::{
     ptr,
     sync::atomic::{
         AtomicBool,
+        AtomicIsize,
         AtomicPtr,
         Ordering,
     },
 };
 
+use crate::{
+    metrics::MetricsSnapshot,
+    node_list::List,
+};
+
 pub struct HazPtr {
     ptr: AtomicPtr<u8>,
     active: AtomicBool,
+    /// When the current address started being protected, 0 if none is.
+    #[cfg(feature = "watchdog")]
+    protected_since: AtomicU64,
+    /// Address of the [`Bounds`][crate::domain::bounded::Bounds] the record was acquired under,
+    /// 0 if none.
+    #[cfg(feature = "watchdog")]
+    bounds: AtomicUsize,
+    /// Thread that acquired the record, 0 if none or if not acquired by an anchor.
+    #[cfg(feature = "owner-tags")]
+    owner: AtomicU64,
+    /// When the record was acquired by its owner.
+    #[cfg(feature = "owner-tags")]
+    acquired_at: AtomicU64,
 }
 
 impl HazPtr {
@@ -18,9 +41,60 @@ impl HazPtr {
         Self {
             ptr: AtomicPtr::new(ptr::null_mut()),
             active: AtomicBool::new(active),
+            #[cfg(feature = "watchdog")]
+            protected_since: AtomicU64::new(0),
+            #[cfg(feature = "watchdog")]
+            bounds: AtomicUsize::new(0),
+            #[cfg(feature = "owner-tags")]
+            owner: AtomicU64::new(0),
+            #[cfg(feature = "owner-tags")]
+            acquired_at: AtomicU64::new(0),
         }
     }
 
+    #[cfg(feature = "watchdog")]
+    #[inline]
+    pub(crate) fn protected_since(&self) -> u64 {
+        self.protected_since.load(Ordering::Relaxed)
+    }
+
+    /// Marks the record as acquired under the bounds at `bounds`, or none if 0.
+    #[cfg(feature = "watchdog")]
+    #[inline]
+    pub(crate) fn set_bounds(&self, bounds: usize) {
+        self.bounds.store(bounds, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "watchdog")]
+    #[inline]
+    pub(crate) fn bounds(&self) -> usize {
+        self.bounds.load(Ordering::Relaxed)
+    }
+
+    /// Tags the record as owned by the current thread, from now on.
+    #[cfg(feature = "owner-tags")]
+    #[inline]
+    pub(crate) fn tag_owner(&self) {
+        self.acquired_at
+            .store(crate::owner::now(), Ordering::Relaxed);
+        self.owner
+            .store(crate::owner::current_thread(), Ordering::Release);
+    }
+
+    #[cfg(feature = "owner-tags")]
+    #[inline]
+    pub(crate) fn untag_owner(&self) {
+        self.owner.store(0, Ordering::Release);
+    }
+
+    /// The owning thread, or 0 if none, and when it acquired the record.
+    #[cfg(feature = "owner-tags")]
+    #[inline]
+    pub(crate) fn owner(&self) -> (u64, u64) {
+        let owner = self.owner.load(Ordering::Acquire);
+        (owner, self.acquired_at.load(Ordering::Relaxed))
+    }
+
     #[inline]
     pub fn ptr(&self) -> *mut u8 {
         self.ptr.load(Ordering::Acquire)
@@ -29,10 +103,15 @@ impl HazPtr {
     #[inline]
     pub fn reset(&self) {
         self.ptr.store(std::ptr::null_mut(), Ordering::Release);
+        #[cfg(feature = "watchdog")]
+        self.protected_since.store(0, Ordering::Relaxed);
     }
 
     #[inline]
     pub fn protect(&self, ptr: *mut u8) {
+        #[cfg(feature = "watchdog")]
+        self.protected_since
+            .store(crate::watchdog::now(), Ordering::Relaxed);
         self.ptr.store(ptr, Ordering::Release);
     }
 
@@ -51,3 +130,186 @@ impl HazPtr {
                 .is_ok()
     }
 }
+
+/// Number of [`HazPtr`] records stored inline in every domain, before any heap node is allocated.
+pub(crate) const INLINE_HAZPTRS: usize = 8;
+
+/// The set of [`HazPtrs`][HazPtr] owned by a domain.
+///
+/// The first [`INLINE_HAZPTRS`] records are stored inline, so domains with few concurrent
+/// [`Anchors`][crate::anchor::Anchor] never allocate for them and scans touch a single contiguous
+/// block. Any further records are pushed onto a [`List`].
+///
+/// Also counts how many records are currently acquired, so that domains can cheaply tell when
+/// nothing can possibly be protected.
+pub(crate) struct HazPtrRecords {
+    inline: [HazPtr; INLINE_HAZPTRS],
+    pub(crate) list: List<HazPtr>,
+    active: AtomicIsize,
+    active_high_water: AtomicIsize,
+}
+
+impl HazPtrRecords {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            inline: [const { HazPtr::new(false) }; INLINE_HAZPTRS],
+            list: List::new(),
+            active: AtomicIsize::new(0),
+            active_high_water: AtomicIsize::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &HazPtr> {
+        self.inline.iter().chain(self.list.iter())
+    }
+
+    /// Total number of records, inline ones included.
+    #[inline]
+    pub fn count(&self) -> isize {
+        INLINE_HAZPTRS as isize + self.list.count.load()
+    }
+
+    /// Same as [`HazPtrRecords::count`], but may fall short by a few records, in exchange for
+    /// not summing every shard of the counter, see [`ShardedCounter::approx`].
+    ///
+    /// [`ShardedCounter::approx`]: crate::counter::ShardedCounter::approx
+    #[inline]
+    pub fn approx_count(&self) -> isize {
+        INLINE_HAZPTRS as isize + self.list.count.approx()
+    }
+
+    /// Creates inactive records until there are at least `total` of them.
+    pub fn reserve(&self, total: usize) {
+        for _ in (self.count() as usize)..total {
+            self.list.push_front(HazPtr::new(false));
+        }
+    }
+
+    /// Number of records currently acquired.
+    #[inline]
+    pub fn active(&self) -> isize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Checks that the records are consistent, see [`Domain::verify`][crate::domain::Domain::verify].
+    pub fn verify(&self) {
+        assert!(
+            self.active() >= 0,
+            "more HazPtrs were released than were acquired"
+        );
+    }
+
+    /// Fills in the hazptr side of `snapshot`.
+    pub fn metrics(&self, snapshot: MetricsSnapshot) -> MetricsSnapshot {
+        MetricsSnapshot {
+            hazptrs_total: self.count() as usize,
+            hazptrs_active: self.active().max(0) as usize,
+            hazptrs_active_high_water: self.active_high_water.load(Ordering::Relaxed) as usize,
+            ..snapshot
+        }
+    }
+
+    #[inline]
+    fn try_acquire_existing(&self) -> Option<&HazPtr> {
+        self.iter().find(|hp| hp.try_acquire())
+    }
+
+    #[inline]
+    fn acquire_new(&self) -> Option<&HazPtr> {
+        self.list.try_push_front(HazPtr::new(true)).ok()
+    }
+
+    /// Acquires an existing record, or creates a new one, returning [None] only if it can't be
+    /// allocated.
+    #[inline]
+    pub fn acquire(&self) -> Option<&HazPtr> {
+        // Must be counted before the record can be used to protect anything.
+        let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        self.active_high_water.fetch_max(active, Ordering::Relaxed);
+        let hazptr = self.try_acquire_existing().or_else(|| self.acquire_new());
+        if hazptr.is_none() {
+            self.active.fetch_sub(1, Ordering::SeqCst);
+        }
+        hazptr
+    }
+
+    /// Same as [`HazPtrRecords::acquire`], but never creates a new record, returning [None] if
+    /// every existing one is acquired.
+    #[inline]
+    pub fn try_acquire(&self) -> Option<&HazPtr> {
+        // Same as in acquire, counted before the record can be used, and uncounted if none is.
+        let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        match self.try_acquire_existing() {
+            Some(hazptr) => {
+                self.active_high_water.fetch_max(active, Ordering::Relaxed);
+                Some(hazptr)
+            }
+            None => {
+                self.active.fetch_sub(1, Ordering::SeqCst);
+                None
+            }
+        }
+    }
+
+    /// Acquires `N` records with a single update of the active count and a single scan of the
+    /// existing records, creating new ones for those it didn't find. Returns [None], releasing the
+    /// ones it got, if any of them can't be allocated.
+    pub fn acquire_many<const N: usize>(&self) -> Option<[&HazPtr; N]> {
+        // Same as in acquire, counted before any of the records can be used.
+        let active = self.active.fetch_add(N as isize, Ordering::SeqCst) + N as isize;
+        self.active_high_water.fetch_max(active, Ordering::Relaxed);
+        let mut existing = self.iter().filter(|hp| hp.try_acquire()).fuse();
+        let hazptrs = [(); N].map(|_| existing.next().or_else(|| self.acquire_new()));
+
+        if hazptrs.iter().all(Option::is_some) {
+            return Some(hazptrs.map(|hazptr| hazptr.unwrap()));
+        }
+        hazptrs.iter().flatten().for_each(|hazptr| hazptr.release());
+        self.active.fetch_sub(N as isize, Ordering::SeqCst);
+        None
+    }
+
+    /// Stops counting `hazptr` as acquired without releasing it, so that its owner can keep it in
+    /// a cache and [unstash] it later without scanning the records. It must not protect anything
+    /// while stashed.
+    ///
+    /// [unstash]: HazPtrRecords::unstash
+    ///
+    #[inline]
+    pub fn stash(&self, hazptr: &HazPtr) {
+        debug_assert!(hazptr.ptr().is_null(), "stashed a HazPtr still protecting");
+        self.active.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Counts a [stashed][HazPtrRecords::stash] record as acquired again.
+    #[inline]
+    pub fn unstash(&self) {
+        // Same as in acquire.
+        let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        self.active_high_water.fetch_max(active, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn release(&self, hazptr: &HazPtr) {
+        hazptr.release();
+        self.active.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Returns true if no record is acquired, thus nothing retired before the call can be protected.
+    ///
+    /// Any [`HazPtr`] acquired after this check will fail to validate pointers that were
+    /// unlinked before it, so those can be reclaimed immediately.
+    ///
+    /// Busy domains nearly always have records acquired, thus the heavy fence, which only makes
+    /// seeing none acquired meaningful, is skipped unless a plain load suggests none are.
+    #[inline]
+    pub fn is_quiescent(&self) -> bool {
+        if self.active.load(Ordering::Relaxed) != 0 {
+            return false;
+        }
+        crate::asymmetric_fence::heavy();
+        self.active.load(Ordering::SeqCst) == 0
+    }
+}
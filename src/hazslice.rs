@@ -0,0 +1,190 @@
+use std::{
+    alloc::{
+        handle_alloc_error,
+        AllocError,
+        Layout,
+    },
+    marker::PhantomData,
+    mem::{
+        self,
+        MaybeUninit,
+    },
+    ptr,
+    sync::atomic::{
+        AtomicPtr,
+        Ordering,
+    },
+};
+
+use crate::{
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazbox::HazBox,
+    published,
+    retire::Retire,
+    Hazard,
+};
+
+/// A fixed number of slots sharing a single domain, each independently swappable like a
+/// [`HazOption`][crate::hazoption::HazOption].
+///
+/// Meant for the buckets of hash tables and sharded registries, where an array of
+/// [`HazBoxes`][HazBox] would keep a copy of the domain for every slot. Slots start empty, and
+/// their values are protected with [`Anchor::moor_slot`][crate::anchor::Anchor::moor_slot].
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use anchorage::{anchor::Anchor, hazslice::HazSlice};
+/// # let hash = 1234_usize;
+/// let buckets = HazSlice::<BTreeMap<u32, String>, _, 64>::new();
+/// buckets.swap(hash % 64, BTreeMap::from([(1, String::from("one"))]));
+///
+/// let mut anchor = Anchor::new();
+/// let bucket = anchor.moor_slot(&buckets, hash % 64);
+/// assert_eq!(bucket.map(BTreeMap::len), Some(1));
+/// ```
+///
+pub struct HazSlice<'dom, T, D, const N: usize>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    pub(crate) slots: [AtomicPtr<T>; N],
+    pub(crate) domain: D,
+    __mk: PhantomData<(&'dom D, T)>,
+}
+
+impl<T, const N: usize> HazSlice<'static, T, GlobalDomain, N>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(GlobalDomain)
+    }
+}
+
+impl<T, const N: usize> Default for HazSlice<'static, T, GlobalDomain, N>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'dom, T, D, const N: usize> HazSlice<'dom, T, D, N>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    /// Creates a slice with every slot empty, which allocates nothing.
+    #[inline]
+    pub fn new_in(domain: D) -> Self {
+        Self {
+            slots: [(); N].map(|_| AtomicPtr::new(ptr::null_mut())),
+            domain,
+            __mk: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Whether the slot at `index` is currently empty. It may be filled or emptied right after.
+    ///
+    /// # Panics
+    ///
+    /// * If `index` is out of bounds.
+    ///
+    #[inline]
+    pub fn is_none(&self, index: usize) -> bool {
+        self.slots[index].load(Ordering::Relaxed).is_null()
+    }
+
+    /// Publishes `with` in the slot at `index`, or empties it if it is [`None`], returning the old
+    /// value to be retired, if there was one.
+    ///
+    /// # Panics
+    ///
+    /// * If `index` is out of bounds.
+    ///
+    #[inline]
+    pub fn swap_opt(&self, index: usize, with: Option<T>) -> Option<Retire<'dom, T, D>> {
+        match self.try_swap_opt(index, with) {
+            Ok(retire) => retire,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazSlice::swap_opt`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`] if the allocation fails, in which case the slot is left unchanged.
+    pub fn try_swap_opt(
+        &self,
+        index: usize,
+        with: Option<T>,
+    ) -> Result<Option<Retire<'dom, T, D>>, AllocError> {
+        let slot = &self.slots[index];
+        let new = match with {
+            Some(with) => HazBox::try_alloc(with, self.domain)?,
+            None => ptr::null_mut(),
+        };
+        if !new.is_null() {
+            published::publish(new);
+        }
+        let old = slot.swap(new, Ordering::AcqRel);
+        if old.is_null() {
+            return Ok(None);
+        }
+        published::unpublish(old);
+
+        Ok(Some(Retire::new_in(old, self.domain)))
+    }
+
+    #[inline]
+    pub fn swap(&self, index: usize, with: T) -> Option<Retire<'dom, T, D>> {
+        self.swap_opt(index, Some(with))
+    }
+
+    /// Empties the slot at `index`, returning the old value to be retired, if there was one.
+    #[inline]
+    pub fn take(&self, index: usize) -> Option<Retire<'dom, T, D>> {
+        // Never allocates, thus never fails.
+        self.try_swap_opt(index, None).ok().flatten()
+    }
+}
+
+impl<'dom, T, D, const N: usize> Drop for HazSlice<'dom, T, D, N>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    fn drop(&mut self) {
+        for slot in &mut self.slots {
+            let ptr = *slot.get_mut();
+            if ptr.is_null() {
+                continue;
+            }
+            published::unpublish(ptr);
+            self.domain.deallocated(mem::size_of::<T>());
+            // Safety: Same as for HazBox, owning the slice means no anchor can be protecting its
+            // values.
+            let _ = unsafe { Box::from_raw_in(ptr, self.domain.allocator()) };
+        }
+    }
+}
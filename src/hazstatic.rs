@@ -0,0 +1,134 @@
+use std::{
+    alloc::AllocError,
+    mem::ManuallyDrop,
+    sync::atomic::{
+        AtomicBool,
+        Ordering,
+    },
+};
+
+use crate::{
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazbox::{
+        untagged,
+        HazBox,
+    },
+    published,
+    retire::Retire,
+    Hazard,
+};
+
+/// A [`HazBox`] created from a static value without allocating, e.g. a default configuration
+/// replaced at runtime.
+///
+/// The static value is never dropped nor deallocated, swapping it out returns a [`Retire`] that
+/// leaves it alone, see [`Retire::is_static`], and only the values published after it are retired
+/// as usual.
+///
+/// Values are protected with [`Anchor::moor_static`][crate::anchor::Anchor::moor_static].
+///
+/// ```
+/// # use anchorage::{anchor::Anchor, hazstatic::HazStatic};
+/// static DEFAULT: &str = "localhost:8080";
+///
+/// let config = HazStatic::from_static(&DEFAULT);
+/// config.set("example.com:443");
+/// assert_eq!(*Anchor::new().moor_static(&config), "example.com:443");
+/// ```
+///
+pub struct HazStatic<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    /// Only dropped once the static value was swapped out, since it doesn't own it until then.
+    pub(crate) inner: ManuallyDrop<HazBox<'dom, T, D>>,
+    /// Address of the static value.
+    static_addr: usize,
+    /// Whether the static value is still published, cleared by the swap that takes it out, since
+    /// zero sized values all share the same address.
+    static_published: AtomicBool,
+}
+
+impl<T> HazStatic<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    pub fn from_static(obj: &'static T) -> Self {
+        Self::from_static_in(obj, GlobalDomain)
+    }
+}
+
+impl<'dom, T, D> HazStatic<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    pub fn from_static_in(obj: &'static T, domain: D) -> Self {
+        let ptr = obj as *const T as *mut T;
+        Self {
+            inner: ManuallyDrop::new(HazBox::from_static_ptr(ptr, domain)),
+            static_addr: ptr as usize,
+            static_published: AtomicBool::new(true),
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.inner.domain
+    }
+
+    /// Whether the box still holds the static value it was created from.
+    #[inline]
+    pub fn is_static(&self) -> bool {
+        self.static_published.load(Ordering::Acquire)
+    }
+
+    /// Same as [`HazBox::swap`].
+    #[inline]
+    pub fn swap(&self, with: T) -> Retire<'dom, T, D> {
+        self.retired(self.inner.swap(with))
+    }
+
+    /// Same as [`HazBox::try_swap`].
+    #[inline]
+    pub fn try_swap(&self, with: T) -> Result<Retire<'dom, T, D>, AllocError> {
+        self.inner.try_swap(with).map(|old| self.retired(old))
+    }
+
+    #[inline]
+    pub fn set(&self, to: T) {
+        let _ = self.swap(to);
+    }
+
+    /// Marks `old` as static if it is the static value, which only the first swap to take it
+    /// out does.
+    #[inline]
+    fn retired(&self, old: Retire<'dom, T, D>) -> Retire<'dom, T, D> {
+        let old = old.into_raw().as_ptr();
+        if old as usize == self.static_addr && self.static_published.swap(false, Ordering::AcqRel) {
+            Retire::new_static_in(old, self.domain())
+        } else {
+            Retire::new_in(old, self.domain())
+        }
+    }
+}
+
+impl<'dom, T, D> Drop for HazStatic<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    fn drop(&mut self) {
+        if *self.static_published.get_mut() {
+            published::unpublish(untagged(*self.inner.ptr.get_mut()));
+        } else {
+            // Safety: The static value was swapped out, thus the box owns its current value.
+            unsafe { ManuallyDrop::drop(&mut self.inner) }
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use std::{
+    alloc::{
+        handle_alloc_error,
+        AllocError,
+        Layout,
+    },
+    cmp,
+    marker::PhantomData,
+    mem::{
+        self,
+        MaybeUninit,
+    },
+    ptr,
+    sync::atomic::{
+        AtomicPtr,
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use crate::{
+    anchor::Anchor,
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazbox::HazBox,
+    published,
+    retire::Retire,
+    Hazard,
+};
+
+/// A growable vector of slots sharing a single domain, each independently swappable like a
+/// [`HazBox`].
+///
+/// Slots are pushed without locking, and never removed, thus an index stays valid for as long as
+/// the vector. The slots themselves never move, only the spine that indexes them is reallocated
+/// when it is full, and the old spine is retired to the domain, so readers that are still looking
+/// up a slot through it are never left dangling.
+///
+/// ```
+/// # use anchorage::{anchor::Anchor, hazvec::HazVec};
+/// let shards = HazVec::new();
+/// let shard = shards.push(vec![String::from("entry")]);
+///
+/// let mut anchor = Anchor::new();
+/// let entries = shards.get(shard, &mut anchor).unwrap().len();
+///
+/// shards.swap(shard, Vec::new());
+/// # assert_eq!(entries, 1);
+/// ```
+///
+pub struct HazVec<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    spine: AtomicPtr<Spine<T>>,
+    domain: D,
+    __mk: PhantomData<(&'dom D, T)>,
+}
+
+/// Indexes the slots of a [`HazVec`], which are only ever filled in order, up to its capacity.
+struct Spine<T> {
+    len: AtomicUsize,
+    slots: Vec<AtomicPtr<AtomicPtr<T>>>,
+}
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Dropping the spine only deallocates its own storage, never the slots it indexes.
+unsafe impl<'dom, T> Hazard<'dom> for Spine<T> where T: Hazard<'dom> {}
+
+impl<T> HazVec<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(GlobalDomain)
+    }
+}
+
+impl<T> Default for HazVec<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'dom, T, D> HazVec<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    #[inline]
+    pub fn new_in(domain: D) -> Self {
+        Self::with_capacity_in(0, domain)
+    }
+
+    /// Creates an empty vector with room for `capacity` slots before its spine is reallocated.
+    pub fn with_capacity_in(capacity: usize, domain: D) -> Self {
+        let spine = Self::alloc_spine(
+            Spine {
+                len: AtomicUsize::new(0),
+                slots: (0..capacity)
+                    .map(|_| AtomicPtr::new(ptr::null_mut()))
+                    .collect(),
+            },
+            domain,
+        );
+
+        Self {
+            spine: AtomicPtr::new(spine),
+            domain,
+            __mk: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    /// Number of slots pushed so far. More may be pushed right after.
+    pub fn len(&self) -> usize {
+        let mut anchor = Anchor::new_in(self.domain);
+        self.moor_spine(&mut anchor).len.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Publishes `value` in a new slot, returning its index.
+    pub fn push(&self, value: T) -> usize {
+        match self.try_push(value) {
+            Ok(index) => index,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazVec::push`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`] if an allocation fails, in which case nothing is pushed.
+    pub fn try_push(&self, value: T) -> Result<usize, AllocError> {
+        let value = HazBox::try_alloc(value, self.domain)?;
+        published::publish(value);
+        let slot = match Box::try_new(AtomicPtr::new(value)) {
+            Ok(slot) => Box::into_raw(slot),
+            Err(err) => {
+                // Safety: The value was never shared.
+                unsafe { Self::dealloc_value(value, self.domain) };
+                return Err(err);
+            }
+        };
+
+        let mut anchor = Anchor::new_in(self.domain);
+        loop {
+            let spine = self.moor_spine(&mut anchor);
+            let len = spine.len.load(Ordering::Acquire);
+            if len == spine.slots.len() {
+                if let Err(err) = self.try_grow(spine) {
+                    // Safety: Neither the slot nor its value were shared.
+                    unsafe {
+                        drop(Box::from_raw(slot));
+                        Self::dealloc_value(value, self.domain);
+                    }
+                    return Err(err);
+                }
+                continue;
+            }
+
+            let pushed = spine.slots[len]
+                .compare_exchange(ptr::null_mut(), slot, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok();
+            // Either way the slot at len is filled, thus whoever filled it may be helped along in
+            // case it hasn't bumped the length yet.
+            let _ = spine
+                .len
+                .compare_exchange(len, len + 1, Ordering::AcqRel, Ordering::Relaxed);
+            if pushed {
+                return Ok(len);
+            }
+        }
+    }
+
+    /// Returns the value of the slot at `index`, protected by `anchor`, or [`None`] if it hasn't
+    /// been pushed yet.
+    pub fn get<'r>(&'r self, index: usize, anchor: &'r mut Anchor<'dom, D>) -> Option<&'r T> {
+        assert!(anchor.domain() == self.domain);
+
+        let slot = self.slot(index, anchor)?;
+        anchor.moor_nullable(slot)
+    }
+
+    /// Publishes `with` in the slot at `index`, returning the old value to be retired.
+    ///
+    /// # Panics
+    ///
+    /// * If the slot at `index` hasn't been pushed yet.
+    ///
+    pub fn swap(&self, index: usize, with: T) -> Retire<'dom, T, D> {
+        match self.try_swap(index, with) {
+            Ok(old) => old,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`HazVec::swap`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`] if the allocation fails, in which case the slot is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// * If the slot at `index` hasn't been pushed yet.
+    ///
+    pub fn try_swap(&self, index: usize, with: T) -> Result<Retire<'dom, T, D>, AllocError> {
+        let slot = self
+            .slot(index, &mut Anchor::new_in(self.domain))
+            .expect("Index out of bounds");
+
+        let new = HazBox::try_alloc(with, self.domain)?;
+        published::publish(new);
+        let old = slot.swap(new, Ordering::AcqRel);
+        published::unpublish(old);
+
+        Ok(Retire::new_in(old, self.domain))
+    }
+
+    /// Same as [`HazVec::swap`], but retires the old value immediately.
+    #[inline]
+    pub fn set(&self, index: usize, to: T) {
+        let _ = self.swap(index, to);
+    }
+
+    /// Looks up the slot at `index` through the spine protected by `anchor`. The slot itself lives
+    /// as long as the vector, thus it no longer needs the spine once found.
+    fn slot(&self, index: usize, anchor: &mut Anchor<'dom, D>) -> Option<&AtomicPtr<T>> {
+        let spine = self.moor_spine(anchor);
+        if index >= spine.len.load(Ordering::Acquire) {
+            return None;
+        }
+        let slot = spine.slots[index].load(Ordering::Acquire);
+        anchor.reset();
+        // Safety: Slots below the length are filled, and only deallocated with the vector.
+        Some(unsafe { &*slot })
+    }
+
+    #[inline]
+    fn moor_spine<'r>(&'r self, anchor: &'r mut Anchor<'dom, D>) -> &'r Spine<T> {
+        anchor
+            .moor_nullable(&self.spine)
+            .expect("The spine is never null")
+    }
+
+    /// Replaces the full `spine` with one twice as large, unless another thread already did.
+    fn try_grow(&self, spine: &Spine<T>) -> Result<(), AllocError> {
+        let capacity = cmp::max(spine.slots.len() * 2, 4);
+        let slots = (0..capacity)
+            .map(|i| match spine.slots.get(i) {
+                Some(slot) => AtomicPtr::new(slot.load(Ordering::Acquire)),
+                None => AtomicPtr::new(ptr::null_mut()),
+            })
+            .collect();
+        let new = Self::try_alloc_spine(
+            Spine {
+                len: AtomicUsize::new(spine.slots.len()),
+                slots,
+            },
+            self.domain,
+        )?;
+
+        let old = spine as *const Spine<T> as *mut Spine<T>;
+        match self
+            .spine
+            .compare_exchange(old, new, Ordering::AcqRel, Ordering::Relaxed)
+        {
+            Ok(_) => {
+                published::unpublish(old);
+                // Readers may still be looking up slots through the old spine.
+                drop(Retire::new_in(old, self.domain));
+            }
+            Err(_) => {
+                published::unpublish(new);
+                // Safety: The spine was never published, thus no anchor can be protecting it.
+                unsafe { Self::dealloc_spine(new, self.domain) };
+            }
+        }
+        Ok(())
+    }
+
+    fn alloc_spine(spine: Spine<T>, domain: D) -> *mut Spine<T> {
+        match Self::try_alloc_spine(spine, domain) {
+            Ok(spine) => spine,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<Spine<T>>>()),
+        }
+    }
+
+    fn try_alloc_spine(spine: Spine<T>, domain: D) -> Result<*mut Spine<T>, AllocError> {
+        let spine = HazBox::try_alloc(spine, domain)?;
+        published::publish(spine);
+        Ok(spine)
+    }
+
+    /// # Safety
+    ///
+    /// `spine` must have been allocated with [`HazVec::alloc_spine`], and not be protected.
+    ///
+    unsafe fn dealloc_spine(spine: *mut Spine<T>, domain: D) {
+        domain.deallocated(mem::size_of::<Spine<T>>());
+        // Safety: Allocated by the domain's allocator, and owned by the caller.
+        drop(unsafe { Box::from_raw_in(spine, domain.allocator()) });
+    }
+
+    /// # Safety
+    ///
+    /// `value` must have been published in a slot, or about to be, and not be protected.
+    ///
+    unsafe fn dealloc_value(value: *mut T, domain: D) {
+        published::unpublish(value);
+        domain.deallocated(mem::size_of::<T>());
+        // Safety: Allocated by the domain's allocator, and owned by the caller.
+        drop(unsafe { Box::from_raw_in(value, domain.allocator()) });
+    }
+}
+
+impl<'dom, T, D> Drop for HazVec<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    fn drop(&mut self) {
+        let spine = *self.spine.get_mut();
+        // Safety: Owning the vector means no anchor can be protecting its spine nor its values.
+        let slots = unsafe { &*spine }.slots.iter();
+        for slot in slots {
+            let slot = slot.load(Ordering::Relaxed);
+            if slot.is_null() {
+                continue;
+            }
+            // Safety: Slots are only ever allocated as boxes by push.
+            let value = unsafe { Box::from_raw(slot) }.into_inner();
+            // Safety: Same as above.
+            unsafe { Self::dealloc_value(value, self.domain) };
+        }
+
+        published::unpublish(spine);
+        // Safety: Same as above.
+        unsafe { Self::dealloc_spine(spine, self.domain) };
+    }
+}
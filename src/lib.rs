@@ -1,12 +1,4 @@
-#![feature(
-    allocator_api,
-    arbitrary_self_types,
-    const_fn,
-    iter_map_while,
-    maybe_uninit_extra,
-    option_result_unwrap_unchecked,
-    ptr_as_uninit
-)]
+#![feature(allocator_api, dropck_eyepatch, unsize)]
 // Lints
 #![warn(
     future_incompatible,
@@ -15,6 +7,8 @@
     rust_2018_idioms
 )]
 #![deny(unsafe_op_in_unsafe_fn)]
+// Bullets of the Safety and Panics sections wrap without indentation.
+#![allow(clippy::doc_lazy_continuation)]
 
 ///
 /// Marks a type as being able to be protected via Hazard Pointers.
@@ -28,21 +22,184 @@
 /// then the references of that data must outlive the domain. Thus borrowed data must be retired to
 /// a temporary domain that is [dropped] before it and cannot be used with the [GlobalDomain].
 ///
+/// ```compile_fail
+/// # use anchorage::hazbox::HazBox;
+/// let values = vec![1, 2, 3];
+/// // The global domain is never dropped, thus its boxes can't borrow local data.
+/// let boxed = HazBox::new(values.as_slice());
+/// ```
+///
+/// ```compile_fail
+/// # use anchorage::{domain::scoped::ScopedDomain, hazbox::HazBox};
+/// let values = vec![1, 2, 3];
+/// let boxed;
+/// {
+///     let domain = ScopedDomain::new();
+///     // Safety: The values outlive the domain.
+///     boxed = HazBox::new_in(values.as_slice(), unsafe { domain.handle() });
+/// }
+/// // Nor can boxes outlive their domain.
+/// drop(boxed);
+/// ```
+///
+/// By default every `Sync + Send` type is a [`Hazard`]. With the `explicit-hazard` feature
+/// enabled, [`Hazard`] becomes an unsafe trait that must be implemented explicitly for each type,
+/// forcing a review of its destructor. It is then only implemented for primitives and for standard
+/// library containers of [`Hazards`][Hazard].
+///
+/// Dynamically sized types, like `str`, slices and trait objects, are [`Hazards`][Hazard] too.
+/// Retired values are type erased into `dyn Hazard` pointers, which a slice or `str` can't be cast
+/// to, thus a [`HazDyn`][crate::hazdyn::HazDyn] stores them inline after a header recording their
+/// layout, and retires a zero sized marker in their place that deallocates them with it.
+///
 /// [dropped]: Drop::drop
 /// [protected]: Anchor::moor
 /// [retired]: Domain::retire
 ///
+#[cfg(not(feature = "explicit-hazard"))]
 pub trait Hazard<'dom>: Sync + Send + 'dom {}
 
-impl<'dom, T> Hazard<'dom> for T where T: Sync + Send + 'dom {}
+#[cfg(not(feature = "explicit-hazard"))]
+impl<'dom, T> Hazard<'dom> for T where T: Sync + Send + 'dom + ?Sized {}
+
+///
+/// Marks a type as being able to be protected via Hazard Pointers.
+///
+/// See the documentation without the `explicit-hazard` feature for the requirements.
+///
+/// # Safety
+///
+/// * The destructor of the type must not access any reference that may dangle after `'dom`,
+/// since it may run at any point until the domain is dropped.
+///
+#[cfg(feature = "explicit-hazard")]
+pub unsafe trait Hazard<'dom>: Sync + Send + 'dom {}
+
+#[cfg(feature = "explicit-hazard")]
+mod explicit_hazard {
+    use std::{
+        boxed::Box,
+        collections::{
+            BTreeMap,
+            HashMap,
+            VecDeque,
+        },
+        mem::ManuallyDrop,
+        string::String,
+        sync::Arc,
+        vec::Vec,
+    };
+
+    use crate::{
+        domain::Domain,
+        hazbox::HazBox,
+        Hazard,
+    };
+
+    macro_rules! impl_hazard {
+        ($($ty:ty),* $(,)?) => {
+            $(unsafe impl<'dom> Hazard<'dom> for $ty {})*
+        };
+    }
+
+    impl_hazard!(
+        (),
+        bool,
+        char,
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        usize,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        isize,
+        f32,
+        f64,
+        String,
+    );
+
+    unsafe impl<'dom, T> Hazard<'dom> for &'dom T where T: Sync + ?Sized {}
+    unsafe impl<'dom> Hazard<'dom> for str {}
+    unsafe impl<'dom, T> Hazard<'dom> for [T] where T: Hazard<'dom> {}
+    unsafe impl<'dom, T> Hazard<'dom> for Box<T> where T: Hazard<'dom> + ?Sized {}
+    unsafe impl<'dom, T> Hazard<'dom> for Vec<T> where T: Hazard<'dom> {}
+    unsafe impl<'dom, T> Hazard<'dom> for VecDeque<T> where T: Hazard<'dom> {}
+    unsafe impl<'dom, T> Hazard<'dom> for Option<T> where T: Hazard<'dom> {}
+    unsafe impl<'dom, T> Hazard<'dom> for Arc<T> where T: Hazard<'dom> + ?Sized {}
+    unsafe impl<'dom, T> Hazard<'dom> for ManuallyDrop<T> where T: Hazard<'dom> {}
+    unsafe impl<'dom, T, const N: usize> Hazard<'dom> for [T; N] where T: Hazard<'dom> {}
+    unsafe impl<'dom, K, V> Hazard<'dom> for BTreeMap<K, V>
+    where
+        K: Hazard<'dom>,
+        V: Hazard<'dom>,
+    {
+    }
+    unsafe impl<'dom, T, D> Hazard<'dom> for HazBox<'dom, T, D>
+    where
+        T: Hazard<'dom>,
+        D: Domain<'dom> + Send + Sync,
+    {
+    }
+    unsafe impl<'dom, K, V, S> Hazard<'dom> for HashMap<K, V, S>
+    where
+        K: Hazard<'dom>,
+        V: Hazard<'dom>,
+        S: Sync + Send + 'dom,
+    {
+    }
+}
+
+#[macro_use]
+#[doc(hidden)]
+pub mod macros;
 
+pub mod aligned;
 pub mod anchor;
+pub mod backoff;
+pub mod cell;
+pub mod compact;
+pub mod config;
+pub mod cursor;
 pub mod domain;
+#[cfg(feature = "failpoints")]
+pub mod failpoints;
+#[cfg(not(feature = "failpoints"))]
+mod failpoints;
 pub mod hazbox;
+pub mod hazdyn;
+pub mod hazoption;
 pub mod hazptr;
+pub mod hazslice;
+pub mod hazstatic;
+pub mod hazvec;
+pub mod map;
+#[cfg(unix)]
+pub mod mapped;
+pub mod metrics;
 pub mod node_list;
+pub mod once;
+pub mod pool;
+pub mod retire;
+pub mod root;
+pub mod seq;
+
+#[cfg(feature = "owner-tags")]
+pub mod owner;
+#[cfg(feature = "registry")]
+pub mod registry;
+#[cfg(feature = "testkit")]
+pub mod testkit;
+#[cfg(feature = "watchdog")]
+pub mod watchdog;
 
-pub(crate) mod retire;
+pub(crate) mod counter;
+pub(crate) mod guarded;
+pub(crate) mod published;
 
 pub mod asymmetric_fence {
     use std::sync::atomic::{
@@ -69,28 +226,12 @@ mod tests {
     };
 
     use crate::{
-        domain::{
-            global::GlobalDomain,
-            Domain,
-        },
+        domain::Domain,
         hazbox::HazBox,
         hazptr::HazPtr,
         Hazard,
     };
 
-    #[test]
-    pub fn test_uwu() {
-        let s = vec![1usize, 2, 3];
-        let s = vec![s];
-
-        {
-            let b: HazBox<'_, &[Vec<usize>], GlobalDomain>;
-            let owo = s.as_slice();
-
-            b = HazBox::<_, GlobalDomain>::new(owo);
-        }
-    }
-
     #[test]
     pub fn test_owo() {
         #[derive(Eq, PartialEq)]
@@ -121,20 +262,13 @@ mod tests {
 
         {
             let owo = s.as_slice();
-            let b = HazBox::new_in(owo, LocalDomainRef(&d1));
+            let _b = HazBox::new_in(owo, LocalDomainRef(&d1));
         }
 
         {
             let d2 = LocalDomain(2);
             let owo = s.as_slice();
-            let b = HazBox::new_in(owo, LocalDomainRef(&d2));
-        }
-        let b: HazBox<'_, &[Vec<usize, Global>], LocalDomainRef<'_>>;
-
-        {
-            let d3 = LocalDomain(3);
-            let owo = s.as_slice();
-            b = HazBox::new_in(owo, LocalDomainRef(&d3));
+            let _b = HazBox::new_in(owo, LocalDomainRef(&d2));
         }
     }
 }
@@ -0,0 +1,251 @@
+use crate::{
+    domain::Domain,
+    hazbox::HazBox,
+    Hazard,
+};
+
+/// Declares a domain type backed by its own `static` state, like the
+/// [`GlobalDomain`][crate::domain::global::GlobalDomain] but separate from it.
+///
+/// Optionally declares static [`HazBoxes`][crate::hazbox::HazBox] bound to the new domain in the
+/// same item, so the pairing between the boxes and their domain cannot be mismatched. The boxes
+/// are allocated on first access.
+///
+/// ```
+/// # use anchorage::{anchor::Anchor, domain};
+/// domain! {
+///     /// Domain for the shared configuration.
+///     pub struct ConfigDomain {
+///         pub static CONFIG: String = String::from("verbose");
+///         pub static ROUTES: Vec<&'static str> = vec!["/", "/health"];
+///     }
+/// }
+///
+/// let mut anchor = Anchor::new_in(ConfigDomain);
+/// let config = anchor.moor(&CONFIG);
+/// # assert_eq!(config, "verbose");
+/// ```
+///
+#[macro_export]
+macro_rules! domain {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident;
+    ) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Default, PartialEq, Eq)]
+        $vis struct $name;
+
+        const _: () = {
+            static STATE: $crate::domain::global::StaticDomain =
+                $crate::domain::global::StaticDomain::new();
+
+            impl $name {
+                #[inline]
+                pub fn eager_reclaim(&self) -> usize {
+                    STATE.eager_reclaim()
+                }
+            }
+
+            impl ::std::fmt::Debug for $name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    STATE.fmt_stats(stringify!($name), f)
+                }
+            }
+
+            // Names the allocator of the GlobalDomain instead of Global itself, which would need
+            // the allocator_api feature wherever the macro is used.
+            unsafe impl $crate::domain::Domain<'static> for $name {
+                type Alloc = <$crate::domain::global::GlobalDomain as $crate::domain::Domain<
+                    'static,
+                >>::Alloc;
+
+                #[inline]
+                fn allocator(self) -> &'static Self::Alloc {
+                    $crate::domain::Domain::allocator($crate::domain::global::GlobalDomain)
+                }
+
+                #[inline]
+                fn acquire(self) -> Option<&'static $crate::hazptr::HazPtr> {
+                    STATE.acquire()
+                }
+
+                #[inline]
+                fn release(self, hazptr: &'static $crate::hazptr::HazPtr) {
+                    STATE.release(hazptr)
+                }
+
+                #[inline]
+                fn allocated(self, bytes: usize) {
+                    STATE.allocated(bytes)
+                }
+
+                #[inline]
+                fn deallocated(self, bytes: usize) {
+                    STATE.deallocated(bytes)
+                }
+
+                unsafe fn retire(
+                    self,
+                    retired: ::std::ptr::NonNull<dyn $crate::Hazard<'static>>,
+                ) {
+                    STATE.retire(retired)
+                }
+
+                unsafe fn retire_all<I>(self, retired: I)
+                where
+                    I: IntoIterator<Item = ::std::ptr::NonNull<dyn $crate::Hazard<'static>>>,
+                {
+                    STATE.retire_all(retired)
+                }
+
+                #[inline]
+                fn eager_reclaim(self) -> usize {
+                    STATE.eager_reclaim()
+                }
+
+                #[inline]
+                fn drain_reclaimable(
+                    self,
+                ) -> ::std::vec::IntoIter<$crate::domain::Reclaimed<'static, Self::Alloc>> {
+                    STATE.drain_reclaimable()
+                }
+
+                #[inline]
+                fn verify(self) {
+                    STATE.verify()
+                }
+
+                #[inline]
+                fn visit_hazptrs(
+                    self,
+                    f: &mut dyn FnMut(&'static $crate::hazptr::HazPtr),
+                ) -> bool {
+                    STATE.visit_hazptrs(f)
+                }
+
+                #[inline]
+                fn metrics(self) -> $crate::metrics::MetricsSnapshot {
+                    STATE.metrics()
+                }
+            }
+        };
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$box_attr:meta])*
+                $box_vis:vis static $box_name:ident: $ty:ty = $init:expr;
+            )*
+        }
+    ) => {
+        $crate::domain! {
+            $(#[$attr])*
+            $vis struct $name;
+        }
+
+        $(
+            $crate::hazbox! {
+                $(#[$box_attr])*
+                $box_vis static $box_name in $name: $ty = $init;
+            }
+        )*
+    };
+}
+
+/// Declares static [`HazBoxes`][crate::hazbox::HazBox], allocated on first access.
+///
+/// Boxes are bound to the [`GlobalDomain`][crate::domain::global::GlobalDomain] unless another
+/// `'static` unit struct domain is given with `in`. Use [`domain!`][crate::domain!] to declare a new domain
+/// along with its boxes.
+///
+/// ```
+/// # use anchorage::{domain, hazbox};
+/// # domain! { struct LimitsDomain; }
+/// hazbox! {
+///     pub static COUNTERS: Vec<usize> = vec![0; 16];
+///     static LIMITS in LimitsDomain: [usize; 2] = [1, 100];
+/// }
+/// ```
+///
+#[macro_export]
+macro_rules! hazbox {
+    () => {};
+    (
+        $(#[$attr:meta])*
+        $vis:vis static $name:ident in $domain:path: $ty:ty = $init:expr;
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        $vis static $name: ::std::sync::LazyLock<$crate::hazbox::HazBox<'static, $ty, $domain>> =
+            ::std::sync::LazyLock::new(|| $crate::hazbox::HazBox::new_in($init, $domain));
+
+        $crate::hazbox! { $($rest)* }
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis static $name:ident: $ty:ty = $init:expr;
+        $($rest:tt)*
+    ) => {
+        $crate::hazbox! {
+            $(#[$attr])*
+            $vis static $name in $crate::domain::global::GlobalDomain: $ty = $init;
+            $($rest)*
+        }
+    };
+}
+
+/// Protects [`HazBoxes`][crate::hazbox::HazBox], returning references to their current values.
+///
+/// * `protect!(anchor, hazbox)` [moors][crate::anchor::Anchor::moor] `hazbox` with `anchor`.
+/// * `protect!(anchor_set => [a, b, ..])` [moors][crate::anchor::AnchorSet::moor_all] all the
+/// boxes at once with an [`AnchorSet`][crate::anchor::AnchorSet], returning an array of references
+/// that were all validated together.
+/// * `protect!(anchor_set => (a, b, ..))` [moors][crate::anchor::AnchorSet::moor_many] boxes
+/// holding different types the same way, returning a tuple of references.
+///
+/// ```
+/// # use anchorage::{anchor::{Anchor, AnchorSet}, hazbox, protect};
+/// hazbox! {
+///     static CONFIG: String = String::from("verbose");
+///     static ROUTES: Vec<&'static str> = vec!["/", "/health"];
+///     static LEFT: usize = 0;
+///     static RIGHT: usize = 1;
+/// }
+///
+/// let mut anchor = Anchor::new();
+/// let config = protect!(anchor, CONFIG);
+///
+/// let mut anchors = AnchorSet::new();
+/// let [left, right] = protect!(anchors => [LEFT, RIGHT]);
+/// # assert_eq!([left, right], [&0, &1]);
+///
+/// let mut anchors = AnchorSet::new();
+/// let (config, routes) = protect!(anchors => (CONFIG, ROUTES));
+/// # assert_eq!(routes.len(), 2);
+/// ```
+///
+#[macro_export]
+macro_rules! protect {
+    ($anchor:expr, $hazbox:expr $(,)?) => {
+        $anchor.moor(&$hazbox)
+    };
+    ($anchors:expr => [$($hazbox:expr),+ $(,)?]) => {
+        $anchors.moor_all([$(&$hazbox),+])
+    };
+    ($anchors:expr => ($($hazbox:expr),+ $(,)?)) => {
+        $anchors.moor_many(($($crate::macros::as_hazbox(&$hazbox),)+))
+    };
+}
+
+/// Returns `hazbox` as is, so that references to boxes declared with [`hazbox!`][crate::hazbox!]
+/// are coerced to plain box references, which tuples don't do on their own.
+#[inline]
+pub fn as_hazbox<'b, 'dom, T, D>(hazbox: &'b HazBox<'dom, T, D>) -> &'b HazBox<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    hazbox
+}
@@ -0,0 +1,524 @@
+use std::{
+    borrow::Borrow,
+    collections::BTreeMap,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        Arc,
+    },
+};
+
+use crate::{
+    anchor::Anchor,
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazbox::HazBox,
+    retire::Retire,
+    root::HazRoot,
+    Hazard,
+};
+
+type Index<'dom, K, V, D> = BTreeMap<K, Arc<Slot<'dom, V, D>>>;
+
+/// The value of a key, shared by the index and the entries of the key.
+struct Slot<'dom, V, D>
+where
+    D: Domain<'dom>,
+    V: Hazard<'dom>,
+{
+    value: HazBox<'dom, V, D>,
+    /// Set once the key is removed, before the slot is unlinked from the index, after which the
+    /// value is never replaced again, so that entries of the key still holding the slot insert
+    /// their value anew instead of into a slot no longer in the map.
+    removed: AtomicBool,
+}
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Dropping the slot only drops its box.
+unsafe impl<'dom, V, D> Hazard<'dom> for Slot<'dom, V, D>
+where
+    D: Domain<'dom> + Send + Sync,
+    V: Hazard<'dom>,
+{
+}
+
+impl<'dom, V, D> Slot<'dom, V, D>
+where
+    D: Domain<'dom>,
+    V: Hazard<'dom>,
+{
+    #[inline]
+    fn new(value: V, domain: D) -> Arc<Self> {
+        Arc::new(Self {
+            value: HazBox::new_in(value, domain),
+            removed: AtomicBool::new(false),
+        })
+    }
+
+    #[inline]
+    fn is_removed(&self) -> bool {
+        self.removed.load(Ordering::Acquire)
+    }
+
+    /// Marks the key as removed, returning whether it wasn't already.
+    #[inline]
+    fn remove(&self) -> bool {
+        !self.removed.swap(true, Ordering::AcqRel)
+    }
+
+    /// Takes the value back out of a slot that was never published.
+    fn into_value(this: Arc<Self>) -> V {
+        match Arc::try_unwrap(this) {
+            Ok(slot) => slot.value.into_inner(),
+            Err(_) => unreachable!("Unpublished slots are never shared"),
+        }
+    }
+}
+
+/// Ordered map whose values can be read and updated in place without locking.
+///
+/// Every value lives in its own [`HazBox`], so updating a value only replaces that value, not the
+/// whole map. The keys are kept in an index published behind a single [`HazRoot`], which is only
+/// replaced when keys are inserted or removed.
+///
+/// Per key read-modify-write goes through [`HazMap::entry`], whose guards keep the value protected
+/// for as long as they are held, so it can be read and replaced without cloning it out first.
+///
+/// Inserting or removing a key clones the whole index, in `O(n)`, thus the map suits keys that
+/// change far less often than their values.
+///
+/// ```
+/// # use anchorage::map::HazMap;
+/// let counters = HazMap::new();
+///
+/// for _ in 0..2 {
+///     counters
+///         .entry("requests")
+///         .and_modify(|count| count + 1)
+///         .or_insert(1_u64);
+/// }
+/// assert_eq!(*counters.get("requests").unwrap().get(), 2);
+/// ```
+///
+pub struct HazMap<'dom, K, V, D>
+where
+    D: Domain<'dom> + Send + Sync,
+    K: Hazard<'dom> + Ord + Clone,
+    V: Hazard<'dom>,
+{
+    index: HazRoot<'dom, Index<'dom, K, V, D>, D>,
+}
+
+impl<K, V> HazMap<'static, K, V, GlobalDomain>
+where
+    K: Hazard<'static> + Ord + Clone,
+    V: Hazard<'static>,
+{
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(GlobalDomain)
+    }
+}
+
+impl<K, V> Default for HazMap<'static, K, V, GlobalDomain>
+where
+    K: Hazard<'static> + Ord + Clone,
+    V: Hazard<'static>,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'dom, K, V, D> HazMap<'dom, K, V, D>
+where
+    D: Domain<'dom> + Send + Sync,
+    K: Hazard<'dom> + Ord + Clone,
+    V: Hazard<'dom>,
+{
+    #[inline]
+    pub fn new_in(domain: D) -> Self {
+        Self {
+            index: HazRoot::new_in(BTreeMap::new(), domain),
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.index.domain()
+    }
+
+    pub fn len(&self) -> usize {
+        let mut anchor = Anchor::new_in(self.domain());
+        self.index
+            .load(&mut anchor)
+            .values()
+            .filter(|slot| !slot.is_removed())
+            .count()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Protects the value of `key`, if any.
+    pub fn get<Q>(&self, key: &Q) -> Option<OccupiedEntry<'_, 'dom, K, V, D>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut anchor = Anchor::new_in(self.domain());
+        let (key, slot) = self
+            .index
+            .load(&mut anchor)
+            .get_key_value(key)
+            .filter(|(_, slot)| !slot.is_removed())
+            .map(|(key, slot)| (key.clone(), slot.clone()))?;
+        anchor.reset();
+
+        Some(OccupiedEntry::new(self, key, slot, anchor))
+    }
+
+    /// Returns the entry of `key`, protecting its value if it has one.
+    pub fn entry(&self, key: K) -> Entry<'_, 'dom, K, V, D> {
+        match self.slot(&key).filter(|slot| !slot.is_removed()) {
+            Some(slot) => Entry::Occupied(OccupiedEntry::new(
+                self,
+                key,
+                slot,
+                Anchor::new_in(self.domain()),
+            )),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
+        }
+    }
+
+    /// Inserts `value` for `key`, returning the old value to be retired, if any.
+    #[inline]
+    pub fn insert(&self, key: K, value: V) -> Option<Retire<'dom, V, D>> {
+        VacantEntry { map: self, key }.insert_with(value, true).1
+    }
+
+    /// Removes `key`, returning whether it was in the map.
+    ///
+    /// Its value is dropped once no entry protects it anymore.
+    pub fn remove<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.slot(key) {
+            Some(slot) => {
+                let removed = slot.remove();
+                self.unlink(key, &slot);
+                removed
+            }
+            None => false,
+        }
+    }
+
+    fn slot<Q>(&self, key: &Q) -> Option<Arc<Slot<'dom, V, D>>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut anchor = Anchor::new_in(self.domain());
+        self.index.load(&mut anchor).get(key).cloned()
+    }
+
+    /// Publishes `slot` for `key`, unless the key has a value that wasn't removed, returning
+    /// whether it was published.
+    fn link(&self, key: &K, slot: &Arc<Slot<'dom, V, D>>) -> bool {
+        let mut linked = false;
+        self.update_index(|index| {
+            linked = !index.get(key).is_some_and(|other| !other.is_removed());
+            linked.then(|| {
+                let mut index = index.clone();
+                index.insert(key.clone(), slot.clone());
+                index
+            })
+        });
+        linked
+    }
+
+    /// Removes `key` from the index, if it is still the key of `slot`, which must be removed.
+    fn unlink<Q>(&self, key: &Q, slot: &Arc<Slot<'dom, V, D>>)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.update_index(|index| {
+            index
+                .get(key)
+                .is_some_and(|other| Arc::ptr_eq(slot, other))
+                .then(|| {
+                    let mut index = index.clone();
+                    index.remove(key);
+                    index
+                })
+        });
+    }
+
+    /// Publishes the index built by `update` from the current one, unless it returns [None].
+    fn update_index<F>(&self, mut update: F)
+    where
+        F: FnMut(&Index<'dom, K, V, D>) -> Option<Index<'dom, K, V, D>>,
+    {
+        let root = self.index.as_hazbox();
+        let mut anchor = Anchor::new_in(self.domain());
+        loop {
+            let current = anchor.moor(root);
+            let expected = current as *const _ as *mut _;
+            let Some(index) = update(current) else {
+                return;
+            };
+            if root.compare_exchange(expected, index).is_ok() {
+                return;
+            }
+        }
+    }
+}
+
+/// The entry of a key in a [`HazMap`], see [`HazMap::entry`].
+pub enum Entry<'m, 'dom, K, V, D>
+where
+    D: Domain<'dom> + Send + Sync,
+    K: Hazard<'dom> + Ord + Clone,
+    V: Hazard<'dom>,
+{
+    Occupied(OccupiedEntry<'m, 'dom, K, V, D>),
+    Vacant(VacantEntry<'m, 'dom, K, V, D>),
+}
+
+impl<'m, 'dom, K, V, D> Entry<'m, 'dom, K, V, D>
+where
+    D: Domain<'dom> + Send + Sync,
+    K: Hazard<'dom> + Ord + Clone,
+    V: Hazard<'dom>,
+{
+    #[inline]
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Replaces the value with the result of `update`, if there is one.
+    #[inline]
+    pub fn and_modify<F>(mut self, update: F) -> Self
+    where
+        F: FnMut(&V) -> V,
+    {
+        if let Entry::Occupied(entry) = &mut self {
+            drop(entry.update(update));
+        }
+        self
+    }
+
+    #[inline]
+    pub fn or_insert(self, value: V) -> OccupiedEntry<'m, 'dom, K, V, D> {
+        self.or_insert_with(|| value)
+    }
+
+    pub fn or_insert_with<F>(self, value: F) -> OccupiedEntry<'m, 'dom, K, V, D>
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry,
+            Entry::Vacant(entry) => entry.insert_if_vacant(value()),
+        }
+    }
+}
+
+/// An entry with a value, which stays protected for as long as the entry is held.
+pub struct OccupiedEntry<'m, 'dom, K, V, D>
+where
+    D: Domain<'dom> + Send + Sync,
+    K: Hazard<'dom> + Ord + Clone,
+    V: Hazard<'dom>,
+{
+    map: &'m HazMap<'dom, K, V, D>,
+    key: K,
+    /// Keeps the box alive even if the key is removed from the map meanwhile.
+    slot: Arc<Slot<'dom, V, D>>,
+    anchor: Anchor<'dom, D>,
+    value: *const V,
+}
+
+impl<'m, 'dom, K, V, D> OccupiedEntry<'m, 'dom, K, V, D>
+where
+    D: Domain<'dom> + Send + Sync,
+    K: Hazard<'dom> + Ord + Clone,
+    V: Hazard<'dom>,
+{
+    fn new(
+        map: &'m HazMap<'dom, K, V, D>,
+        key: K,
+        slot: Arc<Slot<'dom, V, D>>,
+        mut anchor: Anchor<'dom, D>,
+    ) -> Self {
+        let value = anchor.moor(&slot.value) as *const V;
+        Self {
+            map,
+            key,
+            slot,
+            anchor,
+            value,
+        }
+    }
+
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// The value protected by the entry. It may have been replaced since it was protected.
+    #[inline]
+    pub fn get(&self) -> &V {
+        // Safety: The value was moored by our anchor, which keeps protecting it until it's reset.
+        unsafe { &*self.value }
+    }
+
+    /// Replaces the value with the result of `update`, returning the old one to be retired, or
+    /// [None] if the key was removed meanwhile, in which case nothing is updated.
+    ///
+    /// If the value is replaced while `update` runs, its result is discarded and `update` is
+    /// called again with the newer value. The entry protects the new value afterwards.
+    ///
+    pub fn update<F>(&mut self, mut update: F) -> Option<Retire<'dom, V, D>>
+    where
+        F: FnMut(&V) -> V,
+    {
+        loop {
+            // Same as in try_insert.
+            if self.slot.is_removed() {
+                return None;
+            }
+            let expected = self.value as *mut V;
+            let result = self
+                .slot
+                .value
+                .compare_exchange(expected, update(self.get()));
+            self.remoor();
+            if let Ok(old) = result {
+                return Some(old);
+            }
+        }
+    }
+
+    /// Replaces the value with `value`, returning the old one to be retired.
+    ///
+    /// If the key was removed meanwhile, `value` is inserted anew instead, like
+    /// [`HazMap::insert`], and the entry moves to it.
+    ///
+    pub fn insert(&mut self, value: V) -> Option<Retire<'dom, V, D>> {
+        match self.try_insert(value) {
+            Ok(old) => Some(old),
+            Err(value) => {
+                let vacant = VacantEntry {
+                    map: self.map,
+                    key: self.key.clone(),
+                };
+                let (entry, old) = vacant.insert_with(value, true);
+                *self = entry;
+                old
+            }
+        }
+    }
+
+    /// Removes the key from the map, unless it was already replaced by a newer insertion.
+    pub fn remove(self) {
+        self.slot.remove();
+        self.map.unlink(&self.key, &self.slot);
+    }
+
+    /// Replaces the value with `value`, unless the key was removed, giving `value` back.
+    fn try_insert(&mut self, value: V) -> Result<Retire<'dom, V, D>, V> {
+        // A removal marking the slot right after this check is ordered after the swap, which then
+        // only replaces a value about to be removed along with it.
+        if self.slot.is_removed() {
+            return Err(value);
+        }
+        let old = self.slot.value.swap(value);
+        self.remoor();
+        Ok(old)
+    }
+
+    fn remoor(&mut self) {
+        self.value = self.anchor.moor(&self.slot.value) as *const V;
+    }
+}
+
+/// An entry without a value.
+pub struct VacantEntry<'m, 'dom, K, V, D>
+where
+    D: Domain<'dom> + Send + Sync,
+    K: Hazard<'dom> + Ord + Clone,
+    V: Hazard<'dom>,
+{
+    map: &'m HazMap<'dom, K, V, D>,
+    key: K,
+}
+
+impl<'m, 'dom, K, V, D> VacantEntry<'m, 'dom, K, V, D>
+where
+    D: Domain<'dom> + Send + Sync,
+    K: Hazard<'dom> + Ord + Clone,
+    V: Hazard<'dom>,
+{
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Inserts `value`, replacing any value inserted for the key meanwhile.
+    #[inline]
+    pub fn insert(self, value: V) -> OccupiedEntry<'m, 'dom, K, V, D> {
+        self.insert_with(value, true).0
+    }
+
+    /// Inserts `value`, unless a value was inserted for the key meanwhile, in which case `value`
+    /// is dropped and the entry of that value is returned instead.
+    #[inline]
+    pub fn insert_if_vacant(self, value: V) -> OccupiedEntry<'m, 'dom, K, V, D> {
+        self.insert_with(value, false).0
+    }
+
+    /// Inserts `value`, returning its entry along with the value it replaced, if any.
+    fn insert_with(
+        self,
+        mut value: V,
+        replace: bool,
+    ) -> (OccupiedEntry<'m, 'dom, K, V, D>, Option<Retire<'dom, V, D>>) {
+        let domain = self.map.domain();
+        loop {
+            if let Some(slot) = self.map.slot(&self.key).filter(|slot| !slot.is_removed()) {
+                let anchor = Anchor::new_in(domain);
+                let mut entry = OccupiedEntry::new(self.map, self.key.clone(), slot, anchor);
+                if !replace {
+                    return (entry, None);
+                }
+                match entry.try_insert(value) {
+                    Ok(old) => return (entry, Some(old)),
+                    Err(back) => value = back,
+                }
+                continue;
+            }
+
+            let slot = Slot::new(value, domain);
+            if self.map.link(&self.key, &slot) {
+                let entry = OccupiedEntry::new(self.map, self.key, slot, Anchor::new_in(domain));
+                return (entry, None);
+            }
+            // Another value was inserted meanwhile.
+            value = Slot::into_value(slot);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    fmt,
+    io,
+    ops::Deref,
+    os::raw::{
+        c_int,
+        c_void,
+    },
+    ptr::NonNull,
+    slice,
+};
+
+#[cfg(feature = "explicit-hazard")]
+use crate::Hazard;
+
+extern "C" {
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+}
+
+/// A memory mapped region, which is unmapped when dropped.
+///
+/// Meant to be protected behind a [`HazBox`][crate::hazbox::HazBox], e.g. to rotate the mapped
+/// segments of a storage engine while readers are still reading the old ones. Swapping the box
+/// [retires][crate::retire::Retire] the old region, which is then only unmapped once no
+/// [`Anchor`][crate::anchor::Anchor] protects it anymore, so readers never fault on a region
+/// unmapped under them.
+///
+/// ```
+/// # use std::{os::raw::{c_int, c_void}, ptr::NonNull};
+/// # use anchorage::{anchor::Anchor, hazbox::HazBox, mapped::MappedRegion};
+/// # extern "C" {
+/// #     fn mmap(addr: *mut c_void, len: usize, prot: c_int, flags: c_int, fd: c_int, off: i64)
+/// #         -> *mut c_void;
+/// # }
+/// # #[cfg(target_os = "linux")]
+/// # const MAP_ANONYMOUS: c_int = 0x20;
+/// # #[cfg(not(target_os = "linux"))]
+/// # const MAP_ANONYMOUS: c_int = 0x1000;
+/// # fn map_segment(len: usize) -> NonNull<u8> {
+/// #     // Safety: Maps a new private anonymous region, readable and writable.
+/// #     let ptr = unsafe { mmap(std::ptr::null_mut(), len, 1 | 2, 2 | MAP_ANONYMOUS, -1, 0) };
+/// #     assert_ne!(ptr as isize, -1);
+/// #     NonNull::new(ptr.cast()).unwrap()
+/// # }
+/// # const HEADER_LEN: usize = 64;
+/// # let (ptr, len) = (map_segment(4096), 4096);
+/// # let (next, next_len) = (map_segment(8192), 8192);
+/// let segment = HazBox::new(unsafe { MappedRegion::from_raw_parts(ptr, len) });
+///
+/// let mut anchor = Anchor::new();
+/// let header = &anchor.moor(&segment)[..HEADER_LEN];
+/// # assert!(header.iter().all(|&byte| byte == 0));
+///
+/// segment.set(unsafe { MappedRegion::from_raw_parts(next, next_len) });
+/// ```
+///
+pub struct MappedRegion {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// Safety: The region is owned, and only ever read through shared references.
+unsafe impl Send for MappedRegion {}
+
+// Safety: Same as for Send.
+unsafe impl Sync for MappedRegion {}
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Unmapping the region doesn't access anything else.
+unsafe impl<'dom> Hazard<'dom> for MappedRegion {}
+
+impl MappedRegion {
+    /// Takes ownership of the region of `len` bytes mapped at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// * The region must have been mapped with `mmap`, exactly at `ptr` and with `len` bytes, and
+    /// be readable.
+    /// * The region must not be unmapped, remapped or written to by anyone else while owned.
+    ///
+    #[inline]
+    pub unsafe fn from_raw_parts(ptr: NonNull<u8>, len: usize) -> Self {
+        Self { ptr, len }
+    }
+
+    /// Gives up ownership of the region without unmapping it.
+    #[inline]
+    pub fn into_raw_parts(self) -> (NonNull<u8>, usize) {
+        let parts = (self.ptr, self.len);
+        std::mem::forget(self);
+        parts
+    }
+
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Unmaps the region, returning the error reported by `munmap` if it failed, in which case the
+    /// region may still be mapped.
+    pub fn unmap(self) -> io::Result<()> {
+        let (ptr, len) = self.into_raw_parts();
+        // Safety: The region was mapped at ptr with len bytes, and is owned by us.
+        match unsafe { munmap(ptr.as_ptr().cast(), len) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+}
+
+impl Deref for MappedRegion {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Safety: The region is readable, and no one else writes to it while owned.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl AsRef<[u8]> for MappedRegion {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl fmt::Debug for MappedRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedRegion")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl Drop for MappedRegion {
+    fn drop(&mut self) {
+        // Safety: The region was mapped at ptr with len bytes, and is owned by us.
+        let result = unsafe { munmap(self.ptr.as_ptr().cast(), self.len) };
+        // Nothing else can be done about it while reclaiming, the region is leaked.
+        debug_assert_eq!(
+            result,
+            0,
+            "Unable to unmap region: {}",
+            io::Error::last_os_error()
+        );
+    }
+}
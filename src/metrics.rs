@@ -0,0 +1,130 @@
+use std::{
+    mem,
+    ptr::NonNull,
+    sync::atomic::{
+        AtomicIsize,
+        AtomicU64,
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use crate::Hazard;
+
+/// Point in time metrics of a domain, retrieved with [`Domain::metrics`][crate::domain::Domain::metrics].
+///
+/// Meant to be mapped directly onto exporter gauges and counters. Fields named `total_*` only ever
+/// increase, so rates can be computed from the difference between two snapshots.
+///
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MetricsSnapshot {
+    /// Number of [`HazPtrs`][crate::hazptr::HazPtr] owned by the domain.
+    pub hazptrs_total: usize,
+    /// Number of [`HazPtrs`][crate::hazptr::HazPtr] currently acquired.
+    pub hazptrs_active: usize,
+    /// Highest number of [`HazPtrs`][crate::hazptr::HazPtr] acquired at once.
+    pub hazptrs_active_high_water: usize,
+    /// Number of retired [`Hazards`][Hazard] waiting to be reclaimed.
+    pub retired: usize,
+    /// Size in bytes of the retired [`Hazards`][Hazard] waiting to be reclaimed.
+    pub retired_bytes: usize,
+    /// Size in bytes currently allocated through the domain's allocator for [`Hazards`][Hazard],
+    /// both those held by boxes and those retired but not reclaimed yet. Attributes memory to
+    /// the domain it was allocated for, e.g. to find which structure a memory regression comes
+    /// from.
+    pub allocated_bytes: usize,
+    /// Highest number of retired [`Hazards`][Hazard] waiting to be reclaimed at once.
+    pub retired_high_water: usize,
+    /// Number of [`Hazards`][Hazard] retired since the domain was created.
+    pub total_retired: u64,
+    /// Number of [`Hazards`][Hazard] reclaimed since the domain was created.
+    pub total_reclaimed: u64,
+    /// Number of reclamation passes run since the domain was created.
+    pub total_reclaim_passes: u64,
+}
+
+/// Keeps the retirement side counters of a [`MetricsSnapshot`] up to date.
+pub(crate) struct MetricsRecorder {
+    /// Bytes held by boxes, which move to retired_bytes once retired. Signed, since values may be
+    /// retired to a domain other than the one they were allocated for, like a child's parent.
+    live_bytes: AtomicIsize,
+    retired: AtomicUsize,
+    retired_bytes: AtomicUsize,
+    retired_high_water: AtomicUsize,
+    total_retired: AtomicU64,
+    total_reclaimed: AtomicU64,
+    total_reclaim_passes: AtomicU64,
+}
+
+/// Size of the hazard pointed to by `hazard`, which must still be valid.
+#[inline]
+pub(crate) fn hazard_size<'dom>(hazard: NonNull<dyn Hazard<'dom> + 'dom>) -> usize {
+    // Safety: Only called on hazards that haven't been dropped yet.
+    mem::size_of_val(unsafe { hazard.as_ref() })
+}
+
+impl MetricsRecorder {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            live_bytes: AtomicIsize::new(0),
+            retired: AtomicUsize::new(0),
+            retired_bytes: AtomicUsize::new(0),
+            retired_high_water: AtomicUsize::new(0),
+            total_retired: AtomicU64::new(0),
+            total_reclaimed: AtomicU64::new(0),
+            total_reclaim_passes: AtomicU64::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn allocated(&self, bytes: usize) {
+        self.live_bytes.fetch_add(bytes as isize, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn deallocated(&self, bytes: usize) {
+        self.live_bytes.fetch_sub(bytes as isize, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn retired(&self, count: usize, bytes: usize) {
+        self.live_bytes.fetch_sub(bytes as isize, Ordering::Relaxed);
+        let retired = self.retired.fetch_add(count, Ordering::Relaxed) + count;
+        self.retired_bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.retired_high_water
+            .fetch_max(retired, Ordering::Relaxed);
+        self.total_retired
+            .fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn reclaimed(&self, count: usize, bytes: usize) {
+        self.retired.fetch_sub(count, Ordering::Relaxed);
+        self.retired_bytes.fetch_sub(bytes, Ordering::Relaxed);
+        self.total_reclaimed
+            .fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn reclaim_pass(&self) {
+        self.total_reclaim_passes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Fills in the retirement side of `snapshot`.
+    pub fn snapshot(&self, snapshot: MetricsSnapshot) -> MetricsSnapshot {
+        let retired_bytes = self.retired_bytes.load(Ordering::Relaxed);
+        let live_bytes = self.live_bytes.load(Ordering::Relaxed).max(0) as usize;
+        MetricsSnapshot {
+            retired: self.retired.load(Ordering::Relaxed),
+            retired_bytes,
+            allocated_bytes: live_bytes + retired_bytes,
+            retired_high_water: self.retired_high_water.load(Ordering::Relaxed),
+            total_retired: self.total_retired.load(Ordering::Relaxed),
+            total_reclaimed: self.total_reclaimed.load(Ordering::Relaxed),
+            total_reclaim_passes: self.total_reclaim_passes.load(Ordering::Relaxed),
+            ..snapshot
+        }
+    }
+}
@@ -1,17 +1,70 @@
 use std::{
     alloc::{
-        Allocator,
+        AllocError,
         Global,
     },
     iter,
     ptr,
     sync::atomic::{
-        AtomicIsize,
         AtomicPtr,
         Ordering,
     },
 };
 
+use crate::{
+    counter::ShardedCounter,
+    failpoints::{
+        self,
+        Failpoint,
+    },
+};
+
+#[inline]
+fn new_node<T>(value: T, next: *mut Node<T>) -> *mut Node<T> {
+    Box::into_raw(Box::new_in(
+        Node {
+            next: AtomicPtr::new(next),
+            value,
+        },
+        Global,
+    ))
+}
+
+/// Same as [`new_node`], but returns an [`AllocError`] if the allocation fails.
+#[inline]
+fn try_new_node<T>(value: T, next: *mut Node<T>) -> Result<*mut Node<T>, AllocError> {
+    if failpoints::hit(Failpoint::NodeAlloc) {
+        return Err(AllocError);
+    }
+    let node = Box::try_new_in(
+        Node {
+            next: AtomicPtr::new(next),
+            value,
+        },
+        Global,
+    )?;
+    Ok(Box::into_raw(node))
+}
+
+/// Hints the CPU to start loading the node at `ptr` into cache, since it is about to be visited.
+///
+/// Prefetching never faults, so `ptr` may be null or dangling.
+#[inline(always)]
+pub(crate) fn prefetch<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    // Safety: Prefetches are only hints and do not access memory in a way that can fault.
+    unsafe {
+        use std::arch::x86_64::{
+            _mm_prefetch,
+            _MM_HINT_T0,
+        };
+        _mm_prefetch(ptr.cast(), _MM_HINT_T0);
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = ptr;
+}
+
 #[derive(Debug)]
 pub struct Node<T> {
     pub next: AtomicPtr<Node<T>>,
@@ -26,12 +79,13 @@ impl<T> Node<T> {
         iter::successors(Some(self), |&ptr| unsafe {
             ptr.next.load(Ordering::Relaxed).as_ref()
         })
+        .inspect(|node| prefetch(node.next.load(Ordering::Relaxed)))
     }
 }
 
 pub struct List<T> {
     pub head: AtomicPtr<Node<T>>,
-    pub count: AtomicIsize,
+    pub count: ShardedCounter,
 }
 
 impl<T> List<T> {
@@ -39,24 +93,50 @@ impl<T> List<T> {
     pub const fn new() -> Self {
         Self {
             head: AtomicPtr::new(ptr::null_mut()),
-            count: AtomicIsize::new(0),
+            count: ShardedCounter::new(),
         }
     }
 
     #[inline]
     pub fn push_front(&self, value: T) -> &T {
         // Need to allocate a new node
-        let node = Box::into_raw(Box::new_in(
-            Node {
-                next: AtomicPtr::new(ptr::null_mut()),
-                value,
-            },
-            Global,
-        ));
+        let node = new_node(value, ptr::null_mut());
 
         self.push_list_front(node, node, 1)
     }
 
+    /// Same as [`List::push_front`], but returns an [`AllocError`] if the node can't be allocated.
+    #[inline]
+    pub fn try_push_front(&self, value: T) -> Result<&T, AllocError> {
+        let node = try_new_node(value, ptr::null_mut())?;
+
+        Ok(self.push_list_front(node, node, 1))
+    }
+
+    /// Pushes all `values` to the front of the list at once, paying for a single
+    /// [`push_list_front`][List::push_list_front]. Returns how many values were pushed.
+    pub fn push_all_front<I>(&self, values: I) -> isize
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut head: *mut Node<T> = ptr::null_mut();
+        let mut tail: *mut Node<T> = ptr::null_mut();
+        let mut count = 0;
+
+        for value in values {
+            head = new_node(value, head);
+            if tail.is_null() {
+                tail = head;
+            }
+            count += 1;
+        }
+
+        if count > 0 {
+            self.push_list_front(head, tail, count);
+        }
+        count
+    }
+
     #[inline]
     pub(crate) fn push_list_front(
         &self,
@@ -87,7 +167,7 @@ impl<T> List<T> {
         };
 
         // Note: Folly uses SeqCst because it's the default, not clear if necessary.
-        self.count.fetch_add(count, Ordering::SeqCst);
+        self.count.add(count);
         ret
     }
 
@@ -97,3 +177,10 @@ impl<T> List<T> {
         node.into_iter().flat_map(|n| n.iter().map(|n| &n.value))
     }
 }
+
+impl<T> Default for List<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    alloc::{
+        handle_alloc_error,
+        Layout,
+    },
+    mem::{
+        self,
+        MaybeUninit,
+    },
+    ptr,
+    sync::atomic::Ordering,
+};
+
+use crate::{
+    anchor::Anchor,
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazbox::HazBox,
+    hazoption::HazOption,
+    published,
+    retire::Retire,
+    Hazard,
+};
+
+/// A cell written once, like [`OnceCell`][std::cell::OnceCell], but whose value is protected by
+/// [`Anchors`][Anchor] and can later be replaced or taken out to be retired through the domain.
+///
+/// Covers lazily initialized values in lock free structures, without pairing a
+/// [`Once`][std::sync::Once] with a [`HazOption`]. Threads racing to initialize the cell may each
+/// run their initializer, but only the first value is ever published, the others are dropped
+/// without being seen by anyone.
+///
+/// ```
+/// # use anchorage::{anchor::Anchor, domain::global::GlobalDomain, once::HazOnce};
+/// static TABLE: HazOnce<'static, Vec<u32>, GlobalDomain> = HazOnce::new();
+///
+/// let mut anchor = Anchor::new();
+/// let table = TABLE.get_or_init(&mut anchor, || (0..256).collect());
+/// # assert_eq!(table.len(), 256);
+/// ```
+///
+pub struct HazOnce<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    cell: HazOption<'dom, T, D>,
+}
+
+impl<T> HazOnce<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    pub const fn new() -> Self {
+        Self::new_in(GlobalDomain)
+    }
+}
+
+impl<T> Default for HazOnce<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'dom, T, D> HazOnce<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    /// Creates an empty cell, which allocates nothing until it is initialized.
+    #[inline]
+    pub const fn new_in(domain: D) -> Self {
+        Self {
+            cell: HazOption::none_in(domain),
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.cell.domain()
+    }
+
+    /// Returns the value, protected by `anchor`, if the cell has been initialized.
+    #[inline]
+    pub fn get<'r>(&'r self, anchor: &'r mut Anchor<'dom, D>) -> Option<&'r T> {
+        anchor.moor_opt(&self.cell)
+    }
+
+    /// Returns the value, protected by `anchor`, initializing the cell with `init` if it is empty.
+    ///
+    /// If another thread initializes the cell first, the value returned by `init` is dropped and
+    /// the other one is returned instead.
+    ///
+    pub fn get_or_init<'r, F>(&'r self, anchor: &'r mut Anchor<'dom, D>, init: F) -> &'r T
+    where
+        F: FnOnce() -> T,
+    {
+        let mut init = Some(init);
+        let mut new: *mut T = ptr::null_mut();
+        loop {
+            if let Some(value) = anchor.moor_opt(&self.cell) {
+                let value = value as *const T;
+                if !new.is_null() {
+                    // Safety: The value was never published, thus no anchor can be protecting it.
+                    drop(unsafe { self.reclaim_unpublished(new) });
+                }
+                // Safety: The value is protected by the anchor, which is borrowed for 'r.
+                return unsafe { &*value };
+            }
+            if new.is_null() {
+                let init = init.take().expect("Initializer already ran");
+                new = self.alloc(init());
+            }
+
+            // Protected before it is published, thus it can't be taken and reclaimed before it is
+            // returned, even if another thread empties the cell right after.
+            anchor.hazptr().protect(new.cast());
+            if self.publish(new) {
+                // Safety: Same as above, the value was protected before it was ever published.
+                return unsafe { &*new };
+            }
+        }
+    }
+
+    /// Initializes the cell with `value`, returning it back if the cell was already initialized.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let new = self.alloc(value);
+        if self.publish(new) {
+            return Ok(());
+        }
+        // Safety: The value was never published, thus no anchor can be protecting it.
+        Err(*unsafe { self.reclaim_unpublished(new) })
+    }
+
+    /// Whether the cell is currently initialized. It may be initialized or taken right after.
+    #[inline]
+    pub fn is_initialized(&self) -> bool {
+        self.cell.is_some()
+    }
+
+    /// Replaces the value, initializing the cell if it is empty, returning the old value to be
+    /// retired, if there was one.
+    #[inline]
+    pub fn replace(&self, with: T) -> Option<Retire<'dom, T, D>> {
+        self.cell.swap(with)
+    }
+
+    /// Empties the cell, returning the old value to be retired, if there was one, so the cell can
+    /// be initialized again.
+    #[inline]
+    pub fn take(&self) -> Option<Retire<'dom, T, D>> {
+        self.cell.take()
+    }
+
+    #[inline]
+    pub fn as_hazoption(&self) -> &HazOption<'dom, T, D> {
+        &self.cell
+    }
+
+    fn alloc(&self, value: T) -> *mut T {
+        match HazBox::try_alloc(value, self.domain()) {
+            Ok(ptr) => ptr,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Publishes `new` if the cell is empty, returning whether it was.
+    #[inline]
+    fn publish(&self, new: *mut T) -> bool {
+        published::publish(new);
+        let published = self
+            .cell
+            .ptr
+            .compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok();
+        if !published {
+            published::unpublish(new);
+        }
+        published
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated by [`HazOnce::alloc`] and never published.
+    ///
+    unsafe fn reclaim_unpublished(&self, ptr: *mut T) -> Box<T, &'dom D::Alloc> {
+        let domain = self.domain();
+        domain.deallocated(mem::size_of::<T>());
+        // Safety: Allocated by the domain's allocator, and owned by the caller.
+        unsafe { Box::from_raw_in(ptr, domain.allocator()) }
+    }
+}
+
+/// A [`HazOnce`] initialized on first access by a stored initializer, like
+/// [`LazyLock`][std::sync::LazyLock], but whose value is protected by [`Anchors`][Anchor] thereafter.
+///
+/// Readers never block, threads racing to initialize the cell each run the initializer, and the
+/// first one to publish its value wins. The initializer is run again if the value is
+/// [taken][HazLazy::take] out.
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use anchorage::{anchor::Anchor, domain::global::GlobalDomain, once::HazLazy};
+/// fn load_routes() -> BTreeMap<&'static str, u16> {
+///     BTreeMap::from([("/", 80), ("/admin", 8080)])
+/// }
+///
+/// static ROUTES: HazLazy<'static, BTreeMap<&'static str, u16>, GlobalDomain> =
+///     HazLazy::new(load_routes);
+///
+/// let mut anchor = Anchor::new();
+/// let port = ROUTES.moor(&mut anchor).get("/admin");
+/// # assert_eq!(port, Some(&8080));
+/// ```
+///
+pub struct HazLazy<'dom, T, D, F = fn() -> T>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    once: HazOnce<'dom, T, D>,
+    init: F,
+}
+
+impl<T, F> HazLazy<'static, T, GlobalDomain, F>
+where
+    T: Hazard<'static>,
+    F: Fn() -> T,
+{
+    #[inline]
+    pub const fn new(init: F) -> Self {
+        Self::new_in(init, GlobalDomain)
+    }
+}
+
+impl<'dom, T, D, F> HazLazy<'dom, T, D, F>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+    F: Fn() -> T,
+{
+    #[inline]
+    pub const fn new_in(init: F, domain: D) -> Self {
+        Self {
+            once: HazOnce::new_in(domain),
+            init,
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.once.domain()
+    }
+
+    /// Returns the value, protected by `anchor`, initializing it first if it hasn't been yet.
+    #[inline]
+    pub fn moor<'r>(&'r self, anchor: &'r mut Anchor<'dom, D>) -> &'r T {
+        self.once.get_or_init(anchor, &self.init)
+    }
+
+    /// Returns the value, protected by `anchor`, without initializing it.
+    #[inline]
+    pub fn get<'r>(&'r self, anchor: &'r mut Anchor<'dom, D>) -> Option<&'r T> {
+        self.once.get(anchor)
+    }
+
+    /// Replaces the value, returning the old value to be retired, if it had been initialized.
+    #[inline]
+    pub fn replace(&self, with: T) -> Option<Retire<'dom, T, D>> {
+        self.once.replace(with)
+    }
+
+    /// Empties the cell, returning the old value to be retired, if it had been initialized, so the
+    /// next access initializes it again.
+    #[inline]
+    pub fn take(&self) -> Option<Retire<'dom, T, D>> {
+        self.once.take()
+    }
+
+    #[inline]
+    pub fn as_once(&self) -> &HazOnce<'dom, T, D> {
+        &self.once
+    }
+}
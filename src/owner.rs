@@ -0,0 +1,112 @@
+//! Tagging of acquired [`HazPtrs`][HazPtr] with their owner, enabled by the `owner-tags` feature.
+//!
+//! Knowing that something blocks reclamation is rarely enough to find it. With this feature, every
+//! [`HazPtr`] acquired by an [`Anchor`][crate::anchor::Anchor] records which thread acquired it and
+//! when, which is reported along with [stuck guards] and [overdue retirements], or on demand with
+//! [owner_of].
+//!
+//! Threads are identified by a small number assigned on their first acquisition, see
+//! [current_thread].
+//!
+//! [stuck guards]: crate::watchdog::StuckGuard
+//! [overdue retirements]: crate::retire::Overdue
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
+        LazyLock,
+        Mutex,
+        MutexGuard,
+    },
+    thread,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use crate::hazptr::HazPtr;
+
+/// The thread that acquired a [`HazPtr`], and how long ago.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Owner {
+    /// Number of the thread, see [current_thread].
+    pub thread: u64,
+    /// Name of the thread, if it had one and hasn't exited yet.
+    pub thread_name: Option<String>,
+    /// How long ago the [`HazPtr`] was acquired.
+    pub held_for: Duration,
+}
+
+impl fmt::Display for Owner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.thread_name {
+            Some(name) => write!(f, "thread {} ({:?})", self.thread, name)?,
+            None => write!(f, "thread {}", self.thread)?,
+        }
+        write!(f, ", acquired {:?} ago", self.held_for)
+    }
+}
+
+static BASE: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+/// Names of the running threads that have one, by number.
+static THREAD_NAMES: Mutex<BTreeMap<u64, String>> = Mutex::new(BTreeMap::new());
+
+fn thread_names() -> MutexGuard<'static, BTreeMap<u64, String>> {
+    THREAD_NAMES.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Number of a thread, whose name is forgotten once the thread exits.
+struct ThreadNumber(u64);
+
+impl Drop for ThreadNumber {
+    fn drop(&mut self) {
+        thread_names().remove(&self.0);
+    }
+}
+
+thread_local! {
+    static THREAD: ThreadNumber = {
+        let number = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+        if let Some(name) = thread::current().name() {
+            thread_names().insert(number, name.to_owned());
+        }
+        ThreadNumber(number)
+    };
+}
+
+/// Number identifying the current thread in [`Owners`][Owner]. Never 0.
+#[inline]
+pub fn current_thread() -> u64 {
+    // Threads whose thread locals are being destroyed can't be told apart.
+    THREAD.try_with(|thread| thread.0).unwrap_or(u64::MAX)
+}
+
+/// Returns the owner of `hazptr`, if it is acquired by an [`Anchor`][crate::anchor::Anchor].
+pub fn owner_of(hazptr: &HazPtr) -> Option<Owner> {
+    let (thread, acquired_at) = hazptr.owner();
+    if thread == 0 {
+        return None;
+    }
+
+    let thread_name = thread_names().get(&thread).cloned();
+    Some(Owner {
+        thread,
+        thread_name,
+        held_for: Duration::from_nanos(now().saturating_sub(acquired_at)),
+    })
+}
+
+/// Current time, in nanoseconds since an arbitrary point in the past.
+#[inline]
+pub(crate) fn now() -> u64 {
+    BASE.elapsed().as_nanos() as u64
+}
@@ -0,0 +1,279 @@
+use std::{
+    fmt,
+    mem,
+    ops::{
+        Deref,
+        DerefMut,
+    },
+    sync::{
+        Mutex,
+        MutexGuard,
+    },
+};
+
+use crate::{
+    anchor::Anchor,
+    counter::{
+        self,
+        SHARDS,
+    },
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+};
+
+/// Number of idle anchors a pool keeps by default.
+const DEFAULT_CAPACITY: usize = 64;
+
+/// A pool of [`Anchors`][Anchor] of a single domain, which keeps the anchors it handed out once
+/// they are dropped, and hands them back out without going through the domain again.
+///
+/// Meant for request handlers and other short lived tasks that need an anchor each, where
+/// acquiring it from the domain means scanning its [`HazPtrs`][crate::hazptr::HazPtr] for an
+/// inactive one every time.
+///
+/// Idle anchors are kept in per-thread shards, like the
+/// [`ShardedCounter`][crate::counter::ShardedCounter] of the domains, so that threads getting and
+/// returning anchors concurrently don't contend on a single lock. A thread whose shard is empty
+/// takes anchors from the others before acquiring new ones.
+///
+/// ```
+/// # use std::sync::LazyLock;
+/// # use anchorage::{domain::global::GlobalDomain, hazbox::GlobalHazBox, pool::AnchorPool};
+/// # struct Request;
+/// # type Response = usize;
+/// static ROUTES: LazyLock<GlobalHazBox<Vec<String>>> = LazyLock::new(|| GlobalHazBox::new(Vec::new()));
+/// static ANCHORS: LazyLock<AnchorPool<'static, GlobalDomain>> = LazyLock::new(AnchorPool::new);
+///
+/// fn handle(request: Request) -> Response {
+///     let mut anchor = ANCHORS.get();
+///     let routes = anchor.moor(&ROUTES);
+///     // ...
+/// #   routes.len()
+/// }
+/// # assert_eq!(handle(Request), 0);
+/// ```
+///
+pub struct AnchorPool<'dom, D>
+where
+    D: Domain<'dom>,
+{
+    shards: [Shard<'dom, D>; SHARDS],
+    capacity: usize,
+    domain: D,
+}
+
+/// Padded so that each shard sits on its own cache line.
+#[repr(align(128))]
+struct Shard<'dom, D>(Mutex<Vec<Anchor<'dom, D>>>)
+where
+    D: Domain<'dom>;
+
+impl<'dom, D> Shard<'dom, D>
+where
+    D: Domain<'dom>,
+{
+    fn anchors(&self) -> MutexGuard<'_, Vec<Anchor<'dom, D>>> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl AnchorPool<'static, GlobalDomain> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(GlobalDomain)
+    }
+}
+
+impl Default for AnchorPool<'static, GlobalDomain> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'dom, D> AnchorPool<'dom, D>
+where
+    D: Domain<'dom>,
+{
+    #[inline]
+    pub fn new_in(domain: D) -> Self {
+        Self::with_capacity_in(DEFAULT_CAPACITY, domain)
+    }
+
+    /// Creates an empty pool keeping up to `capacity` idle anchors, any further one being released
+    /// to the domain once dropped.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, domain: D) -> Self {
+        Self {
+            shards: [const { Shard(Mutex::new(Vec::new())) }; SHARDS],
+            capacity,
+            domain,
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of idle anchors kept by the pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.anchors().len()).sum()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an idle anchor, or acquires a new one from the domain if there is none, returning
+    /// [None] if that fails.
+    pub fn try_get(&self) -> Option<PooledAnchor<'_, 'dom, D>> {
+        let idle = self
+            .local_first()
+            .find_map(|(_, shard)| shard.anchors().pop());
+        let anchor = match idle {
+            Some(anchor) => {
+                #[cfg(feature = "owner-tags")]
+                anchor.hazptr().tag_owner();
+                anchor
+            }
+            None => Anchor::try_new_in(self.domain)?,
+        };
+
+        Some(PooledAnchor {
+            anchor: Some(anchor),
+            pool: self,
+        })
+    }
+
+    #[inline]
+    pub fn get(&self) -> PooledAnchor<'_, 'dom, D> {
+        self.try_get().expect("Unable to acquire a HazBox Pointer")
+    }
+
+    /// Acquires anchors from the domain until the pool keeps `count` idle ones, or its capacity.
+    pub fn fill(&self, count: usize) {
+        let missing = count.min(self.capacity).saturating_sub(self.len());
+        for _ in 0..missing {
+            match Anchor::try_new_in(self.domain) {
+                Some(anchor) => self.put(anchor),
+                None => break,
+            }
+        }
+    }
+
+    /// Releases every idle anchor to the domain.
+    #[inline]
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            let idle = mem::take(&mut *shard.anchors());
+            drop(idle);
+        }
+    }
+
+    /// Keeps `anchor` in the first shard with room, starting from the current thread's, or
+    /// releases it to the domain if every shard is full.
+    fn put(&self, anchor: Anchor<'dom, D>) {
+        for (index, shard) in self.local_first() {
+            // The capacity is split between the shards, so that they never hold more in total.
+            let limit = self.capacity / SHARDS + usize::from(index < self.capacity % SHARDS);
+            let mut anchors = shard.anchors();
+            if anchors.len() < limit {
+                anchors.push(anchor);
+                return;
+            }
+        }
+    }
+
+    /// Shards in the order the current thread should use them, its own first.
+    fn local_first(&self) -> impl Iterator<Item = (usize, &Shard<'dom, D>)> {
+        let local = counter::local_shard();
+        (local..SHARDS)
+            .chain(0..local)
+            .map(move |index| (index, &self.shards[index]))
+    }
+}
+
+impl<'dom, D> fmt::Debug for AnchorPool<'dom, D>
+where
+    D: Domain<'dom>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnchorPool")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+/// An [`Anchor`] handed out by an [`AnchorPool`], which goes back to it once dropped.
+pub struct PooledAnchor<'p, 'dom, D>
+where
+    D: Domain<'dom>,
+{
+    anchor: Option<Anchor<'dom, D>>,
+    pool: &'p AnchorPool<'dom, D>,
+}
+
+impl<'p, 'dom, D> PooledAnchor<'p, 'dom, D>
+where
+    D: Domain<'dom>,
+{
+    /// Takes the anchor out of the pool for good, thus it is released to the domain once dropped.
+    #[inline]
+    pub fn into_anchor(mut self) -> Anchor<'dom, D> {
+        match self.anchor.take() {
+            Some(anchor) => anchor,
+            None => unreachable!("Only taken once"),
+        }
+    }
+}
+
+impl<'p, 'dom, D> Deref for PooledAnchor<'p, 'dom, D>
+where
+    D: Domain<'dom>,
+{
+    type Target = Anchor<'dom, D>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match &self.anchor {
+            Some(anchor) => anchor,
+            None => unreachable!("Only taken once"),
+        }
+    }
+}
+
+impl<'p, 'dom, D> DerefMut for PooledAnchor<'p, 'dom, D>
+where
+    D: Domain<'dom>,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.anchor {
+            Some(anchor) => anchor,
+            None => unreachable!("Only taken once"),
+        }
+    }
+}
+
+impl<'p, 'dom, D> Drop for PooledAnchor<'p, 'dom, D>
+where
+    D: Domain<'dom>,
+{
+    fn drop(&mut self) {
+        if let Some(anchor) = self.anchor.take() {
+            anchor.reset();
+            self.pool.put(anchor);
+        }
+    }
+}
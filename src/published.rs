@@ -0,0 +1,89 @@
+//! Debug-only registry of the pointers currently published by [`HazBoxes`][HazBox], used to
+//! catch the retirement of values that were never unlinked from their box.
+//!
+//! Pointers are tracked by address across all domains, since two live allocations can never
+//! share one, in shards selected by address so that threads publishing different values rarely
+//! contend. Zero sized values are skipped, since they all share the same dangling address.
+//! Without `debug_assertions` every function here compiles to nothing.
+//!
+//! [HazBox]: crate::hazbox::HazBox
+
+#[cfg(debug_assertions)]
+mod imp {
+    use std::{
+        collections::HashMap,
+        mem,
+        ptr::NonNull,
+        sync::{
+            LazyLock,
+            Mutex,
+            MutexGuard,
+        },
+    };
+
+    use crate::{
+        metrics::hazard_size,
+        Hazard,
+    };
+
+    const SHARDS: usize = 64;
+
+    /// Published addresses, counted since the same pointer may be stored in several boxes.
+    static PUBLISHED: LazyLock<[Mutex<HashMap<usize, usize>>; SHARDS]> =
+        LazyLock::new(|| [(); SHARDS].map(|_| Mutex::default()));
+
+    /// Locks the shard tracking `addr`. Allocations are usually 16 byte aligned, thus neighbouring
+    /// ones land in different shards.
+    fn published(addr: usize) -> MutexGuard<'static, HashMap<usize, usize>> {
+        PUBLISHED[(addr >> 4) % SHARDS]
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub(crate) fn publish<T>(ptr: *const T) {
+        if mem::size_of::<T>() == 0 {
+            return;
+        }
+        *published(ptr as usize).entry(ptr as usize).or_insert(0) += 1;
+    }
+
+    pub(crate) fn unpublish<T>(ptr: *const T) {
+        if mem::size_of::<T>() == 0 {
+            return;
+        }
+        let mut published = published(ptr as usize);
+        if let Some(count) = published.get_mut(&(ptr as usize)) {
+            *count -= 1;
+            if *count == 0 {
+                published.remove(&(ptr as usize));
+            }
+        }
+    }
+
+    pub(crate) fn check_retired<'dom>(hazard: NonNull<dyn Hazard<'dom> + 'dom>) {
+        if hazard_size(hazard) == 0 {
+            return;
+        }
+        let ptr = hazard.as_ptr() as *const u8;
+        assert!(
+            !published(ptr as usize).contains_key(&(ptr as usize)),
+            "retired {:p} while it is still published by a HazBox, it must be swapped out first",
+            ptr
+        );
+    }
+}
+
+#[cfg(debug_assertions)]
+pub(crate) use imp::*;
+
+#[cfg(not(debug_assertions))]
+#[inline(always)]
+pub(crate) fn publish<T>(_ptr: *const T) {}
+
+#[cfg(not(debug_assertions))]
+#[inline(always)]
+pub(crate) fn unpublish<T>(_ptr: *const T) {}
+
+#[cfg(not(debug_assertions))]
+#[inline(always)]
+pub(crate) fn check_retired<'dom>(_hazard: std::ptr::NonNull<dyn crate::Hazard<'dom> + 'dom>) {}
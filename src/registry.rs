@@ -0,0 +1,162 @@
+//! Registry of live domains for application wide monitoring, enabled by the `registry` feature.
+//!
+//! A dashboard reporting on all the reclamation activity of an application would otherwise need
+//! a handle to every domain plumbed into it. Instead, domains are registered here and
+//! [domains] returns the [metrics][Domain::metrics] of all of them at once.
+//!
+//! The [`GlobalDomain`] is always registered. Other `'static` domains are registered with
+//! [register], and [`ScopedDomains`][crate::domain::scoped::ScopedDomain] once pinned with
+//! [`ScopedDomain::register`][crate::domain::scoped::ScopedDomain::register].
+
+use std::sync::{
+    atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    Mutex,
+    MutexGuard,
+};
+
+use crate::{
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    metrics::MetricsSnapshot,
+};
+
+/// A registered domain along with its metrics, as returned by [domains].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DomainStats {
+    /// Number identifying the registration, 0 for the [`GlobalDomain`].
+    pub id: u64,
+    /// Name the domain was registered with.
+    pub name: &'static str,
+    pub metrics: MetricsSnapshot,
+}
+
+struct Entry {
+    id: u64,
+    name: &'static str,
+    domain: *const (),
+    metrics: unsafe fn(*const ()) -> MetricsSnapshot,
+    drop: Option<unsafe fn(*const ())>,
+}
+
+// Safety: The domain is only used through `metrics`, which register_raw requires to be callable from
+// any thread, and through `drop`, only given for the domains boxed by register, which are Send.
+unsafe impl Send for Entry {}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+static ENTRIES: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+fn entries() -> MutexGuard<'static, Vec<Entry>> {
+    // Metrics are plain counters, a panic while holding the lock can't leave them inconsistent.
+    ENTRIES.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Keeps a domain registered, until it is dropped.
+#[must_use = "the domain is unregistered as soon as the registration is dropped"]
+#[derive(Debug)]
+pub struct Registration {
+    id: u64,
+}
+
+impl Registration {
+    #[inline]
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        unregister(self.id);
+    }
+}
+
+/// Registers `domain` under `name`, until the returned [`Registration`] is dropped.
+pub fn register<D>(name: &'static str, domain: D) -> Registration
+where
+    D: Domain<'static> + Send + Sync,
+{
+    unsafe fn metrics<D>(domain: *const ()) -> MetricsSnapshot
+    where
+        D: Domain<'static>,
+    {
+        // Safety: Registered from a Box<D> below.
+        unsafe { (*domain.cast::<D>()).metrics() }
+    }
+
+    unsafe fn drop<D>(domain: *const ()) {
+        // Safety: Registered from a Box<D> below, and only dropped once, when unregistered.
+        std::mem::drop(unsafe { Box::from_raw(domain as *mut D) });
+    }
+
+    let domain = Box::into_raw(Box::new(domain)) as *const ();
+    // Safety: The box is valid until dropped by unregister.
+    let id = unsafe { register_raw(name, domain, metrics::<D>, Some(drop::<D>)) };
+    Registration { id }
+}
+
+/// Registers the domain at `domain`, whose metrics are read with `metrics`, returning the id to
+/// unregister it with. `drop` is called with `domain` once unregistered.
+///
+/// # Safety
+///
+/// * `domain` must be valid to call `metrics` on from any thread, until unregistered.
+///
+pub(crate) unsafe fn register_raw(
+    name: &'static str,
+    domain: *const (),
+    metrics: unsafe fn(*const ()) -> MetricsSnapshot,
+    drop: Option<unsafe fn(*const ())>,
+) -> u64 {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    entries().push(Entry {
+        id,
+        name,
+        domain,
+        metrics,
+        drop,
+    });
+    id
+}
+
+/// Unregisters the domain with the given id. Once this returns, its metrics aren't read anymore.
+pub(crate) fn unregister(id: u64) {
+    let removed = {
+        let mut entries = entries();
+        let index = entries.iter().position(|entry| entry.id == id);
+        index.map(|index| entries.swap_remove(index))
+    };
+    if let Some(Entry {
+        domain,
+        drop: Some(drop),
+        ..
+    }) = removed
+    {
+        // Safety: The entry was removed, thus the domain isn't used by the registry anymore.
+        unsafe { drop(domain) }
+    }
+}
+
+/// Returns every registered domain with its current metrics, the [`GlobalDomain`] first.
+pub fn domains() -> Vec<DomainStats> {
+    let entries = entries();
+
+    let mut domains = Vec::with_capacity(entries.len() + 1);
+    domains.push(DomainStats {
+        id: 0,
+        name: "GlobalDomain",
+        metrics: GlobalDomain.metrics(),
+    });
+    domains.extend(entries.iter().map(|entry| DomainStats {
+        id: entry.id,
+        name: entry.name,
+        // Safety: The domain stays valid while registered, and we hold the lock.
+        metrics: unsafe { (entry.metrics)(entry.domain) },
+    }));
+    domains
+}
@@ -1,12 +1,21 @@
 use std::{
     marker::PhantomData,
-    mem::needs_drop,
+    mem::{
+        self,
+        ManuallyDrop,
+    },
     ops::Deref,
     ptr::NonNull,
+    thread,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 use crate::{
     domain::Domain,
+    hazptr::HazPtr,
     Hazard,
 };
 
@@ -17,6 +26,7 @@ where
 {
     ptr: NonNull<T>,
     domain: D,
+    is_static: bool,
     __mk: PhantomData<&'dom D>,
 }
 
@@ -31,9 +41,206 @@ where
         Self {
             ptr: unsafe { NonNull::new_unchecked(obj) },
             domain,
+            is_static: false,
             __mk: PhantomData,
         }
     }
+
+    /// Same as [`Retire::new_in`], but for a value that was never allocated, and thus is never
+    /// retired nor dropped, see [`HazStatic`][crate::hazstatic::HazStatic].
+    #[inline]
+    pub(crate) fn new_static_in(obj: *mut T, domain: D) -> Self {
+        // Safety: Same as in new_in.
+        Self {
+            ptr: unsafe { NonNull::new_unchecked(obj) },
+            domain,
+            is_static: true,
+            __mk: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    /// Whether the value is a static one a box was created from, which is left alone instead of
+    /// being retired.
+    #[inline]
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+
+    #[inline]
+    pub(crate) fn into_raw(self) -> NonNull<T> {
+        ManuallyDrop::new(self).ptr
+    }
+
+    /// Retires the value to the domain, which deallocates it once it is no longer protected, but
+    /// without running its destructor.
+    ///
+    /// Meant for values whose cleanup was already performed elsewhere, like when their contents
+    /// were moved out, where dropping them again would clean up twice.
+    ///
+    /// # Safety
+    ///
+    /// * Readers may keep protecting the value until it is reclaimed, thus whatever cleanup was
+    /// performed must leave it valid to be read through shared references until then.
+    ///
+    #[inline]
+    pub unsafe fn retire_no_drop(self) {
+        if self.is_static {
+            return;
+        }
+        let domain = self.domain;
+        // ManuallyDrop is transparent, so the value is deallocated with the same layout, only
+        // its destructor is never called.
+        let ptr = self.into_raw().cast::<ManuallyDrop<T>>();
+        // Safety: The value was swapped out of its box, and the caller guarantees it stays valid
+        // until reclaimed.
+        unsafe { domain.retire(ptr) }
+    }
+
+    /// Drops the value as soon as no [`HazPtr`][crate::hazptr::HazPtr] protects it, waiting on the
+    /// current thread until `deadline` at most, for resources like file handles that must not live
+    /// arbitrarily long.
+    ///
+    /// If the value is still protected past the deadline, `escalation` decides what happens. Unless
+    /// it blocks, the value is then retired to the domain as usual.
+    ///
+    /// Domains that can't [enumerate] their [`HazPtrs`][crate::hazptr::HazPtr] never allow
+    /// checking whether the value is protected, thus the value is retired to them right away.
+    ///
+    /// [enumerate]: Domain::visit_hazptrs
+    ///
+    pub fn retire_by(self, deadline: Instant, escalation: Escalation) {
+        if self.is_static {
+            return;
+        }
+        let addr = self.ptr.as_ptr() as usize;
+        let mut backoff = Duration::from_micros(1);
+        let mut escalated = false;
+
+        while let Some(blocking) = self.protectors() {
+            if blocking.is_empty() {
+                let domain = self.domain;
+                let ptr = self.into_raw();
+                domain.deallocated(mem::size_of::<T>());
+                // Safety: No HazPtr of the domain is protecting the value, and any that starts
+                // protecting it now fails to validate, since it was swapped out of its box.
+                drop(unsafe { Box::from_raw_in(ptr.as_ptr(), domain.allocator()) });
+                return;
+            }
+
+            let now = Instant::now();
+            if now >= deadline && !escalated {
+                let overdue = Overdue {
+                    addr,
+                    hazptrs: blocking.iter().map(|&hp| hp as *const _ as usize).collect(),
+                    overdue_by: now - deadline,
+                    #[cfg(feature = "owner-tags")]
+                    owners: blocking
+                        .iter()
+                        .filter_map(|hp| crate::owner::owner_of(hp))
+                        .collect(),
+                };
+                match escalation {
+                    Escalation::Callback(callback) => {
+                        callback(&overdue);
+                        break;
+                    }
+                    Escalation::Log => {
+                        overdue.log();
+                        break;
+                    }
+                    Escalation::Block => {
+                        overdue.log();
+                        escalated = true;
+                    }
+                }
+            }
+
+            thread::sleep(backoff);
+            backoff = (backoff * 2).min(Duration::from_millis(1));
+        }
+
+        // Retired to the domain as usual.
+        drop(self)
+    }
+
+    /// Blocks the current thread until no [`HazPtr`] of the domain protects the value, returning
+    /// false right away if the domain can't [enumerate] them.
+    ///
+    /// [enumerate]: Domain::visit_hazptrs
+    ///
+    pub(crate) fn wait_unprotected(&self) -> bool {
+        let mut backoff = Duration::from_micros(1);
+        loop {
+            match self.protectors() {
+                None => return false,
+                Some(blocking) if blocking.is_empty() => return true,
+                Some(_) => {
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(Duration::from_millis(1));
+                }
+            }
+        }
+    }
+
+    /// Returns the [`HazPtrs`][HazPtr] of the domain currently protecting the value, or [None] if
+    /// the domain can't enumerate them.
+    fn protectors(&self) -> Option<Vec<&'dom HazPtr>> {
+        crate::asymmetric_fence::heavy();
+
+        let addr = self.ptr.as_ptr() as usize;
+        let mut blocking = Vec::new();
+        let visited = self.domain.visit_hazptrs(&mut |hp| {
+            if hp.ptr() as usize == addr {
+                blocking.push(hp);
+            }
+        });
+        visited.then_some(blocking)
+    }
+}
+
+/// What [`Retire::retire_by`] does with a value still protected past its deadline.
+#[derive(Copy, Clone, Debug)]
+pub enum Escalation {
+    /// Calls the function with the details, then retires the value to the domain.
+    Callback(fn(&Overdue)),
+    /// Prints the details to stderr, then retires the value to the domain.
+    Log,
+    /// Prints the details to stderr, then keeps blocking the retiring thread until the value is no
+    /// longer protected and drops it.
+    Block,
+}
+
+/// A value passed to [`Retire::retire_by`] that was still protected past its deadline.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Overdue {
+    /// Address of the value.
+    pub addr: usize,
+    /// Addresses of the [`HazPtrs`][crate::hazptr::HazPtr] protecting the value.
+    pub hazptrs: Vec<usize>,
+    /// How long past the deadline the value was found to still be protected.
+    pub overdue_by: Duration,
+    /// Who acquired each of the protecting [`HazPtrs`][crate::hazptr::HazPtr], for those that
+    /// anchors acquired.
+    #[cfg(feature = "owner-tags")]
+    pub owners: Vec<crate::owner::Owner>,
+}
+
+impl Overdue {
+    fn log(&self) {
+        eprintln!(
+            "anchorage: value at {:#x} is still protected {:?} past its deadline, by HazPtrs at {:#x?}",
+            self.addr, self.overdue_by, self.hazptrs
+        );
+        #[cfg(feature = "owner-tags")]
+        for owner in &self.owners {
+            eprintln!("anchorage: it is protected by {}", owner);
+        }
+    }
 }
 
 impl<'dom, T, D> Deref for Retire<'dom, T, D>
@@ -59,10 +266,104 @@ where
     T: Hazard<'dom>,
 {
     fn drop(&mut self) {
-        if needs_drop::<T>() {
+        // Even values without a destructor must be retired, for their storage to be freed.
+        if !self.is_static {
             // Safety: T is a Hazard, thus nothing in it can dangle from its destructor,
             // for the lifetime 'dom.
             unsafe { self.domain.retire(self.ptr) }
         }
     }
 }
+
+/// Collects [`Retires`][Retire] from a burst of updates and sends them all to the domain at once
+/// when dropped, amortizing the fences and reclamation checks over the whole burst.
+///
+/// Useful for writers that replace many [`Hazards`][Hazard] back-to-back, like clearing a map.
+///
+pub struct RetireBatch<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    retired: Vec<NonNull<T>>,
+    domain: D,
+    __mk: PhantomData<&'dom D>,
+}
+
+impl<'dom, T, D> RetireBatch<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    #[inline]
+    pub fn new_in(domain: D) -> Self {
+        Self::with_capacity_in(0, domain)
+    }
+
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, domain: D) -> Self {
+        Self {
+            retired: Vec::with_capacity(capacity),
+            domain,
+            __mk: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.retired.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.retired.is_empty()
+    }
+
+    /// Adds `retire` to the batch. If the batch can't grow, `retire` is retired on its own
+    /// right away instead of aborting.
+    #[inline]
+    pub fn push(&mut self, retire: Retire<'dom, T, D>) {
+        assert!(self.domain == retire.domain);
+
+        if retire.is_static {
+            return;
+        }
+        if self.retired.try_reserve(1).is_ok() {
+            self.retired.push(retire.into_raw());
+        }
+    }
+}
+
+impl<'dom, T, D> Extend<Retire<'dom, T, D>> for RetireBatch<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Retire<'dom, T, D>>,
+    {
+        iter.into_iter().for_each(|retire| self.push(retire));
+    }
+}
+
+impl<'dom, T, D> Drop for RetireBatch<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    fn drop(&mut self) {
+        let retired = self
+            .retired
+            .drain(..)
+            .map(|ptr| ptr as NonNull<dyn Hazard<'dom>>);
+
+        // Safety: Same as for a single Retire, for every pointer in the batch.
+        unsafe { self.domain.retire_all(retired) }
+    }
+}
@@ -0,0 +1,142 @@
+use std::alloc::AllocError;
+
+use crate::{
+    anchor::Anchor,
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazbox::HazBox,
+    retire::Retire,
+    Hazard,
+};
+
+/// Publishes updates to several values at once, by keeping them all behind a single root pointer.
+///
+/// Swapping several [`HazBoxes`][HazBox] one after the other lets readers observe the update half
+/// applied, with some boxes already holding the new values and others still holding the old ones.
+/// A [`HazRoot`] instead holds all the values in a single indirection struct, and every update
+/// allocates a new one and publishes it with a single pointer swap, so readers always see either
+/// all of the old values or all of the new ones.
+///
+/// ```
+/// # use anchorage::{anchor::Anchor, root::HazRoot};
+/// #[derive(Clone, Default)]
+/// struct Config {
+///     timeout_ms: u64,
+/// }
+///
+/// #[derive(Default)]
+/// struct Routes(Vec<String>);
+///
+/// impl Routes {
+///     fn load() -> Self {
+///         Routes(vec![String::from("/health")])
+///     }
+/// }
+///
+/// struct State {
+///     config: Config,
+///     routes: Routes,
+/// }
+/// # #[cfg(feature = "explicit-hazard")]
+/// # unsafe impl<'dom> anchorage::Hazard<'dom> for State {}
+///
+/// let root = HazRoot::new(State {
+///     config: Config::default(),
+///     routes: Routes::default(),
+/// });
+///
+/// root.update(|State { config, .. }| State {
+///     config: config.clone(),
+///     routes: Routes::load(),
+/// });
+///
+/// let mut anchor = Anchor::new();
+/// let State { config, routes } = root.load(&mut anchor);
+/// # assert_eq!(config.timeout_ms, 0);
+/// # assert_eq!(routes.0, ["/health"]);
+/// ```
+///
+pub struct HazRoot<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    root: HazBox<'dom, T, D>,
+}
+
+impl<T> HazRoot<'static, T, GlobalDomain>
+where
+    T: Hazard<'static>,
+{
+    #[inline]
+    pub fn new(values: T) -> Self {
+        Self::new_in(values, GlobalDomain)
+    }
+}
+
+impl<'dom, T, D> HazRoot<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom>,
+{
+    #[inline]
+    pub fn new_in(values: T, domain: D) -> Self {
+        Self {
+            root: HazBox::new_in(values, domain),
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.root.domain()
+    }
+
+    /// The [`HazBox`] holding the root, to protect it along with other boxes.
+    #[inline]
+    pub fn as_hazbox(&self) -> &HazBox<'dom, T, D> {
+        &self.root
+    }
+
+    /// Protects the current values with `anchor`, all from the same update.
+    #[inline]
+    pub fn load<'r>(&'r self, anchor: &'r mut Anchor<'dom, D>) -> &'r T {
+        anchor.moor(&self.root)
+    }
+
+    /// Publishes all of `values` at once, returning the old ones to be retired.
+    #[inline]
+    pub fn publish(&self, values: T) -> Retire<'dom, T, D> {
+        self.root.swap(values)
+    }
+
+    /// Builds new values from the current ones and publishes them at once, returning the old ones
+    /// to be retired.
+    ///
+    /// If another update is published while `update` runs, its result is discarded and `update`
+    /// is called again with the newer values, so no concurrent update is lost.
+    ///
+    pub fn update<F>(&self, mut update: F) -> Retire<'dom, T, D>
+    where
+        F: FnMut(&T) -> T,
+    {
+        // Only ever None if the closure returns None.
+        self.root
+            .fetch_update(|current| Some(update(current)))
+            .unwrap()
+    }
+
+    /// Same as [`HazRoot::update`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`][std::alloc::handle_alloc_error] if allocating the new values fails,
+    /// in which case the current ones are left published.
+    pub fn try_update<F>(&self, mut update: F) -> Result<Retire<'dom, T, D>, AllocError>
+    where
+        F: FnMut(&T) -> T,
+    {
+        // Same as in update.
+        self.root
+            .try_fetch_update(|current| Some(update(current)))
+            .map(Option::unwrap)
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    alloc::{
+        handle_alloc_error,
+        AllocError,
+        Layout,
+    },
+    cell::UnsafeCell,
+    hint,
+    mem::MaybeUninit,
+    ptr,
+    sync::atomic::{
+        fence,
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use crate::{
+    anchor::Anchor,
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazbox::HazBox,
+    retire::Retire,
+    Hazard,
+};
+
+/// A [`HazBox`] for small, hot and rarely updated values, that can be read without protecting
+/// them.
+///
+/// Besides the [`HazBox`], a copy of the current value is kept inline behind a sequence counter.
+/// [`SeqBox::optimistic_read`] copies it out and checks that the counter didn't change meanwhile,
+/// which needs neither a protecting store nor a fence. Only when a write races with the read does
+/// it fall back to [mooring] the [`HazBox`] with a new [`Anchor`].
+///
+/// Values must be [`Copy`], since a read racing with a write may observe a torn value before
+/// discarding it, which cloning could dereference. Such reads are kept as uninitialized bytes until
+/// the counter shows they aren't torn.
+///
+/// [mooring]: Anchor::moor
+///
+pub struct SeqBox<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom> + Copy,
+{
+    inner: HazBox<'dom, T, D>,
+    /// Odd while a writer is updating `cached`.
+    seq: AtomicUsize,
+    cached: UnsafeCell<T>,
+}
+
+// Safety: `cached` is only written while holding the sequence counter, and reads of it are
+// discarded unless the counter shows no write happened meanwhile.
+unsafe impl<'dom, T, D> Sync for SeqBox<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom> + Copy,
+    HazBox<'dom, T, D>: Sync,
+{
+}
+
+impl<T> SeqBox<'static, T, GlobalDomain>
+where
+    T: Hazard<'static> + Copy,
+{
+    #[inline]
+    pub fn new(obj: T) -> Self {
+        Self::new_in(obj, GlobalDomain)
+    }
+}
+
+impl<'dom, T, D> SeqBox<'dom, T, D>
+where
+    D: Domain<'dom>,
+    T: Hazard<'dom> + Copy,
+{
+    #[inline]
+    pub fn new_in(obj: T, domain: D) -> Self {
+        Self {
+            inner: HazBox::new_in(obj, domain),
+            seq: AtomicUsize::new(0),
+            cached: UnsafeCell::new(obj),
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.inner.domain()
+    }
+
+    /// Protects the current value with `anchor`, like [`Anchor::moor`] does for a [`HazBox`].
+    #[inline]
+    pub fn moor<'r>(&'r self, anchor: &'r mut Anchor<'dom, D>) -> &'r T {
+        anchor.moor(&self.inner)
+    }
+
+    /// Copies the current value out without protecting it, falling back to [`SeqBox::moor`] only
+    /// if a write races with the read.
+    #[inline]
+    pub fn optimistic_read(&self) -> T {
+        let seq = self.seq.load(Ordering::Acquire);
+        if seq & 1 == 0 {
+            // Safety: The read may race with a writer, thus it is copied out as possibly
+            // uninitialized bytes, which are never looked at unless the counter shows no write
+            // happened meanwhile.
+            let value = unsafe { ptr::read_volatile(self.cached.get().cast::<MaybeUninit<T>>()) };
+            fence(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == seq {
+                // Safety: No write happened while copying, thus it is a whole copy of a valid T.
+                return unsafe { value.assume_init() };
+            }
+        }
+
+        *self.moor(&mut Anchor::new_in(self.domain()))
+    }
+
+    /// Publishes `with`, returning the old value to be retired.
+    pub fn swap(&self, with: T) -> Retire<'dom, T, D> {
+        match self.try_swap(with) {
+            Ok(old) => old,
+            Err(_) => handle_alloc_error(Layout::new::<MaybeUninit<T>>()),
+        }
+    }
+
+    /// Same as [`SeqBox::swap`], but returns an [`AllocError`] instead of calling
+    /// [`handle_alloc_error`] if the allocation fails, in which case the value is left unchanged.
+    pub fn try_swap(&self, with: T) -> Result<Retire<'dom, T, D>, AllocError> {
+        let seq = self.lock();
+        let old = self.inner.try_swap(with);
+        if old.is_ok() {
+            // Safety: Holding the sequence counter makes us the only writer, and readers discard
+            // what they read while it's held.
+            unsafe { ptr::write_volatile(self.cached.get(), with) };
+        }
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+        old
+    }
+
+    #[inline]
+    pub fn set(&self, to: T) {
+        let _ = self.swap(to);
+    }
+
+    /// Marks a write as in progress, returning the sequence number it started at.
+    fn lock(&self) -> usize {
+        loop {
+            let seq = self.seq.load(Ordering::Relaxed);
+            if seq & 1 == 0
+                && self
+                    .seq
+                    .compare_exchange_weak(seq, seq + 1, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            {
+                fence(Ordering::Release);
+                return seq;
+            }
+            hint::spin_loop();
+        }
+    }
+}
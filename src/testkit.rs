@@ -0,0 +1,295 @@
+//! Conformance tests for [`Domain`] implementations, enabled by the `testkit` feature.
+//!
+//! Authors of custom domains can run [`assert_domain_conformance`] from their own tests to check
+//! that acquisition, protection and retirement uphold the invariants required by [`Domain`],
+//! both on a single thread and with concurrent readers and writers. Every check panics on failure.
+//!
+//! Domains are expected to reclaim every retired value no longer protected on
+//! [`Domain::eager_reclaim`], which the default implementation doesn't.
+//!
+//! ```
+//! # use anchorage::domain::global::GlobalDomain as MyDomain;
+//! // In a test of the crate defining the domain:
+//! anchorage::testkit::assert_domain_conformance::<MyDomain>();
+//! ```
+//!
+//! Domains borrowing their state can be checked by leaking it for the duration of the test:
+//!
+//! ```
+//! # use anchorage::domain::sim::SimDomain as MyDomainState;
+//! # let seed = 7;
+//! let domain = Box::leak(Box::new(MyDomainState::new(seed)));
+//! // Safety: The domain is never dropped.
+//! anchorage::testkit::assert_domain_conformance_in(unsafe { domain.handle() });
+//! ```
+//!
+//! [`assert_quiescent`] and [`assert_no_guards`] check that a domain was left clean, for the
+//! teardown of tests and benchmarks.
+//!
+//! The [`ops`] module provides a model for driving random operation sequences from fuzzers, and
+//! the [`soak`] module a harness for long-running stress tests.
+
+use std::{
+    collections::BTreeSet,
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+    thread,
+};
+
+use crate::{
+    anchor::Anchor,
+    domain::Domain,
+    hazbox::HazBox,
+};
+
+pub mod ops;
+pub mod soak;
+
+const THREADS: usize = 4;
+const OPS_PER_THREAD: usize = 2_000;
+const FILLERS: usize = 4_096;
+
+const ALIVE: usize = 0xA11C_E000;
+const DEAD: usize = 0xDEAD_0000;
+
+/// Counts [`Tracked`] values created by a single check, and records which were dropped.
+#[derive(Default)]
+struct Tally {
+    created: AtomicUsize,
+    dropped: Mutex<BTreeSet<usize>>,
+}
+
+impl Tally {
+    fn dropped(&self) -> MutexGuard<'_, BTreeSet<usize>> {
+        // A failed assertion must not hide the tally from the following ones.
+        self.dropped.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn is_dropped(&self, id: usize) -> bool {
+        self.dropped().contains(&id)
+    }
+}
+
+/// Value poisoned when dropped, so that reading it after it was reclaimed is likely noticed.
+struct Tracked {
+    magic: usize,
+    id: usize,
+    tally: Arc<Tally>,
+}
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: The destructor only accesses the Arc, owned by the value itself.
+unsafe impl<'dom> crate::Hazard<'dom> for Tracked {}
+
+impl Tracked {
+    fn new(id: usize, tally: &Arc<Tally>) -> Self {
+        tally.created.fetch_add(1, Ordering::Relaxed);
+        Self {
+            magic: ALIVE,
+            id,
+            tally: tally.clone(),
+        }
+    }
+
+    #[track_caller]
+    fn assert_alive(&self) {
+        assert_eq!(
+            self.magic, ALIVE,
+            "value {} was reclaimed while it was protected",
+            self.id
+        );
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        assert!(
+            self.magic == ALIVE && self.tally.dropped().insert(self.id),
+            "value {} was dropped twice",
+            self.id
+        );
+        self.magic = DEAD;
+    }
+}
+
+/// Drains `domain` with [`Domain::eager_reclaim`] and asserts that no retired [`Hazards`] remain,
+/// i.e. that all their memory was returned to the allocator. Meant for test and benchmark teardown.
+///
+/// Relies on [`Domain::metrics`], thus always passes for domains that don't report metrics.
+///
+/// [`Hazards`]: crate::Hazard
+///
+#[track_caller]
+pub fn assert_quiescent<'dom, D>(domain: D)
+where
+    D: Domain<'dom>,
+{
+    domain.eager_reclaim();
+    let retired = domain.metrics().retired;
+    assert_eq!(
+        retired, 0,
+        "{} retired hazards remain after draining the domain",
+        retired
+    );
+}
+
+/// Asserts that no [`Anchor`] of `domain` is still holding a [`HazPtr`], which would otherwise
+/// block reclamation for as long as it is leaked.
+///
+/// Relies on [`Domain::metrics`], thus always passes for domains that don't report metrics.
+///
+/// [`HazPtr`]: crate::hazptr::HazPtr
+///
+#[track_caller]
+pub fn assert_no_guards<'dom, D>(domain: D)
+where
+    D: Domain<'dom>,
+{
+    let active = domain.metrics().hazptrs_active;
+    assert_eq!(active, 0, "{} guards are still held", active);
+}
+
+/// Runs every conformance check against a default constructed `D`.
+pub fn assert_domain_conformance<D>()
+where
+    D: Domain<'static> + Default + Send + Sync,
+{
+    assert_domain_conformance_in(D::default())
+}
+
+/// Runs every conformance check against `domain`.
+pub fn assert_domain_conformance_in<D>(domain: D)
+where
+    D: Domain<'static> + Send + Sync,
+{
+    check_acquire(domain);
+    check_protect(domain);
+    check_threads(domain);
+    check_metrics(domain);
+}
+
+/// Acquired [`HazPtrs`][crate::hazptr::HazPtr] must be distinct, and reusable once released.
+fn check_acquire<D>(domain: D)
+where
+    D: Domain<'static>,
+{
+    let anchors = (0..THREADS * 2)
+        .map(|_| Anchor::try_new_in(domain).expect("domain failed to acquire a HazPtr"))
+        .collect::<Vec<_>>();
+
+    for (i, a) in anchors.iter().enumerate() {
+        for b in &anchors[i + 1..] {
+            assert!(
+                !std::ptr::eq(a.hazptr(), b.hazptr()),
+                "domain acquired the same HazPtr twice"
+            );
+        }
+    }
+    drop(anchors);
+
+    for _ in 0..THREADS * 4 {
+        drop(Anchor::try_new_in(domain).expect("domain failed to reacquire a released HazPtr"));
+    }
+}
+
+/// A protected value must survive its retirement and any amount of reclamation pressure, every
+/// retired value must be dropped at most once, and all of them once nothing protects them.
+fn check_protect<D>(domain: D)
+where
+    D: Domain<'static>,
+{
+    let tally = Arc::new(Tally::default());
+    let hazbox = HazBox::new_in(Tracked::new(0, &tally), domain);
+
+    let mut anchor = Anchor::new_in(domain);
+    let protected = anchor.moor(&hazbox) as *const Tracked;
+
+    for id in 1..=FILLERS {
+        hazbox
+            .try_set(Tracked::new(id, &tally))
+            .expect("domain failed to allocate");
+        // Safety: Only dereferenced while the anchor is protecting it.
+        unsafe { &*protected }.assert_alive();
+    }
+
+    drop(anchor);
+    drop(hazbox);
+    domain.eager_reclaim();
+
+    let (created, dropped) = (tally.created.load(Ordering::Relaxed), tally.dropped().len());
+    assert_eq!(
+        dropped, created,
+        "domain dropped {} values out of {} created, once none were protected",
+        dropped, created
+    );
+}
+
+/// Values protected by readers must never be reclaimed by concurrent writers.
+fn check_threads<D>(domain: D)
+where
+    D: Domain<'static> + Send + Sync,
+{
+    let tally = Arc::new(Tally::default());
+    let hazbox = Arc::new(HazBox::new_in(Tracked::new(0, &tally), domain));
+    let done = Arc::new(AtomicBool::new(false));
+
+    let readers = (0..THREADS)
+        .map(|_| {
+            let (hazbox, done) = (hazbox.clone(), done.clone());
+            thread::spawn(move || {
+                let mut anchor = Anchor::new_in(domain);
+                while !done.load(Ordering::Relaxed) {
+                    anchor.moor(&hazbox).assert_alive();
+                    anchor.reset();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let writers = (0..THREADS)
+        .map(|thread| {
+            let (hazbox, tally) = (hazbox.clone(), tally.clone());
+            thread::spawn(move || {
+                for op in 0..OPS_PER_THREAD {
+                    let id = 1 + thread * OPS_PER_THREAD + op;
+                    hazbox
+                        .try_set(Tracked::new(id, &tally))
+                        .expect("domain failed to allocate");
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    writers
+        .into_iter()
+        .for_each(|writer| writer.join().expect("writer panicked"));
+    done.store(true, Ordering::Relaxed);
+    readers
+        .into_iter()
+        .for_each(|reader| reader.join().expect("reader panicked"));
+}
+
+/// Metrics reported by the domain must be consistent with each other.
+fn check_metrics<D>(domain: D)
+where
+    D: Domain<'static>,
+{
+    let metrics = domain.metrics();
+    assert!(
+        metrics.total_reclaimed <= metrics.total_retired,
+        "domain reclaimed more values than were retired"
+    );
+    // Domains that don't count their HazPtrs report 0 for all of these.
+    if metrics.hazptrs_total > 0 {
+        assert!(metrics.hazptrs_active <= metrics.hazptrs_total);
+        assert!(metrics.hazptrs_active <= metrics.hazptrs_active_high_water);
+    }
+}
@@ -0,0 +1,270 @@
+//! Operation model for fuzzing structures built on [`HazBoxes`][HazBox] and [`Anchors`][Anchor].
+//!
+//! A [`Model`] owns a few boxes and anchors in a domain and [applies][Model::apply] enumerated
+//! [`Ops`][Op] to them, keeping an oracle of which values must still be alive: the ones published
+//! in a box and the ones protected by an anchor. After every operation the oracle is checked
+//! against the values actually dropped, and [`Model::finish`] reports any leaks.
+//!
+//! With the `arbitrary` feature, [`Op`] implements [`arbitrary::Arbitrary`], so fuzzers can
+//! generate operation sequences directly:
+//!
+//! ```
+//! # use anchorage::{
+//! #     domain::{global::GlobalDomain as MyDomain, Domain},
+//! #     testkit::ops::{Model, Op},
+//! # };
+//! # macro_rules! fuzz_target {
+//! #     (|$ops:ident: $ty:ty| $body:block) => {
+//! #         (|$ops: $ty| $body)(vec![Op::Moor { anchor: 0, hazbox: 0 }, Op::Set { hazbox: 0 }])
+//! #     };
+//! # }
+//! fuzz_target!(|ops: Vec<Op>| {
+//!     let mut model = Model::new_in(MyDomain);
+//!     model.run(ops);
+//!     let outcome = model.finish();
+//!     MyDomain.eager_reclaim();
+//!     assert_eq!(outcome.leaked(), 0);
+//! });
+//! ```
+
+use std::{
+    fmt,
+    sync::{
+        atomic::Ordering,
+        Arc,
+    },
+};
+
+use super::{
+    Tally,
+    Tracked,
+};
+use crate::{
+    anchor::Anchor,
+    domain::Domain,
+    hazbox::HazBox,
+};
+
+/// Number of [`HazBoxes`][HazBox] owned by a [`Model`].
+pub const BOXES: usize = 4;
+
+/// Number of [`Anchors`][Anchor] owned by a [`Model`].
+pub const ANCHORS: usize = 4;
+
+/// An operation on a [`Model`]. Indices wrap around the number of boxes or anchors.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Op {
+    /// Replaces the value of a box, retiring the old one.
+    Set { hazbox: u8 },
+    /// Protects the value of a box with an anchor, acquiring the anchor first if needed.
+    Moor { anchor: u8, hazbox: u8 },
+    /// Stops protecting the value moored by an anchor.
+    Reset { anchor: u8 },
+    /// Releases an anchor back to the domain.
+    Release { anchor: u8 },
+    /// Reads the value moored by an anchor.
+    Read { anchor: u8 },
+}
+
+/// Counts of values created and dropped by a [`Model`], returned by [`Model::finish`].
+#[derive(Clone)]
+pub struct Outcome {
+    tally: Arc<Tally>,
+}
+
+impl Outcome {
+    /// Number of values created by the model.
+    pub fn created(&self) -> usize {
+        self.tally.created.load(Ordering::Relaxed)
+    }
+
+    /// Number of values dropped so far, which may keep increasing as the domain reclaims them.
+    pub fn dropped(&self) -> usize {
+        self.tally.dropped().len()
+    }
+
+    /// Number of values not yet dropped. Only 0 once the domain reclaimed everything retired to it.
+    pub fn leaked(&self) -> usize {
+        self.created() - self.dropped()
+    }
+}
+
+impl fmt::Debug for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Outcome")
+            .field("created", &self.created())
+            .field("dropped", &self.dropped())
+            .finish()
+    }
+}
+
+/// An anchor owned by a [`Model`], along with what it protects.
+struct Moored<'dom, D>
+where
+    D: Domain<'dom>,
+{
+    anchor: Anchor<'dom, D>,
+    value: Option<(*const Tracked, usize)>,
+}
+
+/// A set of [`HazBoxes`][HazBox] and [`Anchors`][Anchor] driven by [`Ops`][Op], checked against
+/// an oracle of expected liveness.
+pub struct Model<'dom, D>
+where
+    D: Domain<'dom>,
+{
+    domain: D,
+    // Dropped before the boxes, so no anchor outlives the values it protects.
+    anchors: Vec<Option<Moored<'dom, D>>>,
+    boxes: Vec<(HazBox<'dom, Tracked, D>, usize)>,
+    next_id: usize,
+    tally: Arc<Tally>,
+}
+
+impl<'dom, D> Model<'dom, D>
+where
+    D: Domain<'dom>,
+{
+    pub fn new_in(domain: D) -> Self {
+        let tally = Arc::new(Tally::default());
+        let boxes = (0..BOXES)
+            .map(|id| (HazBox::new_in(Tracked::new(id, &tally), domain), id))
+            .collect();
+
+        Self {
+            domain,
+            anchors: (0..ANCHORS).map(|_| None).collect(),
+            boxes,
+            next_id: BOXES,
+            tally,
+        }
+    }
+
+    #[inline]
+    pub fn domain(&self) -> D {
+        self.domain
+    }
+
+    /// Applies every operation in `ops` in order.
+    pub fn run<I>(&mut self, ops: I)
+    where
+        I: IntoIterator<Item = Op>,
+    {
+        ops.into_iter().for_each(|op| self.apply(op))
+    }
+
+    /// Applies `op`, then checks that no value expected to be alive was dropped.
+    ///
+    /// # Panics
+    ///
+    /// If a published or protected value was dropped, or any value was dropped twice.
+    ///
+    pub fn apply(&mut self, op: Op) {
+        match op {
+            Op::Set { hazbox } => {
+                // Ids are never reused, even by values dropped because their allocation failed.
+                let id = self.next_id;
+                self.next_id += 1;
+                let value = Tracked::new(id, &self.tally);
+                let (hazbox, published) = &mut self.boxes[hazbox as usize % BOXES];
+                // Allocation failures leave the box unchanged and are not a conformance issue.
+                if hazbox.try_set(value).is_ok() {
+                    *published = id;
+                }
+            }
+            Op::Moor { anchor, hazbox } => {
+                let domain = self.domain;
+                let slot = &mut self.anchors[anchor as usize % ANCHORS];
+                if slot.is_none() {
+                    *slot = Anchor::try_new_in(domain).map(|anchor| Moored {
+                        anchor,
+                        value: None,
+                    });
+                }
+                if let Some(moored) = slot {
+                    let value = moored.anchor.moor(&self.boxes[hazbox as usize % BOXES].0);
+                    moored.value = Some((value as *const Tracked, value.id));
+                }
+            }
+            Op::Reset { anchor } => {
+                if let Some(moored) = &mut self.anchors[anchor as usize % ANCHORS] {
+                    moored.anchor.reset();
+                    moored.value = None;
+                }
+            }
+            Op::Release { anchor } => self.anchors[anchor as usize % ANCHORS] = None,
+            Op::Read { anchor } => {
+                if let Some(Moored {
+                    value: Some((value, id)),
+                    ..
+                }) = &self.anchors[anchor as usize % ANCHORS]
+                {
+                    // Safety: The value is protected by the anchor until it is reset or released.
+                    let value = unsafe { &**value };
+                    value.assert_alive();
+                    assert_eq!(value.id, *id, "protected value changed under its anchor");
+                }
+            }
+        }
+
+        self.check();
+    }
+
+    /// Drops every box and anchor, returning the counts of values created and dropped.
+    ///
+    /// Values retired to the domain may still be waiting to be reclaimed, thus leaks can only be
+    /// checked once the domain is drained, e.g. by [`eager_reclaim`] or by dropping it.
+    ///
+    /// [`eager_reclaim`]: crate::domain::global::GlobalDomain::eager_reclaim
+    ///
+    pub fn finish(self) -> Outcome {
+        Outcome {
+            tally: self.tally.clone(),
+        }
+    }
+
+    /// Checks the oracle: published and protected values must not have been dropped.
+    fn check(&self) {
+        let published = self.boxes.iter().map(|&(_, id)| id);
+        let protected = self
+            .anchors
+            .iter()
+            .flatten()
+            .filter_map(|moored| moored.value.map(|(_, id)| id));
+
+        for id in published.chain(protected) {
+            assert!(
+                !self.tally.is_dropped(id),
+                "value {} was dropped while still published or protected",
+                id
+            );
+        }
+    }
+}
+
+impl<'dom, D> fmt::Debug for Model<'dom, D>
+where
+    D: Domain<'dom>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Model")
+            .field(
+                "published",
+                &self.boxes.iter().map(|&(_, id)| id).collect::<Vec<_>>(),
+            )
+            .field(
+                "protected",
+                &self
+                    .anchors
+                    .iter()
+                    .map(|moored| {
+                        moored
+                            .as_ref()
+                            .and_then(|moored| moored.value.map(|(_, id)| id))
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,228 @@
+//! Long-running stress harness for domains and the structures built on them.
+//!
+//! A [`Soak`] runs a weighted mix of user-provided operations from several threads for a fixed
+//! duration, while the calling thread periodically invokes [`Domain::verify`]. Any panic, either
+//! from an operation or from a broken invariant, fails the run.
+//!
+//! ```
+//! # use std::{sync::Arc, time::Duration};
+//! # use anchorage::{domain::global::GlobalDomain, testkit::soak::Soak};
+//! # struct MyQueue(std::sync::Mutex<Vec<u32>>);
+//! # impl MyQueue {
+//! #     fn new_in(_: GlobalDomain) -> Self { Self(Default::default()) }
+//! #     fn push(&self, value: u32) { self.0.lock().unwrap().push(value) }
+//! #     fn pop(&self) -> Option<u32> { self.0.lock().unwrap().pop() }
+//! # }
+//! let queue = Arc::new(MyQueue::new_in(GlobalDomain));
+//! let report = Soak::new(GlobalDomain)
+//!     .threads(8)
+//!     .duration(Duration::from_secs(30))
+//! #   .duration(Duration::from_millis(10))
+//!     .op(3, { let queue = queue.clone(); move |_| queue.push(1) })
+//!     .op(2, { let queue = queue.clone(); move |_| drop(queue.pop()) })
+//!     .run();
+//! ```
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicU64,
+            Ordering,
+        },
+        Arc,
+    },
+    thread,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use crate::domain::Domain;
+
+type SoakOp<D> = Arc<dyn Fn(D) + Send + Sync>;
+
+/// Builder and runner for a soak test against a domain.
+pub struct Soak<D>
+where
+    D: Domain<'static>,
+{
+    domain: D,
+    threads: usize,
+    duration: Duration,
+    verify_every: Duration,
+    seed: u64,
+    ops: Vec<(u32, SoakOp<D>)>,
+}
+
+/// Results of a [`Soak`] run.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SoakReport {
+    /// Number of times each operation ran, in the order they were added.
+    pub ops: Vec<u64>,
+    /// Number of times [`Domain::verify`] was called.
+    pub verifications: u64,
+}
+
+impl<D> Soak<D>
+where
+    D: Domain<'static> + Send + Sync,
+{
+    /// Creates a soak test with 4 threads, running for 1 second and verifying every 10
+    /// milliseconds, with no operations.
+    pub fn new(domain: D) -> Self {
+        Self {
+            domain,
+            threads: 4,
+            duration: Duration::from_secs(1),
+            verify_every: Duration::from_millis(10),
+            seed: 0x5eed,
+            ops: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    #[inline]
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    #[inline]
+    pub fn verify_every(mut self, verify_every: Duration) -> Self {
+        self.verify_every = verify_every;
+        self
+    }
+
+    /// Sets the seed used to pick operations, so the mix can be varied between runs.
+    #[inline]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Adds an operation, picked with probability proportional to `weight`.
+    pub fn op<F>(mut self, weight: u32, op: F) -> Self
+    where
+        F: Fn(D) + Send + Sync + 'static,
+    {
+        self.ops.push((weight, Arc::new(op)));
+        self
+    }
+
+    /// Runs the soak test, returning how many times each operation ran.
+    ///
+    /// # Panics
+    ///
+    /// If no operation with a non zero weight was added, if any operation panics, or if
+    /// [`Domain::verify`] panics.
+    ///
+    pub fn run(self) -> SoakReport {
+        let Self {
+            domain,
+            threads,
+            duration,
+            verify_every,
+            seed,
+            ops,
+        } = self;
+
+        let total_weight = ops.iter().map(|&(weight, _)| weight as u64).sum::<u64>();
+        assert!(total_weight > 0, "soak test has no operations to run");
+
+        let ops = Arc::new(ops);
+        let counts = Arc::new(
+            (0..ops.len())
+                .map(|_| AtomicU64::new(0))
+                .collect::<Vec<_>>(),
+        );
+        let done = Arc::new(AtomicBool::new(false));
+
+        let workers = (0..threads as u64)
+            .map(|thread| {
+                let (ops, counts, done) = (ops.clone(), counts.clone(), done.clone());
+                let mut rng = (seed ^ thread.wrapping_mul(0x9e37_79b9_7f4a_7c15)) | 1;
+                thread::spawn(move || {
+                    while !done.load(Ordering::Relaxed) {
+                        rng ^= rng >> 12;
+                        rng ^= rng << 25;
+                        rng ^= rng >> 27;
+                        let mut pick = rng.wrapping_mul(0x2545_f491_4f6c_dd1d) % total_weight;
+
+                        let index = ops
+                            .iter()
+                            .position(|&(weight, _)| match pick.checked_sub(weight as u64) {
+                                Some(rest) => {
+                                    pick = rest;
+                                    false
+                                }
+                                None => true,
+                            })
+                            .unwrap_or(ops.len() - 1);
+
+                        (ops[index].1)(domain);
+                        counts[index].fetch_add(1, Ordering::Relaxed);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Stops the workers even if verify panics, instead of leaving them running forever.
+        let stop = Stop(&done);
+        let deadline = Instant::now() + duration;
+        let mut verifications = 0;
+        while Instant::now() < deadline {
+            thread::sleep(verify_every.min(deadline.saturating_duration_since(Instant::now())));
+            domain.verify();
+            verifications += 1;
+        }
+
+        drop(stop);
+        for worker in workers {
+            if let Err(panic) = worker.join() {
+                std::panic::resume_unwind(panic);
+            }
+        }
+        domain.verify();
+
+        SoakReport {
+            ops: counts
+                .iter()
+                .map(|count| count.load(Ordering::Relaxed))
+                .collect(),
+            verifications: verifications + 1,
+        }
+    }
+}
+
+/// Tells the workers of a soak test to stop once dropped.
+struct Stop<'a>(&'a AtomicBool);
+
+impl Drop for Stop<'_> {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl<D> fmt::Debug for Soak<D>
+where
+    D: Domain<'static> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Soak")
+            .field("domain", &self.domain)
+            .field("threads", &self.threads)
+            .field("duration", &self.duration)
+            .field("verify_every", &self.verify_every)
+            .field("seed", &self.seed)
+            .field("ops", &self.ops.len())
+            .finish()
+    }
+}
@@ -0,0 +1,124 @@
+//! Detection of guards held for too long, enabled by the `watchdog` feature.
+//!
+//! A single leaked or stuck protection silently blocks the reclamation of whatever it protects.
+//! With this feature, every [`HazPtr`] records when it started protecting its current address, and
+//! protections older than the [threshold][set_threshold] are reported to a [callback][set_callback]
+//! whenever a reclamation pass scans the [`HazPtrs`][HazPtr] of a domain, or on demand with [check].
+
+use std::{
+    sync::{
+        atomic::{
+            AtomicU64,
+            AtomicUsize,
+            Ordering,
+        },
+        LazyLock,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use crate::{
+    domain::Domain,
+    hazptr::HazPtr,
+};
+
+/// A protection held for longer than the [threshold][set_threshold].
+#[cfg_attr(not(feature = "owner-tags"), derive(Copy))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StuckGuard {
+    /// The protected address.
+    pub addr: usize,
+    /// How long the address has been protected for.
+    pub held_for: Duration,
+    /// Who acquired the protecting [`HazPtr`], if an anchor did.
+    #[cfg(feature = "owner-tags")]
+    pub owner: Option<crate::owner::Owner>,
+}
+
+static BASE: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+static THRESHOLD_NANOS: AtomicU64 = AtomicU64::new(1_000_000_000);
+
+/// The callback, as a `fn(&StuckGuard)`, or 0 for the default one.
+static CALLBACK: AtomicUsize = AtomicUsize::new(0);
+
+pub(crate) fn log_stuck(guard: &StuckGuard) {
+    eprintln!(
+        "anchorage: address {:#x} has been protected for {:?}, blocking its reclamation",
+        guard.addr, guard.held_for
+    );
+    #[cfg(feature = "owner-tags")]
+    if let Some(owner) = &guard.owner {
+        eprintln!("anchorage: it is protected by {}", owner);
+    }
+}
+
+/// Sets how long a protection may be held before being reported. Defaults to 1 second.
+pub fn set_threshold(threshold: Duration) {
+    let nanos = threshold.as_nanos().min(u64::MAX as u128) as u64;
+    THRESHOLD_NANOS.store(nanos, Ordering::Relaxed);
+}
+
+/// Sets the function called with every protection held for too long.
+/// Defaults to printing it to stderr.
+pub fn set_callback(callback: fn(&StuckGuard)) {
+    CALLBACK.store(callback as usize, Ordering::Relaxed);
+}
+
+/// Reports all protections in `domain` held for too long, returning how many there were.
+pub fn check<'dom, D>(domain: D) -> usize
+where
+    D: Domain<'dom>,
+{
+    let mut stuck = 0;
+    domain.visit_hazptrs(&mut |hazptr| stuck += check_hazptr(hazptr) as usize);
+    stuck
+}
+
+/// Current time, in nanoseconds since an arbitrary point in the past. Never 0.
+#[inline]
+pub(crate) fn now() -> u64 {
+    BASE.elapsed().as_nanos() as u64 + 1
+}
+
+/// Reports the protection held by `hazptr` if held for too long, returning whether it was.
+pub(crate) fn check_hazptr(hazptr: &HazPtr) -> bool {
+    let threshold = Duration::from_nanos(THRESHOLD_NANOS.load(Ordering::Relaxed));
+    match stuck_guard(hazptr, threshold) {
+        Some(guard) => {
+            match CALLBACK.load(Ordering::Relaxed) {
+                0 => log_stuck(&guard),
+                // Safety: Only ever set from a valid fn(&StuckGuard) pointer.
+                callback => unsafe {
+                    std::mem::transmute::<usize, fn(&StuckGuard)>(callback)(&guard)
+                },
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the protection held by `hazptr` if held for at least `threshold`.
+pub(crate) fn stuck_guard(hazptr: &HazPtr, threshold: Duration) -> Option<StuckGuard> {
+    let since = hazptr.protected_since();
+    let addr = hazptr.ptr() as usize;
+    if since == 0 || addr == 0 {
+        return None;
+    }
+
+    let held_for = Duration::from_nanos(now().saturating_sub(since));
+    if held_for < threshold {
+        return None;
+    }
+
+    Some(StuckGuard {
+        addr,
+        held_for,
+        #[cfg(feature = "owner-tags")]
+        owner: crate::owner::owner_of(hazptr),
+    })
+}
@@ -0,0 +1,67 @@
+use std::{
+    panic::{
+        self,
+        AssertUnwindSafe,
+    },
+    sync::{
+        atomic::Ordering,
+        Arc,
+    },
+};
+
+use anchorage::{
+    anchor::Anchor,
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazbox::HazBox,
+};
+
+#[test]
+fn upgrade_ignores_tags() {
+    let src = HazBox::new(1_u64);
+    let mut anchor = Anchor::new();
+    anchor.moor(&src);
+
+    let weak = anchor.downgrade();
+    src.fetch_or_tag(1, Ordering::AcqRel);
+
+    let mut anchor = weak.upgrade(&src).ok().unwrap();
+    assert_eq!(anchor.moor_tagged(&src), (&1, 1));
+}
+
+#[test]
+fn upgrade_tagged_compares_tags() {
+    let src = HazBox::new(1_u64);
+    let mut anchor = Anchor::new();
+    let (_, tag) = anchor.moor_tagged(&src);
+
+    let weak = anchor.downgrade();
+    src.fetch_or_tag(1, Ordering::AcqRel);
+
+    let weak = match weak.upgrade_tagged(&src, tag) {
+        Ok(_) => panic!("upgraded though the tag changed"),
+        Err(weak) => weak,
+    };
+    let mut anchor = weak.upgrade_tagged(&src, 1).ok().unwrap();
+    assert_eq!(anchor.moor_tagged(&src), (&1, 1));
+}
+
+#[test]
+fn moor_with_resets_the_anchor_when_unwinding() {
+    let value = Arc::new(());
+    let src = HazBox::new(value.clone());
+    let mut anchor = Anchor::new();
+
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        anchor.moor_with(&src, |_| panic!("reading failed"))
+    }));
+    assert!(res.is_err());
+
+    // The anchor is still held, but must not protect the value it was moored to anymore.
+    src.set(Arc::new(()));
+    GlobalDomain.eager_reclaim();
+    assert_eq!(Arc::strong_count(&value), 1);
+    drop(anchor);
+}
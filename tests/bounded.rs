@@ -0,0 +1,56 @@
+use std::{
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+    thread,
+    time::Duration,
+};
+
+use anchorage::{
+    anchor::Anchor,
+    domain::{
+        bounded::{
+            BoundedDomain,
+            Bounds,
+            Enforcement,
+        },
+        global::GlobalDomain,
+    },
+    hazbox::HazBox,
+    watchdog::StuckGuard,
+};
+
+static EXCEEDED: AtomicUsize = AtomicUsize::new(0);
+
+fn exceeded(_guard: &StuckGuard) {
+    EXCEEDED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn enforces_only_its_own_protections() {
+    static BOUNDS: Bounds = Bounds::new(Duration::from_millis(1), Enforcement::Callback(exceeded));
+    static OTHER: Bounds = Bounds::new(Duration::from_millis(1), Enforcement::Abort);
+
+    let domain = BoundedDomain::new(GlobalDomain, &BOUNDS);
+    let bounded = HazBox::new_in(1, domain);
+    let unbounded = HazBox::new(2);
+    let other = HazBox::new_in(3, BoundedDomain::new(GlobalDomain, &OTHER));
+
+    let mut held = Anchor::new_in(domain);
+    let mut unrelated = Anchor::new();
+    assert_eq!(*held.moor(&bounded), 1);
+    assert_eq!(*unrelated.moor(&unbounded), 2);
+    thread::sleep(Duration::from_millis(5));
+
+    assert_eq!(domain.enforce(), 1);
+    assert_eq!(EXCEEDED.load(Ordering::SeqCst), 1);
+
+    // Protections through other bounds are only enforced by domains under them.
+    let mut outer = Anchor::new_in(other.domain());
+    assert_eq!(*outer.moor(&other), 3);
+    drop(held);
+    thread::sleep(Duration::from_millis(5));
+    assert_eq!(domain.enforce(), 0);
+    drop(outer);
+}
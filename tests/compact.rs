@@ -0,0 +1,111 @@
+#![feature(allocator_api)]
+
+use std::{
+    alloc::{
+        Allocator,
+        Layout,
+    },
+    ptr::NonNull,
+};
+
+use anchorage::{
+    anchor::Anchor,
+    compact::{
+        CompactBox,
+        Slab,
+    },
+    domain::{
+        backend::{
+            BackendDomain,
+            HazPtrSlots,
+            LockedRetired,
+            Threshold,
+        },
+        scoped::ScopedDomain,
+        Domain,
+    },
+};
+
+#[test]
+fn try_swap_returns_value_when_full() {
+    let domain = ScopedDomain::new_in(Slab::with_capacity(1));
+    // Safety: The values borrow nothing.
+    let handle = unsafe { domain.handle() };
+    let compact = CompactBox::new_in(String::from("first"), handle);
+
+    match compact.try_swap(String::from("second")) {
+        Ok(_) => panic!("the slab has a single slot"),
+        Err((_, value)) => assert_eq!(value, "second"),
+    };
+}
+
+#[test]
+fn swapped_out_slots_are_freed() {
+    let domain = ScopedDomain::new_in(Slab::with_capacity(2));
+    // Safety: Same as above.
+    let handle = unsafe { domain.handle() };
+    let compact = CompactBox::new_in(0_u64, handle);
+
+    for value in 1..10 {
+        assert!(
+            compact.try_swap(value).is_ok(),
+            "swap {} found no free slot",
+            value
+        );
+        handle.eager_reclaim();
+    }
+}
+
+#[test]
+fn protected_slots_are_freed_once_reclaimed() {
+    let threshold = Threshold {
+        retired: usize::MAX,
+        per_hazptr: 0,
+    };
+    let domain = BackendDomain::new_in(
+        HazPtrSlots::new(),
+        LockedRetired::new(),
+        threshold,
+        Slab::with_capacity(2),
+    );
+    // Safety: Same as above.
+    let handle = unsafe { domain.handle() };
+    let compact = CompactBox::new_in(0_u64, handle);
+
+    for value in 1..10 {
+        let mut anchor = Anchor::new_in(handle);
+        assert_eq!(*compact.moor(&mut anchor), value - 1);
+        let old = compact.swap(value);
+        assert_eq!(*old, value - 1);
+        drop(old);
+        // The old slot stays retired while protected.
+        assert_eq!(handle.eager_reclaim(), 0);
+
+        anchor.reset();
+        assert_eq!(handle.eager_reclaim(), 1);
+    }
+}
+
+#[test]
+#[should_panic(expected = "wasn't allocated by this slab")]
+fn deallocate_rejects_misaligned_pointers() {
+    let slab = Slab::<u64>::with_capacity(2);
+    let slot = slab.allocate(Layout::new::<u64>()).unwrap().cast::<u8>();
+    // Safety: The slab panics before touching the pointer.
+    unsafe {
+        let inside = NonNull::new_unchecked(slot.as_ptr().add(1));
+        slab.deallocate(inside, Layout::new::<u64>());
+    }
+}
+
+#[test]
+#[should_panic(expected = "wasn't allocated by this slab")]
+fn deallocate_rejects_foreign_pointers() {
+    let slab = Slab::<u64>::with_capacity(2);
+    let mut foreign = 0_u64;
+    // Safety: Same as above.
+    unsafe {
+        let foreign = NonNull::from(&mut foreign).cast::<u8>();
+        slab.deallocate(foreign, Layout::new::<u64>());
+    }
+}
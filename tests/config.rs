@@ -0,0 +1,68 @@
+use std::{
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+    },
+    thread,
+};
+
+use anchorage::config::SnapshotConfig;
+
+#[test]
+fn hooks_may_change_the_config() {
+    let config = Arc::new(SnapshotConfig::new(0));
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    config.on_change({
+        let config = Arc::downgrade(&config);
+        let calls = calls.clone();
+        move |_, &new| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            let config = config.upgrade().unwrap();
+            if new == 1 {
+                config.store(2);
+                config.on_change(|_, _| {});
+            }
+        }
+    });
+
+    config.store(1);
+    assert_eq!(*config.load(), 2);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn hooks_see_changes_in_order() {
+    let config = Arc::new(SnapshotConfig::new(0_u64));
+    let changes = Arc::new(Mutex::new(Vec::new()));
+
+    config.on_change({
+        let changes = changes.clone();
+        move |&old, &new| changes.lock().unwrap().push((old, new))
+    });
+
+    let writers: Vec<_> = (0..4)
+        .map(|_| {
+            let config = config.clone();
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    config.update(|count| count + 1);
+                }
+            })
+        })
+        .collect();
+    writers
+        .into_iter()
+        .for_each(|writer| writer.join().unwrap());
+
+    let changes = changes.lock().unwrap();
+    assert_eq!(changes.len(), 400);
+    assert!(changes
+        .iter()
+        .enumerate()
+        .all(|(i, &change)| change == (i as u64, i as u64 + 1)));
+}
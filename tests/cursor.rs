@@ -0,0 +1,82 @@
+use std::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+
+use anchorage::{
+    cursor::Cursor,
+    domain::{
+        global::GlobalDomain,
+        Domain,
+    },
+    hazoption::HazOption,
+};
+
+struct Node {
+    key: u32,
+    next: HazOption<'static, Node, GlobalDomain>,
+    drops: &'static AtomicUsize,
+}
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Dropping the node only touches a static and the nodes it owns.
+unsafe impl<'dom> anchorage::Hazard<'dom> for Node {}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn list(keys: &[u32], drops: &'static AtomicUsize) -> HazOption<'static, Node, GlobalDomain> {
+    keys.iter().rev().fold(HazOption::new(None), |next, &key| {
+        HazOption::new(Some(Node { key, next, drops }))
+    })
+}
+
+#[test]
+fn walks_every_node() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let head = list(&[1, 2, 3], &DROPS);
+
+    let mut cursor = Cursor::new();
+    let mut keys = Vec::new();
+    // Safety: Nothing is unlinked while walking.
+    let mut node = unsafe { cursor.start(&head) };
+    while let Some(entry) = node {
+        keys.push(entry.key);
+        node = unsafe { cursor.advance(|entry| &entry.next) };
+    }
+
+    assert_eq!(keys, [1, 2, 3]);
+    assert!(cursor.current().is_none());
+}
+
+#[test]
+fn keeps_retired_node_alive() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    let head = list(&[1, 2, 3], &DROPS);
+
+    let mut cursor = Cursor::new();
+    // Safety: The second node is unlinked by take, which retires it along with its links.
+    unsafe {
+        cursor.start(&head);
+        assert_eq!(cursor.advance(|entry| &entry.next).unwrap().key, 2);
+    }
+
+    let mut anchor = anchorage::anchor::Anchor::new();
+    drop(anchor.moor_opt(&head).unwrap().next.take());
+    anchor.reset();
+    GlobalDomain.eager_reclaim();
+
+    assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+    assert_eq!(cursor.current().unwrap().key, 2);
+    assert_eq!(
+        unsafe { cursor.advance(|entry| &entry.next) }.unwrap().key,
+        3
+    );
+
+    cursor.reset();
+    GlobalDomain.eager_reclaim();
+    assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,54 @@
+#![feature(allocator_api)]
+
+use std::alloc::{
+    AllocError,
+    Global,
+};
+
+use anchorage::{
+    anchor::Anchor,
+    domain::{
+        global::GlobalDomain,
+        scoped::ScopedDomain,
+    },
+    failpoints::{
+        self,
+        Failpoint,
+    },
+    hazbox::HazBox,
+};
+
+#[test]
+fn acquire_fails_in_domain() {
+    failpoints::fail_next(Failpoint::Acquire);
+    assert!(Anchor::try_new_in(GlobalDomain).is_none());
+    assert!(Anchor::try_new_in(GlobalDomain).is_some());
+}
+
+#[test]
+fn node_alloc_fails_acquire() {
+    let domain = ScopedDomain::new_in(Global);
+    // Safety: Nothing is retired.
+    let handle = unsafe { domain.handle() };
+
+    // Fills the records kept inline, so that the next one needs a node.
+    let inline = (0..8).map(|_| Anchor::new_in(handle)).collect::<Vec<_>>();
+    failpoints::fail_next(Failpoint::NodeAlloc);
+    assert!(Anchor::try_new_in(handle).is_none());
+    assert!(Anchor::try_new_in(handle).is_some());
+    drop(inline);
+}
+
+#[test]
+fn alloc_fails_before_initializing() {
+    failpoints::fail_next(Failpoint::Alloc);
+    let failed = HazBox::<u64, _>::try_new_with_in(
+        |_| -> Result<_, AllocError> { panic!("initialized without storage") },
+        GlobalDomain,
+    );
+    assert!(failed.is_err());
+
+    let haz = HazBox::try_new_with_in(|slot| Ok::<_, AllocError>(slot.write(1_u64)), GlobalDomain)
+        .unwrap();
+    assert_eq!(*Anchor::new().moor(&haz), 1);
+}
@@ -0,0 +1,71 @@
+use std::sync::{
+    atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+    Arc,
+};
+
+use anchorage::{
+    anchor::Anchor,
+    domain::{
+        global::{
+            GlobalDomain,
+            ReclaimBudget,
+        },
+        Domain,
+    },
+    hazbox::{
+        GlobalHazBox,
+        HazBox,
+    },
+};
+
+struct CountDrops(Arc<AtomicUsize>);
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Dropping it only touches the counter it owns.
+unsafe impl<'dom> anchorage::Hazard<'dom> for CountDrops {}
+
+impl Drop for CountDrops {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn empty_budget_reclaims_nothing() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let hazbox = HazBox::new(CountDrops(drops.clone()));
+    // Keeps the domain from reclaiming the old value as soon as it's retired.
+    let other = HazBox::new(0);
+    let mut anchor = Anchor::new();
+    anchor.moor(&other);
+    // The first retirement may run the timed cleanup, leaving the next one retired.
+    hazbox.set(CountDrops(drops.clone()));
+    hazbox.set(CountDrops(drops.clone()));
+    let dropped = drops.load(Ordering::SeqCst);
+
+    let progress = GlobalDomain.eager_reclaim_within(ReclaimBudget::default().max_objects(0));
+    assert_eq!(progress.reclaimed, 0);
+    assert_eq!(progress.blocked, 0);
+    assert_eq!(drops.load(Ordering::SeqCst), dropped);
+
+    let progress = GlobalDomain.eager_reclaim_within(ReclaimBudget::default());
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+    assert_eq!(progress.reclaimed, 2 - dropped);
+}
+
+#[test]
+fn adopted_boxes_count_as_allocated() {
+    const SIZE: usize = 1 << 16;
+    let before = GlobalDomain.metrics().allocated_bytes;
+    let hazbox: GlobalHazBox<[u8; SIZE]> = Box::new([0_u8; SIZE]).into();
+    let adopted = GlobalDomain.metrics().allocated_bytes;
+    drop(hazbox);
+    let after = GlobalDomain.metrics().allocated_bytes;
+
+    // Other tests may allocate meanwhile, but far less.
+    assert!(adopted >= before + SIZE / 2);
+    assert!(after + SIZE / 2 <= adopted);
+}
@@ -0,0 +1,66 @@
+use std::{
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        mpsc,
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use anchorage::{
+    anchor::Anchor,
+    hazbox::HazBox,
+};
+
+#[test]
+fn swap_from_waits_for_readers() {
+    let from = Arc::new(HazBox::new(String::from("moved")));
+    let to = HazBox::new(String::from("replaced"));
+    let released = Arc::new(AtomicBool::new(false));
+
+    let (moored_tx, moored_rx) = mpsc::channel();
+    let (swapped_tx, swapped_rx) = mpsc::channel();
+    let reader = thread::spawn({
+        let from = from.clone();
+        let released = released.clone();
+        move || {
+            let mut anchor = Anchor::new();
+            let value = anchor.moor(&from);
+            moored_tx.send(()).unwrap();
+            swapped_rx.recv().unwrap();
+
+            thread::sleep(Duration::from_millis(50));
+            assert_eq!(value, "moved");
+            released.store(true, Ordering::SeqCst);
+        }
+    });
+
+    moored_rx.recv().unwrap();
+    let retired = from.swap(String::from("new"));
+    swapped_tx.send(()).unwrap();
+    let old = to.swap_from(retired).ok().unwrap();
+    assert!(released.load(Ordering::SeqCst));
+    assert_eq!(*old, "replaced");
+
+    reader.join().unwrap();
+    assert_eq!(to.into_inner(), "moved");
+}
+
+#[test]
+fn try_moor_keeps_the_anchor_apart_from_the_error() {
+    let src = HazBox::new(1);
+    let stale = src.as_ptr(Ordering::Relaxed);
+    src.set(2);
+
+    let mut anchor = Anchor::new();
+    let err = anchor.try_moor(&src, stale).unwrap_err();
+    assert_eq!(err.expected(), stale as usize);
+    assert_eq!(*anchor.try_moor(&src, err.actual() as *mut _).unwrap(), 2);
+
+    let boxed: Box<dyn std::error::Error + Send + Sync + 'static> = Box::new(err);
+    assert!(boxed.to_string().starts_with("expected"));
+}
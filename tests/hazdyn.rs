@@ -0,0 +1,60 @@
+use std::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+
+use anchorage::{
+    anchor::Anchor,
+    domain::child::ChildDomain,
+    hazdyn::HazDyn,
+};
+
+struct Counted(&'static AtomicUsize, u32);
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Counting the drop only touches a static.
+unsafe impl<'dom> anchorage::Hazard<'dom> for Counted {}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn slices_are_reclaimed_inline() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    let domain = ChildDomain::new();
+    // Safety: The values only borrow statics.
+    let handle = unsafe { domain.handle() };
+    let slice = HazDyn::from_iter_in((0..3).map(|i| Counted(&DROPS, i)), handle);
+
+    let mut anchor = Anchor::new_in(handle);
+    let moored = anchor.moor_dyn(&slice);
+    assert_eq!(
+        moored.iter().map(|item| item.1).collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+
+    slice.set_from_iter((0..5).map(|i| Counted(&DROPS, i + 10)));
+    assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+    assert_eq!(moored[2].1, 2);
+
+    drop((anchor, slice));
+    assert_eq!(DROPS.load(Ordering::SeqCst), 5);
+    drop(domain);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 8);
+}
+
+#[test]
+fn strs_are_swapped() {
+    let text = HazDyn::from("first");
+    let mut anchor = Anchor::new();
+    assert_eq!(anchor.moor_dyn(&text), "first");
+
+    text.set_str("a longer second");
+    assert_eq!(anchor.moor_dyn(&text), "a longer second");
+    text.set_str("");
+    assert_eq!(anchor.moor_dyn(&text), "");
+}
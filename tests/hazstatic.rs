@@ -0,0 +1,54 @@
+use std::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+
+use anchorage::{
+    anchor::Anchor,
+    domain::global::GlobalDomain,
+    hazstatic::HazStatic,
+};
+
+#[test]
+fn static_value_is_left_alone() {
+    static DEFAULT: String = String::new();
+
+    let config = HazStatic::from_static(&DEFAULT);
+    let mut anchor = Anchor::new();
+    assert_eq!(anchor.moor_static(&config), "");
+    assert!(config.is_static());
+
+    let old = config.swap(String::from("loaded"));
+    assert!(old.is_static());
+    assert!(!config.is_static());
+    assert_eq!(anchor.moor_static(&config), "loaded");
+
+    assert!(!config.swap(String::from("reloaded")).is_static());
+}
+
+#[test]
+fn zero_sized_values_are_dropped_once_swapped_out() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Marker;
+
+    #[cfg(feature = "explicit-hazard")]
+    // Safety: Counting the drop only touches a static.
+    unsafe impl<'dom> anchorage::Hazard<'dom> for Marker {}
+
+    impl Drop for Marker {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    static DEFAULT: Marker = Marker;
+
+    let marker = HazStatic::from_static(&DEFAULT);
+    assert!(marker.swap(Marker).is_static());
+    assert!(!marker.swap(Marker).is_static());
+    drop(marker);
+
+    GlobalDomain.eager_reclaim();
+    assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+}
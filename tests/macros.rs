@@ -0,0 +1,71 @@
+use anchorage::{
+    anchor::{
+        Anchor,
+        AnchorSet,
+    },
+    domain,
+    hazbox,
+    hazbox::HazBox,
+    protect,
+};
+
+domain! {
+    /// Domain declared without enabling any unstable feature in this crate.
+    struct TestDomain {
+        static NAMES: Vec<&'static str> = vec!["first"];
+        static SCRATCH: usize = 0;
+    }
+}
+
+hazbox! {
+    static COUNT: usize = 1;
+    static LIMIT in TestDomain: usize = 10;
+}
+
+#[test]
+fn declared_boxes_start_with_their_initial_values() {
+    let mut anchor = Anchor::new();
+    assert_eq!(*anchor.moor(&COUNT), 1);
+
+    let mut anchor = Anchor::new_in(TestDomain);
+    assert_eq!(*anchor.moor(&NAMES), ["first"]);
+    anchor.reset();
+    assert_eq!(*anchor.moor(&LIMIT), 10);
+}
+
+#[test]
+fn declared_domains_reclaim_on_their_own() {
+    let mut anchor = Anchor::new_in(TestDomain);
+    assert_eq!(*anchor.moor(&SCRATCH), 0);
+    SCRATCH.set(1);
+    // The old value stays retired while the anchor protects it.
+    assert_eq!(TestDomain.eager_reclaim(), 0);
+
+    drop(anchor);
+    assert_eq!(TestDomain.eager_reclaim(), 1);
+}
+
+#[test]
+fn protect_moors_single_boxes() {
+    let mut anchor = Anchor::new();
+    let count = protect!(anchor, COUNT);
+    assert_eq!(*count, 1);
+}
+
+#[test]
+fn protect_moors_arrays_of_boxes() {
+    let (first, second) = (HazBox::new(1), HazBox::new(2));
+    let mut anchors = AnchorSet::new();
+
+    let [first, second] = protect!(anchors => [first, second]);
+    assert_eq!((*first, *second), (1, 2));
+}
+
+#[test]
+fn protect_moors_tuples_of_boxes() {
+    let (count, name) = (HazBox::new(1), HazBox::new(String::from("first")));
+    let mut anchors = AnchorSet::new();
+
+    let (count, name) = protect!(anchors => (count, name));
+    assert_eq!((*count, name.as_str()), (1, "first"));
+}
@@ -0,0 +1,59 @@
+use anchorage::map::{
+    Entry,
+    HazMap,
+};
+
+#[test]
+fn insert_returns_the_replaced_value() {
+    let map = HazMap::new();
+
+    assert!(map.insert(1, String::from("first")).is_none());
+    let old = map.insert(1, String::from("second")).unwrap();
+
+    assert_eq!(*old, "first");
+    assert_eq!(map.get(&1).unwrap().get(), "second");
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn entry_inserts_anew_after_the_key_is_removed() {
+    let map = HazMap::new();
+    map.insert(1, String::from("first"));
+
+    let Entry::Occupied(mut entry) = map.entry(1) else {
+        panic!("the key was inserted");
+    };
+    assert!(map.remove(&1));
+    assert!(map.get(&1).is_none());
+
+    assert!(entry.insert(String::from("second")).is_none());
+    assert_eq!(entry.get(), "second");
+    assert_eq!(map.get(&1).unwrap().get(), "second");
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn entry_update_fails_after_the_key_is_removed() {
+    let map = HazMap::new();
+    map.insert(1, 1_u64);
+
+    let mut entry = map.get(&1).unwrap();
+    assert!(map.remove(&1));
+
+    assert!(entry.update(|value| value + 1).is_none());
+    assert!(map.get(&1).is_none());
+    assert!(map.is_empty());
+}
+
+#[test]
+fn stale_entry_remove_keeps_newer_insertions() {
+    let map = HazMap::new();
+    map.insert(1, 1_u64);
+
+    let entry = map.get(&1).unwrap();
+    assert!(map.remove(&1));
+    map.insert(1, 2);
+    entry.remove();
+
+    assert_eq!(*map.get(&1).unwrap().get(), 2);
+}
@@ -0,0 +1,39 @@
+use std::thread;
+
+use anchorage::{
+    domain::global::GlobalDomain,
+    hazbox::HazBox,
+    pool::AnchorPool,
+};
+
+#[test]
+fn pool_keeps_at_most_its_capacity() {
+    let pool = AnchorPool::with_capacity_in(3, GlobalDomain);
+
+    let anchors = (0..5).map(|_| pool.get()).collect::<Vec<_>>();
+    assert!(pool.is_empty());
+    drop(anchors);
+    assert_eq!(pool.len(), 3);
+
+    pool.fill(10);
+    assert_eq!(pool.len(), 3);
+    pool.clear();
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn anchors_returned_by_other_threads_are_reused() {
+    let pool = AnchorPool::with_capacity_in(64, GlobalDomain);
+    let src = HazBox::new(1);
+
+    thread::scope(|scope| {
+        scope.spawn(|| pool.fill(4));
+    });
+    assert_eq!(pool.len(), 4);
+
+    let mut anchors = (0..4).map(|_| pool.get()).collect::<Vec<_>>();
+    assert!(pool.is_empty());
+    for anchor in &mut anchors {
+        assert_eq!(*anchor.moor(&src), 1);
+    }
+}
@@ -0,0 +1,64 @@
+#![feature(allocator_api)]
+
+use std::{
+    alloc::Global,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use anchorage::{
+    anchor::Anchor,
+    domain::scoped::ScopedDomain,
+    hazbox::HazBox,
+};
+
+struct Counted(&'static AtomicUsize, u32);
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Counting the drop only touches a static.
+unsafe impl<'dom> anchorage::Hazard<'dom> for Counted {}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn handle_moors_and_retires() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    let domain = ScopedDomain::new_in(Global);
+    // Safety: The values only borrow statics.
+    let handle = unsafe { domain.handle() };
+    let first = HazBox::new_in(Counted(&DROPS, 1), handle);
+    let second = HazBox::new_in(Counted(&DROPS, 2), handle);
+
+    // Keeps the domain from freeing retired values inline.
+    let mut outer = Anchor::new_in(handle);
+    let mut anchor = Anchor::new_in(handle);
+    assert_eq!(outer.moor(&first).1, 1);
+    assert_eq!(anchor.moor(&second).1, 2);
+
+    second.set(Counted(&DROPS, 3));
+    assert_eq!(anchor.moor(&second).1, 3);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+
+    drop((outer, anchor, first, second));
+    assert_eq!(DROPS.load(Ordering::SeqCst), 2);
+    drop(domain);
+    assert_eq!(DROPS.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn constructors() {
+    let new = ScopedDomain::new();
+    let default = ScopedDomain::default();
+    // Safety: Nothing is retired.
+    unsafe {
+        assert!(new.handle() == new.handle());
+        assert!(new.handle() != default.handle());
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Mutex;
+
+use anchorage::{
+    anchor::Anchor,
+    domain::sim::{
+        SimDomain,
+        SimDomainRef,
+        SimEvent,
+    },
+    hazbox::HazBox,
+};
+
+const TASKS: usize = 3;
+
+/// Tasks replacing a shared value after reading it, returning the steps they took and the values
+/// they read, in order.
+fn script(seed: u64) -> (Vec<SimEvent>, Vec<usize>) {
+    let sim = SimDomain::new(seed);
+    // Safety: The values borrow nothing.
+    let domain = unsafe { sim.handle() };
+    let hazbox = HazBox::new_in(0, domain);
+    let reads = Mutex::new(Vec::new());
+
+    let tasks = (0..TASKS)
+        .map(|task| {
+            let (hazbox, reads) = (&hazbox, &reads);
+            move |domain: SimDomainRef| {
+                for i in 1..=10 {
+                    let mut anchor = Anchor::new_in(domain);
+                    domain.yield_now();
+                    reads.lock().unwrap().push(*anchor.moor(hazbox));
+                    hazbox.set(task * 100 + i);
+                }
+            }
+        })
+        .collect();
+    domain.run(tasks);
+
+    drop(hazbox);
+    // Allocation addresses differ between runs, unlike the rest of the trace.
+    let events = sim
+        .events()
+        .into_iter()
+        .map(|event| match event {
+            SimEvent::Retire { .. } => SimEvent::Retire { addr: 0 },
+            event => event,
+        })
+        .collect();
+    (events, reads.into_inner().unwrap())
+}
+
+#[test]
+fn replays_interleaving() {
+    let (events, reads) = script(7);
+    assert_eq!((events.clone(), reads.clone()), script(7));
+
+    let switches = events
+        .iter()
+        .filter(|event| matches!(event, SimEvent::Switch { .. }))
+        .count();
+    assert!(switches > TASKS);
+    // Tasks read values set by each other.
+    assert!(reads.windows(2).any(|pair| pair[0] / 100 != pair[1] / 100));
+}
+
+#[test]
+fn seeds_interleave_differently() {
+    assert_ne!(script(7), script(8));
+}
+
+/// Acquires a [`HazPtr`][anchorage::hazptr::HazPtr] of its domain when dropped.
+struct AcquireOnDrop<'dom>(SimDomainRef<'dom>);
+
+#[cfg(feature = "explicit-hazard")]
+// Safety: Dropping it only uses its domain, which is alive for 'dom.
+unsafe impl<'dom> anchorage::Hazard<'dom> for AcquireOnDrop<'dom> {}
+
+impl Drop for AcquireOnDrop<'_> {
+    fn drop(&mut self) {
+        drop(Anchor::new_in(self.0));
+    }
+}
+
+#[test]
+fn reclaimed_values_may_use_the_domain() {
+    let sim = SimDomain::new(7).reclaim_one_in(0);
+    // Safety: The values only borrow the domain.
+    let domain = unsafe { sim.handle() };
+    let hazbox = HazBox::new_in(AcquireOnDrop(domain), domain);
+
+    hazbox.set(AcquireOnDrop(domain));
+    assert_eq!(sim.reclaim(), 1);
+}
@@ -0,0 +1,127 @@
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use anchorage::{
+    anchor::Anchor,
+    domain::{
+        global::GlobalDomain,
+        local::ThreadLocalDomain,
+        scoped::ScopedDomain,
+        sim::SimDomain,
+        Domain,
+    },
+    hazbox::HazBox,
+    testkit::{
+        self,
+        ops::{
+            Model,
+            Op,
+            Outcome,
+        },
+        soak::Soak,
+    },
+};
+
+/// Pseudo random operations covering every kind of [`Op`].
+fn ops(count: usize) -> impl Iterator<Item = Op> {
+    let mut rng = 0x9e37_79b9_7f4a_7c15_u64;
+    (0..count).map(move |_| {
+        rng ^= rng >> 12;
+        rng ^= rng << 25;
+        rng ^= rng >> 27;
+        let bits = rng.wrapping_mul(0x2545_f491_4f6c_dd1d);
+        let (anchor, hazbox) = ((bits >> 8) as u8, (bits >> 16) as u8);
+        match bits % 5 {
+            0 => Op::Set { hazbox },
+            1 => Op::Moor { anchor, hazbox },
+            2 => Op::Reset { anchor },
+            3 => Op::Release { anchor },
+            _ => Op::Read { anchor },
+        }
+    })
+}
+
+/// Runs the conformance checks, the operation model and a short soak against `domain`, returning
+/// the outcome of the model to be checked for leaks once the domain is drained.
+fn exercise<D>(domain: D) -> Outcome
+where
+    D: Domain<'static> + Send + Sync,
+{
+    testkit::assert_domain_conformance_in(domain);
+
+    let mut model = Model::new_in(domain);
+    model.run(ops(10_000));
+    let outcome = model.finish();
+    assert!(outcome.created() > 0);
+
+    let hazbox = Arc::new(HazBox::new_in(0_usize, domain));
+    let report = Soak::new(domain)
+        .duration(Duration::from_millis(100))
+        .op(1, {
+            let hazbox = hazbox.clone();
+            move |_| hazbox.set(1)
+        })
+        .op(3, {
+            let hazbox = hazbox.clone();
+            move |domain| assert!(*Anchor::new_in(domain).moor(&hazbox) <= 1)
+        })
+        .run();
+    assert!(report.ops.iter().all(|&count| count > 0));
+    assert!(report.verifications > 0);
+
+    testkit::assert_no_guards(domain);
+    outcome
+}
+
+/// Same as [`exercise`], for domains that reclaim everything on [`Domain::eager_reclaim`].
+fn exercise_drained<D>(domain: D)
+where
+    D: Domain<'static> + Send + Sync,
+{
+    let outcome = exercise(domain);
+    domain.eager_reclaim();
+    assert_eq!(outcome.leaked(), 0);
+}
+
+#[test]
+fn global_domain() {
+    exercise_drained(GlobalDomain);
+}
+
+#[test]
+fn thread_local_domain() {
+    exercise_drained(ThreadLocalDomain);
+}
+
+#[test]
+fn scoped_domain() {
+    // Only reclaims once quiescent or dropped, thus it is leaked while in use and dropped after.
+    let domain = Box::into_raw(Box::new(ScopedDomain::new()));
+    // Safety: Everything using the handle is dropped before the domain, and the values retired to
+    // it borrow nothing.
+    let outcome = exercise(unsafe { (*domain).handle() });
+    drop(unsafe { Box::from_raw(domain) });
+    assert_eq!(outcome.leaked(), 0);
+}
+
+#[test]
+fn sim_domain() {
+    let domain = Box::leak(Box::new(SimDomain::new(7)));
+    // Safety: The domain is never dropped.
+    exercise_drained(unsafe { domain.handle() });
+}
+
+#[cfg(feature = "watchdog")]
+#[test]
+fn bounded_domain() {
+    use anchorage::domain::bounded::{
+        BoundedDomain,
+        Bounds,
+        Enforcement,
+    };
+
+    static BOUNDS: Bounds = Bounds::new(Duration::from_secs(60), Enforcement::Abort);
+    exercise_drained(BoundedDomain::new(GlobalDomain, &BOUNDS));
+}